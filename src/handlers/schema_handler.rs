@@ -2,20 +2,56 @@
 // 提供查询数据库结构的 HTTP 端点
 
 use crate::models::response::ApiResponse;
-use crate::schema::{self, SchemaCache};
+use crate::schema::{
+    self, erd::ErdFormat, json_schema::SchemaVariant, snapshot::SchemaSnapshot, stats::StatsSort,
+    SchemaCache,
+};
 use actix_web::{get, web, HttpResponse, Result};
+use serde::Deserialize;
 use serde_json::json;
 use sqlx::PgPool;
 
-/// 获取所有表名
+/// 通用的 schema 查询参数
+///
+/// 所有 schema 相关端点都支持 `?schema=<name>`，默认为 "public"
+#[derive(Debug, Deserialize)]
+pub struct SchemaQuery {
+    /// Schema 名称
+    pub schema: Option<String>,
+}
+
+/// 列出所有非系统 schema
+///
+/// GET /schema/schemas
+#[get("/schema/schemas")]
+pub async fn get_schemas(pool: web::Data<PgPool>) -> Result<HttpResponse> {
+    match schema::inspector::get_schemas(pool.get_ref()).await {
+        Ok(schemas) => Ok(HttpResponse::Ok().json(ApiResponse::success(json!({
+            "schemas": schemas,
+            "count": schemas.len(),
+        })))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::error(&format!(
+            "Failed to fetch schemas: {}",
+            e
+        )))),
+    }
+}
+
+/// 获取所有表名（包含视图、物化视图和外部表）
+///
+/// 分区不单独列出，而是列在所属分区表的 `partitions` 中
 ///
 /// GET /schema/tables
 #[get("/schema/tables")]
-pub async fn get_tables(pool: web::Data<PgPool>) -> Result<HttpResponse> {
-    match schema::get_all_tables(pool.get_ref(), None).await {
-        Ok(tables) => Ok(HttpResponse::Ok().json(ApiResponse::success(json!({
-            "tables": tables,
-            "count": tables.len(),
+pub async fn get_tables(
+    pool: web::Data<PgPool>,
+    query: web::Query<SchemaQuery>,
+) -> Result<HttpResponse> {
+    match schema::get_relations(pool.get_ref(), query.schema.as_deref()).await {
+        Ok(relations) => Ok(HttpResponse::Ok().json(ApiResponse::success(json!({
+            "tables": relations.iter().map(|r| r.name.as_str()).collect::<Vec<_>>(),
+            "count": relations.len(),
+            "relations": relations,
         })))),
         Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::error(&format!(
             "Failed to fetch tables: {}",
@@ -31,10 +67,11 @@ pub async fn get_tables(pool: web::Data<PgPool>) -> Result<HttpResponse> {
 pub async fn get_table_info(
     pool: web::Data<PgPool>,
     path: web::Path<String>,
+    query: web::Query<SchemaQuery>,
 ) -> Result<HttpResponse> {
     let table_name = path.into_inner();
 
-    match schema::get_table_schema(pool.get_ref(), &table_name, None).await {
+    match schema::get_table_schema(pool.get_ref(), &table_name, query.schema.as_deref()).await {
         Ok(schema) => Ok(HttpResponse::Ok().json(ApiResponse::success(schema))),
         Err(e) => Ok(HttpResponse::NotFound().json(ApiResponse::error(&format!(
             "Table '{}' not found: {}",
@@ -43,12 +80,124 @@ pub async fn get_table_info(
     }
 }
 
+/// 获取指定表的行级安全状态和策略
+///
+/// GET /schema/tables/{table_name}/policies
+#[get("/schema/tables/{table_name}/policies")]
+pub async fn get_table_policies(
+    pool: web::Data<PgPool>,
+    path: web::Path<String>,
+    query: web::Query<SchemaQuery>,
+) -> Result<HttpResponse> {
+    let table_name = path.into_inner();
+
+    match schema::get_table_schema(pool.get_ref(), &table_name, query.schema.as_deref()).await {
+        Ok(schema) => Ok(HttpResponse::Ok().json(ApiResponse::success(json!({
+            "table": schema.name,
+            "rls_enabled": schema.row_security.enabled,
+            "rls_forced": schema.row_security.forced,
+            "policies": schema.row_security.policies,
+        })))),
+        Err(e) => Ok(HttpResponse::NotFound().json(ApiResponse::error(&format!(
+            "Table '{}' not found: {}",
+            table_name, e
+        )))),
+    }
+}
+
+/// JSON Schema 查询参数
+#[derive(Debug, Deserialize)]
+pub struct JsonSchemaQuery {
+    /// Schema 名称，默认为 "public"
+    pub schema: Option<String>,
+    /// 场景: read、insert、update，不指定时返回全部三种
+    pub variant: Option<String>,
+}
+
+/// 获取指定表的 JSON Schema (draft 2020-12)
+///
+/// GET /schema/tables/{table_name}/jsonschema?variant=insert
+#[get("/schema/tables/{table_name}/jsonschema")]
+pub async fn get_table_json_schema(
+    pool: web::Data<PgPool>,
+    path: web::Path<String>,
+    query: web::Query<JsonSchemaQuery>,
+) -> Result<HttpResponse> {
+    let table_name = path.into_inner();
+
+    let variant = match query.variant.as_deref() {
+        None => None,
+        Some(value) => match SchemaVariant::parse(value) {
+            Some(variant) => Some(variant),
+            None => {
+                return Ok(HttpResponse::BadRequest().json(ApiResponse::error(&format!(
+                    "Invalid variant '{}': expected read, insert or update",
+                    value
+                ))))
+            }
+        },
+    };
+
+    match schema::get_table_schema(pool.get_ref(), &table_name, query.schema.as_deref()).await {
+        Ok(table) => {
+            let document = match variant {
+                Some(variant) => schema::json_schema::table_json_schema(&table, variant),
+                None => SchemaVariant::ALL
+                    .iter()
+                    .map(|variant| {
+                        (
+                            variant.as_str().to_string(),
+                            schema::json_schema::table_json_schema(&table, *variant),
+                        )
+                    })
+                    .collect::<serde_json::Map<_, _>>()
+                    .into(),
+            };
+            Ok(HttpResponse::Ok().json(ApiResponse::success(document)))
+        }
+        Err(e) => Ok(HttpResponse::NotFound().json(ApiResponse::error(&format!(
+            "Table '{}' not found: {}",
+            table_name, e
+        )))),
+    }
+}
+
+/// Schema 概览查询参数
+#[derive(Debug, Deserialize)]
+pub struct OverviewQuery {
+    /// Schema 名称，默认为 "public"
+    pub schema: Option<String>,
+    /// 是否包含每个表的完整结构
+    pub detailed: Option<bool>,
+    /// 是否包含每个表的统计信息和存储大小
+    pub stats: Option<bool>,
+}
+
 /// 获取 Schema 概览
 ///
-/// GET /schema/overview
+/// GET /schema/overview?schema=public&detailed=true&stats=true
 #[get("/schema/overview")]
-pub async fn get_schema_overview(pool: web::Data<PgPool>) -> Result<HttpResponse> {
-    match schema::get_schema_overview(pool.get_ref(), None).await {
+pub async fn get_schema_overview(
+    pool: web::Data<PgPool>,
+    query: web::Query<OverviewQuery>,
+) -> Result<HttpResponse> {
+    let schema_name = query.schema.as_deref();
+    let mut result = if query.detailed.unwrap_or(false) {
+        schema::inspector::get_schema_overview_detailed(pool.get_ref(), schema_name).await
+    } else {
+        schema::get_schema_overview(pool.get_ref(), schema_name).await
+    };
+
+    if query.stats.unwrap_or(false) {
+        if let Ok(overview) = &mut result {
+            match schema::stats::get_all_table_stats(pool.get_ref(), schema_name).await {
+                Ok(stats) => overview.table_stats = stats,
+                Err(e) => result = Err(e),
+            }
+        }
+    }
+
+    match result {
         Ok(overview) => Ok(HttpResponse::Ok().json(ApiResponse::success(overview))),
         Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::error(&format!(
             "Failed to fetch schema overview: {}",
@@ -57,6 +206,364 @@ pub async fn get_schema_overview(pool: web::Data<PgPool>) -> Result<HttpResponse
     }
 }
 
+/// 表统计信息查询参数
+#[derive(Debug, Deserialize)]
+pub struct StatsQuery {
+    /// Schema 名称，默认为 "public"
+    pub schema: Option<String>,
+    /// 排序方式: size（默认）、rows、dead_tuples、seq_scans、name
+    pub sort: Option<String>,
+}
+
+/// 获取所有表的统计信息和存储大小
+///
+/// GET /schema/stats?schema=public&sort=size
+#[get("/schema/stats")]
+pub async fn get_table_stats(
+    pool: web::Data<PgPool>,
+    query: web::Query<StatsQuery>,
+) -> Result<HttpResponse> {
+    let sort = match query.sort.as_deref() {
+        None => StatsSort::Size,
+        Some(value) => match StatsSort::parse(value) {
+            Some(sort) => sort,
+            None => {
+                return Ok(HttpResponse::BadRequest().json(ApiResponse::error(&format!(
+                    "Invalid sort '{}': expected size, rows, dead_tuples, seq_scans or name",
+                    value
+                ))))
+            }
+        },
+    };
+
+    match schema::stats::get_all_table_stats(pool.get_ref(), query.schema.as_deref()).await {
+        Ok(mut stats) => {
+            schema::stats::sort_stats(&mut stats, sort);
+            let total_bytes: i64 = stats.iter().map(|s| s.total_bytes).sum();
+            Ok(HttpResponse::Ok().json(ApiResponse::success(json!({
+                "tables": stats,
+                "count": stats.len(),
+                "total_bytes": total_bytes,
+                "needs_vacuum": stats
+                    .iter()
+                    .filter(|s| s.needs_vacuum())
+                    .map(|s| s.name.as_str())
+                    .collect::<Vec<_>>(),
+            }))))
+        }
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::error(&format!(
+            "Failed to fetch table statistics: {}",
+            e
+        )))),
+    }
+}
+
+/// 获取指定表的统计信息和存储大小
+///
+/// GET /schema/tables/{table_name}/stats
+#[get("/schema/tables/{table_name}/stats")]
+pub async fn get_table_stats_info(
+    pool: web::Data<PgPool>,
+    path: web::Path<String>,
+    query: web::Query<SchemaQuery>,
+) -> Result<HttpResponse> {
+    let table_name = path.into_inner();
+
+    match schema::stats::get_table_stats(pool.get_ref(), &table_name, query.schema.as_deref())
+        .await
+    {
+        Ok(stats) => Ok(HttpResponse::Ok().json(ApiResponse::success(stats))),
+        Err(e) => Ok(HttpResponse::NotFound().json(ApiResponse::error(&format!(
+            "Table '{}' not found: {}",
+            table_name, e
+        )))),
+    }
+}
+
+/// 获取整个 schema 的表关系图
+///
+/// GET /schema/relationships
+#[get("/schema/relationships")]
+pub async fn get_relationships(
+    pool: web::Data<PgPool>,
+    query: web::Query<SchemaQuery>,
+) -> Result<HttpResponse> {
+    match schema::relationships::get_relationship_graph(pool.get_ref(), query.schema.as_deref())
+        .await
+    {
+        Ok(graph) => Ok(HttpResponse::Ok().json(ApiResponse::success(graph))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::error(&format!(
+            "Failed to build relationship graph: {}",
+            e
+        )))),
+    }
+}
+
+/// 获取指定表的关系
+///
+/// GET /schema/relationships/{table_name}
+#[get("/schema/relationships/{table_name}")]
+pub async fn get_table_relationships(
+    pool: web::Data<PgPool>,
+    path: web::Path<String>,
+    query: web::Query<SchemaQuery>,
+) -> Result<HttpResponse> {
+    let table_name = path.into_inner();
+
+    match schema::relationships::get_relationship_graph(pool.get_ref(), query.schema.as_deref())
+        .await
+    {
+        Ok(graph) => match graph.get_table(&table_name) {
+            Some(table) => Ok(HttpResponse::Ok().json(ApiResponse::success(table))),
+            None => Ok(HttpResponse::NotFound().json(ApiResponse::error(&format!(
+                "Table '{}' not found",
+                table_name
+            )))),
+        },
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::error(&format!(
+            "Failed to build relationship graph: {}",
+            e
+        )))),
+    }
+}
+
+/// 获取所有用户定义类型（枚举、域、复合类型）
+///
+/// GET /schema/types
+#[get("/schema/types")]
+pub async fn get_user_types(
+    pool: web::Data<PgPool>,
+    query: web::Query<SchemaQuery>,
+) -> Result<HttpResponse> {
+    match schema::inspector::get_user_types(pool.get_ref(), query.schema.as_deref()).await {
+        Ok(types) => Ok(HttpResponse::Ok().json(ApiResponse::success(json!({
+            "types": types,
+            "count": types.len(),
+        })))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::error(&format!(
+            "Failed to fetch user-defined types: {}",
+            e
+        )))),
+    }
+}
+
+/// 获取所有序列（当前值、步长、所属列）
+///
+/// GET /schema/sequences
+#[get("/schema/sequences")]
+pub async fn get_sequences(
+    pool: web::Data<PgPool>,
+    query: web::Query<SchemaQuery>,
+) -> Result<HttpResponse> {
+    match schema::inspector::get_sequences(pool.get_ref(), query.schema.as_deref()).await {
+        Ok(sequences) => Ok(HttpResponse::Ok().json(ApiResponse::success(json!({
+            "sequences": sequences,
+            "count": sequences.len(),
+        })))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::error(&format!(
+            "Failed to fetch sequences: {}",
+            e
+        )))),
+    }
+}
+
+/// 获取已安装的扩展及其版本
+///
+/// GET /schema/extensions
+#[get("/schema/extensions")]
+pub async fn get_extensions(pool: web::Data<PgPool>) -> Result<HttpResponse> {
+    match schema::inspector::get_extensions(pool.get_ref()).await {
+        Ok(extensions) => Ok(HttpResponse::Ok().json(ApiResponse::success(json!({
+            "extensions": extensions,
+            "count": extensions.len(),
+            "updates_available": extensions
+                .iter()
+                .filter(|e| e.has_update())
+                .map(|e| e.name.as_str())
+                .collect::<Vec<_>>(),
+        })))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::error(&format!(
+            "Failed to fetch extensions: {}",
+            e
+        )))),
+    }
+}
+
+/// 生成 TypeScript 类型定义
+///
+/// GET /schema/types.ts
+///
+/// 包含所有表和视图的 Row/Insert/Update 类型、枚举、复合类型、函数和外键关系
+#[get("/schema/types.ts")]
+pub async fn get_typescript_types(
+    pool: web::Data<PgPool>,
+    query: web::Query<SchemaQuery>,
+) -> Result<HttpResponse> {
+    let schema_name = query.schema.as_deref().unwrap_or("public");
+    let pool = pool.get_ref();
+
+    let loaded = tokio::try_join!(
+        schema::inspector::get_all_table_schemas(pool, Some(schema_name)),
+        schema::inspector::get_functions(pool, Some(schema_name)),
+        schema::inspector::get_user_types(pool, Some(schema_name)),
+    );
+
+    match loaded {
+        Ok((tables, functions, user_types)) => Ok(HttpResponse::Ok()
+            .content_type("text/plain; charset=utf-8")
+            .body(schema::typescript::generate_typescript(
+                schema_name,
+                &tables,
+                &functions,
+                &user_types,
+            ))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::error(&format!(
+            "Failed to generate TypeScript types: {}",
+            e
+        )))),
+    }
+}
+
+/// 实体关系图查询参数
+#[derive(Debug, Deserialize)]
+pub struct ErdQuery {
+    /// Schema 名称，默认为 "public"
+    pub schema: Option<String>,
+    /// 输出格式: mermaid（默认）、dot
+    pub format: Option<String>,
+    /// 逗号分隔的表名，不指定时导出所有表
+    pub tables: Option<String>,
+    /// 从指定的表沿外键扩展的跳数，默认为 1
+    pub hops: Option<usize>,
+}
+
+/// 获取实体关系图
+///
+/// GET /schema/erd?format=mermaid&tables=users,posts&hops=1
+#[get("/schema/erd")]
+pub async fn get_erd(
+    pool: web::Data<PgPool>,
+    query: web::Query<ErdQuery>,
+) -> Result<HttpResponse> {
+    let format = match query.format.as_deref() {
+        None => ErdFormat::Mermaid,
+        Some(value) => match ErdFormat::parse(value) {
+            Some(format) => format,
+            None => {
+                return Ok(HttpResponse::BadRequest().json(ApiResponse::error(&format!(
+                    "Invalid format '{}': expected mermaid or dot",
+                    value
+                ))))
+            }
+        },
+    };
+    let roots: Vec<String> = query
+        .tables
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+        .collect();
+
+    let tables = match schema::inspector::get_all_table_schemas(
+        pool.get_ref(),
+        Some(query.schema.as_deref().unwrap_or("public")),
+    )
+    .await
+    {
+        Ok(tables) => tables,
+        Err(e) => {
+            return Ok(HttpResponse::InternalServerError().json(ApiResponse::error(&format!(
+                "Failed to generate ER diagram: {}",
+                e
+            ))))
+        }
+    };
+
+    match schema::erd::select_tables(&tables, &roots, query.hops.unwrap_or(1)) {
+        Ok(selected) => Ok(HttpResponse::Ok()
+            .content_type(format.content_type())
+            .body(schema::erd::render_erd(&selected, format))),
+        Err(e) => Ok(HttpResponse::NotFound().json(ApiResponse::error(&e.to_string()))),
+    }
+}
+
+/// 获取所有函数和存储过程
+///
+/// GET /schema/functions
+#[get("/schema/functions")]
+pub async fn get_functions(
+    pool: web::Data<PgPool>,
+    query: web::Query<SchemaQuery>,
+) -> Result<HttpResponse> {
+    match schema::inspector::get_functions(pool.get_ref(), query.schema.as_deref()).await {
+        Ok(functions) => Ok(HttpResponse::Ok().json(ApiResponse::success(json!({
+            "functions": functions,
+            "count": functions.len(),
+        })))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::error(&format!(
+            "Failed to fetch functions: {}",
+            e
+        )))),
+    }
+}
+
+/// 获取指定函数的信息（包含所有重载）
+///
+/// GET /schema/functions/{function_name}
+#[get("/schema/functions/{function_name}")]
+pub async fn get_function_info(
+    pool: web::Data<PgPool>,
+    path: web::Path<String>,
+    query: web::Query<SchemaQuery>,
+) -> Result<HttpResponse> {
+    let function_name = path.into_inner();
+
+    let schema_name = query.schema.as_deref();
+
+    match schema::inspector::get_function(pool.get_ref(), &function_name, schema_name).await {
+        Ok(functions) if !functions.is_empty() => {
+            Ok(HttpResponse::Ok().json(ApiResponse::success(functions)))
+        }
+        Ok(_) => Ok(HttpResponse::NotFound().json(ApiResponse::error(&format!(
+            "Function '{}' not found",
+            function_name
+        )))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::error(&format!(
+            "Failed to fetch function '{}': {}",
+            function_name, e
+        )))),
+    }
+}
+
+/// 使用缓存获取函数信息
+///
+/// GET /schema/cached/functions/{function_name}
+#[get("/schema/cached/functions/{function_name}")]
+pub async fn get_cached_function_info(
+    cache: web::Data<SchemaCache>,
+    path: web::Path<String>,
+    query: web::Query<SchemaQuery>,
+) -> Result<HttpResponse> {
+    let function_name = path.into_inner();
+
+    match cache.get_function(&function_name, query.schema.as_deref()).await {
+        Ok(functions) if !functions.is_empty() => {
+            Ok(HttpResponse::Ok().json(ApiResponse::success(functions)))
+        }
+        Ok(_) => Ok(HttpResponse::NotFound().json(ApiResponse::error(&format!(
+            "Function '{}' not found",
+            function_name
+        )))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::error(&format!(
+            "Failed to fetch function '{}': {}",
+            function_name, e
+        )))),
+    }
+}
+
 /// 使用缓存获取表结构信息
 ///
 /// GET /schema/cached/tables/{table_name}
@@ -64,10 +571,11 @@ pub async fn get_schema_overview(pool: web::Data<PgPool>) -> Result<HttpResponse
 pub async fn get_cached_table_info(
     cache: web::Data<SchemaCache>,
     path: web::Path<String>,
+    query: web::Query<SchemaQuery>,
 ) -> Result<HttpResponse> {
     let table_name = path.into_inner();
 
-    match cache.get_table_schema(&table_name, None).await {
+    match cache.get_table_schema(&table_name, query.schema.as_deref()).await {
         Ok(schema) => Ok(HttpResponse::Ok().json(ApiResponse::success(schema))),
         Err(e) => Ok(HttpResponse::NotFound().json(ApiResponse::error(&format!(
             "Table '{}' not found: {}",
@@ -94,12 +602,29 @@ pub async fn clear_cache(cache: web::Data<SchemaCache>) -> Result<HttpResponse>
     Ok(HttpResponse::Ok().json(ApiResponse::success("Cache cleared")))
 }
 
+/// 使指定表的缓存失效
+///
+/// POST /schema/cache/invalidate/{table_name}
+#[actix_web::post("/schema/cache/invalidate/{table_name}")]
+pub async fn invalidate_cache(
+    cache: web::Data<SchemaCache>,
+    path: web::Path<String>,
+    query: web::Query<SchemaQuery>,
+) -> Result<HttpResponse> {
+    let table_name = path.into_inner();
+    cache.invalidate(&table_name, query.schema.as_deref()).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success("Cache invalidated")))
+}
+
 /// 预加载所有表的 schema 到缓存
 ///
 /// POST /schema/cache/preload
 #[actix_web::post("/schema/cache/preload")]
-pub async fn preload_cache(cache: web::Data<SchemaCache>) -> Result<HttpResponse> {
-    match cache.preload(None).await {
+pub async fn preload_cache(
+    cache: web::Data<SchemaCache>,
+    query: web::Query<SchemaQuery>,
+) -> Result<HttpResponse> {
+    match cache.preload(query.schema.as_deref()).await {
         Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::success("Cache preloaded"))),
         Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::error(&format!(
             "Failed to preload cache: {}",
@@ -107,3 +632,124 @@ pub async fn preload_cache(cache: web::Data<SchemaCache>) -> Result<HttpResponse
         )))),
     }
 }
+
+/// 获取 OpenAPI 3.1 文档
+///
+/// GET /schema/openapi.json
+///
+/// 文档由 Schema 缓存生成，缓存刷新后会自动重新生成
+#[get("/schema/openapi.json")]
+pub async fn get_openapi_document(
+    cache: web::Data<SchemaCache>,
+    query: web::Query<SchemaQuery>,
+) -> Result<HttpResponse> {
+    match cache.openapi_document(query.schema.as_deref()).await {
+        Ok(document) => Ok(HttpResponse::Ok().json(document)),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::error(&format!(
+            "Failed to generate OpenAPI document: {}",
+            e
+        )))),
+    }
+}
+
+/// 快照下载查询参数
+#[derive(Debug, Deserialize)]
+pub struct SnapshotQuery {
+    /// 逗号分隔的 schema 名称，默认为 "public"
+    pub schemas: Option<String>,
+}
+
+/// 下载 schema 快照
+///
+/// GET /schema/snapshot?schemas=public,app
+///
+/// 返回带版本哈希的 JSON 快照文件，可通过 `serve --snapshot` 或 `codegen --snapshot` 加载
+#[get("/schema/snapshot")]
+pub async fn get_schema_snapshot(
+    cache: web::Data<SchemaCache>,
+    query: web::Query<SnapshotQuery>,
+) -> Result<HttpResponse> {
+    let schemas: Vec<String> = query
+        .schemas
+        .as_deref()
+        .unwrap_or("public")
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+        .collect();
+
+    match cache.snapshot(&schemas).await {
+        Ok(snapshot) => Ok(HttpResponse::Ok()
+            .insert_header(("ETag", format!("\"{}\"", snapshot.version)))
+            .insert_header((
+                "Content-Disposition",
+                "attachment; filename=\"schema-snapshot.json\"",
+            ))
+            .json(snapshot)),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::error(&format!(
+            "Failed to create schema snapshot: {}",
+            e
+        )))),
+    }
+}
+
+/// 差异比较的基准
+///
+/// 只接受快照：按请求中的连接串连接数据库会让未认证的调用方探测内网主机，
+/// 两个数据库之间的比较请使用 `orpheus diff` 子命令
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffBase {
+    /// `GET /schema/snapshot` 或 `orpheus snapshot` 生成的快照
+    Snapshot(SchemaSnapshot),
+}
+
+/// 差异比较请求
+#[derive(Debug, Deserialize)]
+pub struct DiffRequest {
+    /// Schema 名称，默认为 "public"
+    pub schema: Option<String>,
+    /// 基准（差异方向为 基准 -> 当前数据库）
+    pub base: DiffBase,
+    /// 是否生成迁移 SQL
+    #[serde(default)]
+    pub generate_sql: bool,
+}
+
+/// 比较基准与当前数据库的 schema 差异
+///
+/// POST /schema/diff
+///
+/// 请求体: `{"base": {"snapshot": {"format_version": 1, "version": "...", "schemas": {...}}}}`
+#[actix_web::post("/schema/diff")]
+pub async fn diff_schema(
+    pool: web::Data<PgPool>,
+    body: web::Json<DiffRequest>,
+) -> Result<HttpResponse> {
+    let request = body.into_inner();
+    let schema_name = request.schema.as_deref();
+
+    let result = match &request.base {
+        DiffBase::Snapshot(snapshot) => {
+            if let Err(e) = snapshot.verify() {
+                return Ok(HttpResponse::BadRequest()
+                    .json(ApiResponse::error(&format!("Invalid snapshot: {}", e))));
+            }
+            schema::diff::diff_against_snapshot(pool.get_ref(), schema_name, snapshot).await
+        }
+    };
+
+    match result {
+        Ok(diff) => {
+            let migration_sql = request.generate_sql.then(|| diff.to_migration_sql());
+            Ok(HttpResponse::Ok().json(ApiResponse::success(json!({
+                "identical": diff.is_empty(),
+                "diff": diff,
+                "migration_sql": migration_sql,
+            }))))
+        }
+        Err(e) => Ok(HttpResponse::InternalServerError()
+            .json(ApiResponse::error(&format!("Failed to diff schema: {}", e)))),
+    }
+}
@@ -29,33 +29,317 @@ mod handlers;  // 临时保留 GitHub handler 作为 API 示例
 
 use crate::handlers::github_handler::get_github_repo_stars;
 use crate::handlers::schema_handler;
-use crate::schema::SchemaCache;
-use actix_web::{web, App, HttpServer};
+use crate::schema::{
+    cache::CacheConfig,
+    listener, rust_codegen,
+    shared::{self, SharedCache},
+    snapshot::SchemaSnapshot,
+    SchemaCache,
+};
+use crate::models::response::ApiResponse;
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::{from_fn, Next};
+use actix_web::{web, App, HttpResponse, HttpServer};
+use clap::{Args, Parser, Subcommand};
 use dotenvy::dotenv;
+use sqlx::postgres::PgPoolOptions;
 use sqlx::{Pool, Postgres};
 use std::env;
+use std::path::{Path, PathBuf};
+
+/// 命令行参数
+#[derive(Debug, Parser)]
+#[command(name = "orpheus", version, about = "Orpheus BaaS Platform")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// 子命令
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// 启动 HTTP 服务器（默认）
+    Serve(ServeArgs),
+    /// 根据数据库表结构生成 Rust 模型代码（serde + sqlx::FromRow）
+    Codegen {
+        /// 输出目录（不存在时自动创建）
+        #[arg(short, long, default_value = "src/generated")]
+        out: PathBuf,
+        /// Schema 名称
+        #[arg(short, long, default_value = "public")]
+        schema: String,
+        /// 只生成指定的表，可重复指定（默认生成所有表和视图）
+        #[arg(short, long = "table")]
+        tables: Vec<String>,
+        /// 从快照文件读取表结构（不连接数据库）
+        #[arg(long)]
+        snapshot: Option<PathBuf>,
+    },
+    /// 将 schema 结构导出为快照文件
+    Snapshot {
+        /// 输出文件
+        #[arg(short, long, default_value = "schema-snapshot.json")]
+        out: PathBuf,
+        /// Schema 名称，可重复指定
+        #[arg(short, long = "schema", default_value = "public")]
+        schemas: Vec<String>,
+    },
+    /// 比较另一个数据库或快照文件与 DATABASE_URL 的 schema 差异（方向为 基准 -> 当前数据库）
+    Diff {
+        /// 基准数据库的连接串
+        #[arg(long, required_unless_present = "base_snapshot", conflicts_with = "base_snapshot")]
+        base: Option<String>,
+        /// 基准快照文件（`orpheus snapshot` 生成）
+        #[arg(long)]
+        base_snapshot: Option<PathBuf>,
+        /// Schema 名称
+        #[arg(short, long, default_value = "public")]
+        schema: String,
+        /// 输出迁移 SQL 而不是 JSON 差异
+        #[arg(long)]
+        sql: bool,
+    },
+}
+
+/// `serve` 子命令的参数
+#[derive(Debug, Default, Args)]
+struct ServeArgs {
+    /// 启动时从快照文件预热 Schema 缓存
+    #[arg(long)]
+    snapshot: Option<PathBuf>,
+    /// 离线模式：不连接数据库，Schema 缓存只从快照读取
+    #[arg(long, requires = "snapshot")]
+    offline: bool,
+    /// 监听 DDL 变更通知，使受影响的 Schema 缓存失效
+    #[arg(long, conflicts_with = "offline")]
+    watch_ddl: bool,
+    /// 启动时安装 DDL event trigger（需要超级用户权限，隐含 --watch-ddl）
+    #[arg(long, conflicts_with = "offline")]
+    install_ddl_trigger: bool,
+    /// 使用 Redis 作为多实例共享的 Schema 缓存（默认只使用进程内缓存）
+    #[arg(long, conflicts_with = "offline")]
+    shared_cache: bool,
+    /// Redis 共享缓存的键前缀（默认为 orpheus:schema），连接不同数据库的实例应使用不同的前缀
+    #[arg(long, requires = "shared_cache")]
+    cache_namespace: Option<String>,
+}
 
 /// Orpheus BaaS 平台主入口
 ///
-/// 初始化：
-/// - PostgreSQL 数据库连接池
-/// - Redis 连接（用于缓存和会话）
-/// - HTTP 服务器
+/// 执行子命令（默认启动 HTTP 服务器），需要数据库的子命令连接 DATABASE_URL
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    let command = cli.command.unwrap_or(Command::Serve(ServeArgs::default()));
+
     // 加载环境变量
     dotenv().ok();
 
-    // 数据库连接
+    match command {
+        Command::Serve(args) => {
+            // 离线模式下不创建连接池
+            let pool = if args.offline {
+                None
+            } else {
+                Some(connect_database().await?)
+            };
+            serve(pool, &args).await
+        }
+        Command::Codegen {
+            out,
+            schema,
+            tables,
+            snapshot,
+        } => codegen(&out, &schema, &tables, snapshot.as_deref()).await,
+        Command::Snapshot { out, schemas } => {
+            export_snapshot(&connect_database().await?, &out, &schemas).await
+        }
+        Command::Diff {
+            base,
+            base_snapshot,
+            schema,
+            sql,
+        } => {
+            diff(
+                &connect_database().await?,
+                base.as_deref(),
+                base_snapshot.as_deref(),
+                &schema,
+                sql,
+            )
+            .await
+        }
+    }
+}
+
+/// 连接 DATABASE_URL 指定的数据库
+async fn connect_database() -> anyhow::Result<Pool<Postgres>> {
     let database_url: String = env::var("DATABASE_URL")?;
-    let pool: Pool<Postgres> = Pool::<Postgres>::connect(&database_url).await?;
+    Ok(Pool::<Postgres>::connect(&database_url).await?)
+}
+
+/// 比较基准数据库（或快照文件）与当前数据库的 schema 差异并输出到标准输出
+async fn diff(
+    pool: &Pool<Postgres>,
+    base_url: Option<&str>,
+    base_snapshot: Option<&Path>,
+    schema_name: &str,
+    sql: bool,
+) -> anyhow::Result<()> {
+    let diff = match (base_snapshot, base_url) {
+        (Some(path), _) => {
+            let snapshot = SchemaSnapshot::read_from_file(path)?;
+            schema::diff::diff_against_snapshot(pool, Some(schema_name), &snapshot).await?
+        }
+        (None, Some(base_url)) => {
+            let base_pool = PgPoolOptions::new()
+                .max_connections(1)
+                .connect(base_url)
+                .await?;
+            let result = schema::diff::diff_databases(&base_pool, pool, Some(schema_name)).await;
+            base_pool.close().await;
+            result?
+        }
+        (None, None) => anyhow::bail!("Either --base or --base-snapshot is required"),
+    };
+
+    if sql {
+        print!("{}", diff.to_migration_sql());
+    } else {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+    }
 
-    // Redis 连接
-    let redis_url: String = env::var("REDIS_URL")?;
-    let client = redis::Client::open(redis_url)?;
+    Ok(())
+}
 
+/// 导出 schema 快照文件
+async fn export_snapshot(
+    pool: &Pool<Postgres>,
+    out: &Path,
+    schemas: &[String],
+) -> anyhow::Result<()> {
+    let snapshot = schema::snapshot::capture(pool, schemas).await?;
+    snapshot.write_to_file(out)?;
+
+    println!(
+        "📦 已导出 {} 个表的快照到 {}（版本: {}）",
+        snapshot.table_count(),
+        out.display(),
+        snapshot.version
+    );
+
+    Ok(())
+}
+
+/// 生成 Rust 模型代码并写入输出目录
+///
+/// 指定快照文件时从快照读取表结构，否则从数据库读取
+async fn codegen(
+    out: &Path,
+    schema_name: &str,
+    tables: &[String],
+    snapshot: Option<&Path>,
+) -> anyhow::Result<()> {
+    let mut table_schemas = match snapshot {
+        Some(path) => SchemaSnapshot::read_from_file(path)?
+            .tables(schema_name)
+            .map(<[_]>::to_vec)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Schema '{}' not found in snapshot {}",
+                    schema_name,
+                    path.display()
+                )
+            })?,
+        None => {
+            let pool = connect_database().await?;
+            schema::get_all_table_schemas(&pool, Some(schema_name)).await?
+        }
+    };
+    if !tables.is_empty() {
+        if let Some(missing) = tables
+            .iter()
+            .find(|name| !table_schemas.iter().any(|t| &t.name == *name))
+        {
+            anyhow::bail!("Table '{}.{}' does not exist", schema_name, missing);
+        }
+        table_schemas.retain(|t| tables.contains(&t.name));
+    }
+
+    let files = rust_codegen::generate_rust_models(&table_schemas)?;
+    let written = rust_codegen::write_rust_models(out, &files)?;
+
+    println!(
+        "🦀 已为 {} 个表生成 Rust 模型（schema: {}）:",
+        table_schemas.len(),
+        schema_name
+    );
+    for path in written {
+        println!("   {}", path.display());
+    }
+
+    Ok(())
+}
+
+/// 启动 HTTP 服务器
+///
+/// 初始化：
+/// - Schema 缓存（可选以 Redis 作为多实例共享的第二层，只有这时才需要 REDIS_URL；
+///   可选从快照文件预热，离线模式下只从快照读取）
+/// - DDL 变更监听（可选）
+/// - HTTP 服务器（离线模式下需要数据库的端点返回 503）
+async fn serve(pool: Option<Pool<Postgres>>, args: &ServeArgs) -> anyhow::Result<()> {
     // 初始化 Schema 缓存
-    let schema_cache = SchemaCache::with_defaults(pool.clone());
+    let mut schema_cache = match &pool {
+        Some(pool) => SchemaCache::new(pool.clone(), CacheConfig::default()),
+        None => SchemaCache::offline(CacheConfig::default()),
+    };
+    if args.shared_cache {
+        let redis_url = env::var("REDIS_URL")
+            .map_err(|_| anyhow::anyhow!("--shared-cache requires REDIS_URL"))?;
+        let client = redis::Client::open(redis_url)?;
+        let namespace = args
+            .cache_namespace
+            .as_deref()
+            .unwrap_or(shared::DEFAULT_NAMESPACE);
+        let shared_cache = SharedCache::new(client, namespace);
+        println!(
+            "🗄️  Schema 缓存使用 Redis 共享（键前缀: {}，实例: {}）",
+            namespace,
+            shared_cache.instance_id()
+        );
+        shared_cache.spawn_connector();
+        schema_cache = schema_cache.with_shared(shared_cache.clone());
+        shared::spawn_invalidation_subscriber(shared_cache, schema_cache.clone());
+    }
+    schema_cache.spawn_sweeper();
+    if let Some(path) = &args.snapshot {
+        let snapshot = SchemaSnapshot::read_from_file(path)?;
+        println!(
+            "📦 已从快照 {} 加载 {} 个表（版本: {}）{}",
+            path.display(),
+            snapshot.table_count(),
+            snapshot.version,
+            if args.offline { "，离线模式" } else { "" }
+        );
+        schema_cache.load_snapshot(snapshot).await;
+    }
+
+    // DDL 变更监听（与离线模式互斥）
+    if let Some(pool) = &pool {
+        if args.install_ddl_trigger {
+            listener::install_ddl_trigger(pool).await?;
+            println!("🔔 已安装 DDL event trigger");
+        }
+        if args.watch_ddl || args.install_ddl_trigger {
+            if !listener::ddl_trigger_installed(pool).await? {
+                println!("⚠️  未安装 DDL event trigger，监听不会收到通知（使用 --install-ddl-trigger 安装）");
+            }
+            listener::spawn_ddl_listener(pool.clone(), schema_cache.clone());
+            println!("🔔 正在监听 DDL 变更（频道: {}）", listener::DDL_CHANNEL);
+        }
+    }
 
     println!("🚀 Orpheus BaaS Platform");
     println!("   Core Services:");
@@ -69,12 +353,35 @@ async fn main() -> anyhow::Result<()> {
     println!("🌐 Server running at http://127.0.0.1:8080");
     println!();
     println!("📚 Schema API 端点:");
+    println!("   GET  /schema/schemas             - 列出所有 schema");
     println!("   GET  /schema/tables              - 列出所有表");
     println!("   GET  /schema/tables/{{name}}       - 获取表结构");
-    println!("   GET  /schema/overview            - Schema 概览");
+    println!("   GET  /schema/tables/{{name}}/policies - 行级安全策略");
+    println!("   GET  /schema/tables/{{name}}/stats - 表统计信息");
+    println!("   GET  /schema/tables/{{name}}/jsonschema - JSON Schema（?variant=insert）");
+    println!("   GET  /schema/overview            - Schema 概览（?detailed=true&stats=true）");
+    println!("   GET  /schema/stats               - 表统计信息和存储大小（?sort=size）");
+    println!("   GET  /schema/relationships       - 表关系图");
+    println!("   GET  /schema/relationships/{{name}} - 指定表的关系");
+    println!("   GET  /schema/types               - 用户定义类型");
+    println!("   GET  /schema/sequences           - 序列（当前值、所属列）");
+    println!("   GET  /schema/extensions          - 已安装的扩展");
+    println!("   GET  /schema/types.ts            - TypeScript 类型定义");
+    println!("   GET  /schema/erd                 - 实体关系图（?format=mermaid|dot）");
+    println!("   GET  /schema/functions           - 列出所有函数");
+    println!("   GET  /schema/functions/{{name}}    - 获取函数信息");
     println!("   GET  /schema/cached/tables/{{name}} - 获取表结构（缓存）");
-    println!("   GET  /schema/cache/stats         - 缓存统计");
+    println!("   GET  /schema/cached/functions/{{name}} - 获取函数信息（缓存）");
+    println!("   GET  /schema/cache/stats         - 缓存统计（命中率、加载耗时、淘汰数）");
     println!("   POST /schema/cache/preload       - 预加载缓存");
+    println!("   POST /schema/cache/invalidate/{{name}} - 使表缓存失效");
+    println!("   POST /schema/diff                - 与快照比较差异");
+    println!("   GET  /schema/openapi.json        - OpenAPI 3.1 文档");
+    println!("   GET  /schema/snapshot            - 下载 schema 快照（?schemas=public,app）");
+    println!("   （以上端点均支持 ?schema=<name>，默认为 public）");
+    if args.offline {
+        println!("   （离线模式：只有 /schema/cached、/schema/cache、openapi.json 和 snapshot 端点可用）");
+    }
     println!();
     println!("📚 其他示例端点:");
     println!("   GET  /github/stars/:owner/:repo  - GitHub 仓库 stars 查询");
@@ -94,26 +401,30 @@ async fn main() -> anyhow::Result<()> {
             ])
             .max_age(3600);
 
-        App::new()
+        let app = App::new()
             .wrap(cors)
-            .app_data(web::Data::new(pool.clone()))
-            .app_data(web::Data::new(client.clone()))
             .app_data(web::Data::new(schema_cache.clone()))
-            // Schema API 端点
-            .service(schema_handler::get_tables)
-            .service(schema_handler::get_table_info)
-            .service(schema_handler::get_schema_overview)
-            .service(schema_handler::get_cached_table_info)
-            .service(schema_handler::get_cache_stats)
-            .service(schema_handler::clear_cache)
-            .service(schema_handler::preload_cache)
+            // 只依赖 Schema 缓存的端点
+            .configure(cache_routes)
             // 示例端点：GitHub API 集成
-            .service(get_github_repo_stars)
+            .service(get_github_repo_stars);
             // TODO: 添加核心 BaaS 端点
             // .service(web::scope("/rest/v1").configure(rest::configure))
             // .service(web::scope("/realtime/v1").configure(realtime::configure))
             // .service(web::scope("/storage/v1").configure(storage::configure))
             // .service(web::scope("/meta/v1").configure(meta::configure))
+
+        // 直接查询数据库的端点
+        match &pool {
+            Some(pool) => app
+                .app_data(web::Data::new(pool.clone()))
+                .configure(database_routes),
+            None => app.service(
+                web::scope("")
+                    .wrap(from_fn(reject_offline))
+                    .configure(database_routes),
+            ),
+        }
     })
     .workers(10)
     .bind(("0.0.0.0", 8080))?
@@ -122,3 +433,53 @@ async fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// 直接查询数据库的 Schema API 端点
+fn database_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(schema_handler::get_schemas)
+        .service(schema_handler::get_tables)
+        .service(schema_handler::get_table_info)
+        .service(schema_handler::get_table_policies)
+        .service(schema_handler::get_table_stats_info)
+        .service(schema_handler::get_table_json_schema)
+        .service(schema_handler::get_schema_overview)
+        .service(schema_handler::get_table_stats)
+        .service(schema_handler::get_relationships)
+        .service(schema_handler::get_table_relationships)
+        .service(schema_handler::get_user_types)
+        .service(schema_handler::get_sequences)
+        .service(schema_handler::get_extensions)
+        .service(schema_handler::get_typescript_types)
+        .service(schema_handler::get_erd)
+        .service(schema_handler::get_functions)
+        .service(schema_handler::get_function_info)
+        .service(schema_handler::diff_schema);
+}
+
+/// 只依赖 Schema 缓存的端点（离线模式下从快照读取）
+fn cache_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(schema_handler::get_cached_function_info)
+        .service(schema_handler::get_cached_table_info)
+        .service(schema_handler::get_cache_stats)
+        .service(schema_handler::clear_cache)
+        .service(schema_handler::invalidate_cache)
+        .service(schema_handler::preload_cache)
+        .service(schema_handler::get_openapi_document)
+        .service(schema_handler::get_schema_snapshot);
+}
+
+/// 离线模式下拒绝需要数据库的端点（未知路径仍然返回 404）
+async fn reject_offline(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    if req.match_pattern().is_none() {
+        return Ok(next.call(req).await?.map_into_left_body());
+    }
+    let response = HttpResponse::ServiceUnavailable().json(ApiResponse::<()>::with_code(
+        503,
+        None,
+        Some("Database is not available in offline mode".to_string()),
+    ));
+    Ok(req.into_response(response).map_into_right_body())
+}
@@ -20,11 +20,10 @@ impl<T> ApiResponse<T> {
     }
 
     /// 创建自定义状态码的响应
-    #[allow(dead_code)]
     pub fn with_code(code: u16, data: Option<T>, message: Option<String>) -> Self {
         Self {
             code,
-            success: code >= 200 && code < 300,
+            success: (200..300).contains(&code),
             data,
             message,
         }
@@ -1,13 +1,25 @@
 // Schema Cache - Schema 信息缓存层
 // 避免频繁查询 information_schema，提高性能
 
-use super::{inspector, types::TableSchema};
-use anyhow::Result;
+use super::{
+    inspector,
+    listener::CacheAction,
+    openapi,
+    shared::{SharedCache, SharedEntry},
+    snapshot::{self, SchemaSnapshot, SnapshotSchema},
+    types::{FunctionInfo, TableSchema},
+};
+use anyhow::{anyhow, Result};
+use futures_util::future::{BoxFuture, FutureExt, Shared};
+use serde_json::Value;
 use sqlx::PgPool;
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError};
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
 
 /// Schema 缓存配置
 #[derive(Debug, Clone)]
@@ -16,6 +28,16 @@ pub struct CacheConfig {
     pub ttl: Duration,
     /// 是否启用缓存
     pub enabled: bool,
+    /// 离线模式：不访问数据库，缓存未命中时从加载的快照读取
+    pub offline: bool,
+    /// 过期后仍可直接返回旧值的时间窗口（stale-while-revalidate），期间在后台刷新；
+    /// None 表示过期后等待重新加载
+    pub stale_while_revalidate: Option<Duration>,
+    /// 每类缓存（表、函数、OpenAPI 文档）的最大条目数，超出时淘汰最久未使用的条目，
+    /// 直到只剩上限的 90%；None 表示不限制
+    pub max_entries: Option<usize>,
+    /// 定期清理过期条目的间隔（由 `spawn_sweeper` 启动）；None 表示不清理
+    pub sweep_interval: Option<Duration>,
 }
 
 impl Default for CacheConfig {
@@ -23,48 +45,285 @@ impl Default for CacheConfig {
         Self {
             ttl: Duration::from_secs(300), // 默认 5 分钟过期
             enabled: true,
+            offline: false,
+            stale_while_revalidate: None,
+            max_entries: Some(10_000),
+            sweep_interval: Some(Duration::from_secs(60)),
         }
     }
 }
 
 /// Schema 缓存项
-#[derive(Debug, Clone)]
-struct CacheEntry {
-    schema: TableSchema,
+#[derive(Debug)]
+struct CacheEntry<T> {
+    value: T,
     cached_at: Instant,
+    /// 最近一次访问的时间（`clock_micros`），用于 LRU 淘汰
+    last_used: AtomicU64,
 }
 
-impl CacheEntry {
-    fn new(schema: TableSchema) -> Self {
+impl<T> CacheEntry<T> {
+    fn new(value: T) -> Self {
         Self {
-            schema,
+            value,
             cached_at: Instant::now(),
+            last_used: AtomicU64::new(clock_micros()),
         }
     }
 
+    /// 从 Redis 读取的条目：沿用 Redis 中剩余的过期时间，而不是重新开始计时
+    fn from_shared(entry: SharedEntry<T>, ttl: Duration) -> Self {
+        let mut cache_entry = Self::new(entry.value);
+        if let Some(remaining) = entry.ttl {
+            let age = ttl.saturating_sub(remaining);
+            cache_entry.cached_at = cache_entry
+                .cached_at
+                .checked_sub(age)
+                .unwrap_or(cache_entry.cached_at);
+        }
+        cache_entry
+    }
+
+    /// 记录一次访问（只需要读锁）
+    fn touch(&self) {
+        self.last_used.store(clock_micros(), Ordering::Relaxed);
+    }
+
     fn is_expired(&self, ttl: Duration) -> bool {
         self.cached_at.elapsed() > ttl
     }
+
+    /// 已过期，但仍在 stale-while-revalidate 窗口内
+    fn is_stale_usable(&self, config: &CacheConfig) -> bool {
+        config
+            .stale_while_revalidate
+            .is_some_and(|window| self.cached_at.elapsed() <= config.ttl + window)
+    }
+
+    /// 已过期且不能再作为旧值返回，可以清理
+    fn is_evictable(&self, config: &CacheConfig) -> bool {
+        self.is_expired(config.ttl) && !self.is_stale_usable(config)
+    }
+}
+
+/// 进程内单调时钟（微秒），用于比较条目的访问先后
+fn clock_micros() -> u64 {
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+    u64::try_from(EPOCH.get_or_init(Instant::now).elapsed().as_micros()).unwrap_or(u64::MAX)
+}
+
+/// 条目数超过上限时淘汰最久未使用的条目，直到只剩上限的 90%，返回淘汰的数量
+///
+/// 一次多淘汰一些，缓存满了之后不必在每次插入时都扫描所有条目
+fn evict_lru<T>(entries: &mut HashMap<String, CacheEntry<T>>, max_entries: Option<usize>) -> usize {
+    let Some(max_entries) = max_entries else {
+        return 0;
+    };
+    if entries.len() <= max_entries {
+        return 0;
+    }
+    let excess = entries.len() - (max_entries - max_entries / 10);
+
+    let mut by_last_used: Vec<(u64, String)> = entries
+        .iter()
+        .map(|(key, entry)| (entry.last_used.load(Ordering::Relaxed), key.clone()))
+        .collect();
+    // 只需要找出最久未使用的 excess 个条目，不必完整排序
+    by_last_used.select_nth_unstable(excess - 1);
+    for (_, key) in by_last_used.into_iter().take(excess) {
+        entries.remove(&key);
+    }
+    excess
+}
+
+/// 移除可以清理的过期条目，返回移除的数量
+fn remove_expired<T>(entries: &mut HashMap<String, CacheEntry<T>>, config: &CacheConfig) -> usize {
+    let before = entries.len();
+    entries.retain(|_, entry| !entry.is_evictable(config));
+    before - entries.len()
+}
+
+/// 缓存的访问和加载计数
+#[derive(Debug, Default)]
+struct CacheMetrics {
+    /// 内存缓存命中（包括返回旧值）
+    hits: AtomicU64,
+    /// 过期后在 stale-while-revalidate 窗口内返回旧值
+    stale_hits: AtomicU64,
+    /// 内存缓存未命中
+    misses: AtomicU64,
+    /// 内存未命中但 Redis 命中
+    shared_hits: AtomicU64,
+    /// 从数据库（离线时从快照）加载的次数
+    loads: AtomicU64,
+    /// 加载失败的次数
+    load_failures: AtomicU64,
+    /// 所有加载的总耗时（微秒）
+    load_micros: AtomicU64,
+    /// 因超出条目上限被淘汰的条目数
+    evictions: AtomicU64,
+    /// 过期后被定期清理移除的条目数
+    expirations: AtomicU64,
+}
+
+impl CacheMetrics {
+    fn count(counter: &AtomicU64, n: usize) {
+        counter.fetch_add(u64::try_from(n).unwrap_or(u64::MAX), Ordering::Relaxed);
+    }
+
+    fn get(counter: &AtomicU64) -> u64 {
+        counter.load(Ordering::Relaxed)
+    }
+}
+
+/// 正在进行的加载，等待者共享同一个结果
+type Flight<T> = Shared<BoxFuture<'static, Result<T, Arc<anyhow::Error>>>>;
+
+/// 缓存键 -> (加载编号, 加载)
+type FlightMap<T> = HashMap<String, (u64, Flight<T>)>;
+
+/// 按缓存键合并并发加载（single-flight）
+///
+/// 同一个键同时只执行一次加载，其他调用等待并共享结果（包括错误）
+#[derive(Clone)]
+struct SingleFlight<T> {
+    flights: Arc<Mutex<FlightMap<T>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl<T: Clone + Send + Sync + 'static> SingleFlight<T> {
+    fn new() -> Self {
+        Self {
+            flights: Arc::new(Mutex::new(HashMap::new())),
+            next_id: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// 执行加载；同一个键已有加载在进行时等待它的结果
+    ///
+    /// 加载不依赖发起的调用：发起者被取消后，其他等待者仍会得到结果
+    async fn run<F>(&self, key: String, load: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>> + Send + 'static,
+    {
+        let flight = {
+            let mut flights = self.lock();
+            match flights.get(&key) {
+                Some((_, flight)) => flight.clone(),
+                None => {
+                    let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+                    let registry = self.clone();
+                    let flight_key = key.clone();
+                    let flight = async move {
+                        let result = load.await.map_err(Arc::new);
+                        registry.finish(&flight_key, id);
+                        result
+                    }
+                    .boxed()
+                    .shared();
+                    flights.insert(key, (id, flight.clone()));
+                    flight
+                }
+            }
+        };
+
+        flight.await.map_err(|e| anyhow!("{:#}", e))
+    }
+
+    /// 指定的键是否有加载在进行
+    fn is_running(&self, key: &str) -> bool {
+        self.lock().contains_key(key)
+    }
+
+    /// 忘记键满足条件的进行中的加载，之后对这些键的调用重新开始加载
+    fn forget(&self, matches: impl Fn(&str) -> bool) {
+        self.lock().retain(|key, _| !matches(key));
+    }
+
+    /// 加载结束后移除（键已被新的加载占用时保留）
+    fn finish(&self, key: &str, id: u64) {
+        let mut flights = self.lock();
+        if flights.get(key).is_some_and(|(current, _)| *current == id) {
+            flights.remove(key);
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, FlightMap<T>> {
+        self.flights.lock().unwrap_or_else(PoisonError::into_inner)
+    }
 }
 
 /// Schema 缓存管理器
 #[derive(Clone)]
 pub struct SchemaCache {
-    pool: PgPool,
-    cache: Arc<RwLock<HashMap<String, CacheEntry>>>,
+    /// 离线模式下为 None，不会连接数据库
+    pool: Option<PgPool>,
+    cache: Arc<RwLock<HashMap<String, CacheEntry<TableSchema>>>>,
+    /// 函数缓存，以 schema 名为键
+    functions: Arc<RwLock<HashMap<String, CacheEntry<Vec<FunctionInfo>>>>>,
+    /// 生成的 OpenAPI 文档，以 schema 名为键
+    openapi: Arc<RwLock<HashMap<String, CacheEntry<Value>>>>,
+    /// 最近加载的快照（离线模式下作为数据源）
+    snapshot: Arc<RwLock<Option<Arc<SchemaSnapshot>>>>,
+    /// 多实例共享的 Redis 缓存层（可选）
+    shared: Option<SharedCache>,
+    /// 进行中的表结构加载
+    table_flights: SingleFlight<TableSchema>,
+    /// 进行中的函数加载
+    function_flights: SingleFlight<Vec<FunctionInfo>>,
+    /// 每次失效时递增；加载期间发生过失效时，加载结果不写入缓存
+    generation: Arc<AtomicU64>,
+    metrics: Arc<CacheMetrics>,
     config: CacheConfig,
 }
 
 impl SchemaCache {
-    /// 创建新的缓存实例
+    /// 创建新的缓存实例（`config.offline` 为 true 时不使用连接池）
     pub fn new(pool: PgPool, config: CacheConfig) -> Self {
+        Self::build((!config.offline).then_some(pool), config)
+    }
+
+    /// 创建离线缓存实例，所有结构都来自 `load_snapshot` 加载的快照
+    pub fn offline(config: CacheConfig) -> Self {
+        Self::build(
+            None,
+            CacheConfig {
+                offline: true,
+                ..config
+            },
+        )
+    }
+
+    fn build(pool: Option<PgPool>, config: CacheConfig) -> Self {
         Self {
             pool,
             cache: Arc::new(RwLock::new(HashMap::new())),
+            functions: Arc::new(RwLock::new(HashMap::new())),
+            openapi: Arc::new(RwLock::new(HashMap::new())),
+            snapshot: Arc::new(RwLock::new(None)),
+            shared: None,
+            table_flights: SingleFlight::new(),
+            function_flights: SingleFlight::new(),
+            generation: Arc::new(AtomicU64::new(0)),
+            metrics: Arc::new(CacheMetrics::default()),
             config,
         }
     }
 
+    /// 在内存缓存之后加上 Redis 缓存层
+    ///
+    /// 内存未命中时先读 Redis 再查数据库；失效、刷新和清空同时作用于 Redis，
+    /// 并通知其他实例（需要另外启动 `shared::spawn_invalidation_subscriber` 接收通知）
+    pub fn with_shared(mut self, shared: SharedCache) -> Self {
+        self.shared = Some(shared);
+        self
+    }
+
+    /// Redis 缓存层
+    pub fn shared(&self) -> Option<&SharedCache> {
+        self.shared.as_ref()
+    }
+
     /// 使用默认配置创建缓存
     pub fn with_defaults(pool: PgPool) -> Self {
         Self::new(pool, CacheConfig::default())
@@ -85,29 +344,39 @@ impl SchemaCache {
 
         // 如果禁用缓存，直接从数据库查询
         if !self.config.enabled {
-            return inspector::get_table_schema(&self.pool, table_name, Some(schema)).await;
+            return self.load_table_schema(table_name, schema).await;
         }
 
         // 检查缓存
-        {
+        let stale = {
             let cache_read = self.cache.read().await;
-            if let Some(entry) = cache_read.get(&cache_key) {
-                if !entry.is_expired(self.config.ttl) {
-                    return Ok(entry.schema.clone());
+            match cache_read.get(&cache_key) {
+                Some(entry) if !entry.is_expired(self.config.ttl) => {
+                    entry.touch();
+                    CacheMetrics::count(&self.metrics.hits, 1);
+                    return Ok(entry.value.clone());
+                }
+                Some(entry) if entry.is_stale_usable(&self.config) => {
+                    entry.touch();
+                    CacheMetrics::count(&self.metrics.hits, 1);
+                    CacheMetrics::count(&self.metrics.stale_hits, 1);
+                    Some(entry.value.clone())
+                }
+                _ => {
+                    CacheMetrics::count(&self.metrics.misses, 1);
+                    None
                 }
             }
-        }
-
-        // 缓存未命中或已过期，从数据库读取
-        let table_schema = inspector::get_table_schema(&self.pool, table_name, Some(schema)).await?;
+        };
 
-        // 更新缓存
-        {
-            let mut cache_write = self.cache.write().await;
-            cache_write.insert(cache_key, CacheEntry::new(table_schema.clone()));
+        // 已过期但仍在 stale-while-revalidate 窗口内：直接返回旧值，在后台刷新
+        if let Some(table_schema) = stale {
+            self.revalidate_table(table_name, schema);
+            return Ok(table_schema);
         }
 
-        Ok(table_schema)
+        // 缓存未命中或已过期，并发请求只加载一次
+        self.fetch_table_schema(table_name, schema).await
     }
 
     /// 获取所有表名（带缓存）
@@ -117,7 +386,7 @@ impl SchemaCache {
 
         // 如果禁用缓存，直接查询
         if !self.config.enabled {
-            return inspector::get_all_tables(&self.pool, Some(schema)).await;
+            return self.load_table_names(schema).await;
         }
 
         // 检查缓存中是否有表列表
@@ -133,22 +402,201 @@ impl SchemaCache {
         }
 
         // 缓存未命中，直接查询
-        inspector::get_all_tables(&self.pool, Some(schema)).await
+        self.load_table_names(schema).await
+    }
+
+    /// 获取指定 schema 下的所有函数（带缓存）
+    pub async fn get_functions(&self, schema_name: Option<&str>) -> Result<Vec<FunctionInfo>> {
+        let schema = schema_name.unwrap_or("public");
+
+        if !self.config.enabled {
+            return self.load_functions(schema).await;
+        }
+
+        let stale = {
+            let functions_read = self.functions.read().await;
+            match functions_read.get(schema) {
+                Some(entry) if !entry.is_expired(self.config.ttl) => {
+                    entry.touch();
+                    CacheMetrics::count(&self.metrics.hits, 1);
+                    return Ok(entry.value.clone());
+                }
+                Some(entry) if entry.is_stale_usable(&self.config) => {
+                    entry.touch();
+                    CacheMetrics::count(&self.metrics.hits, 1);
+                    CacheMetrics::count(&self.metrics.stale_hits, 1);
+                    Some(entry.value.clone())
+                }
+                _ => {
+                    CacheMetrics::count(&self.metrics.misses, 1);
+                    None
+                }
+            }
+        };
+
+        if let Some(functions) = stale {
+            self.revalidate_functions(schema);
+            return Ok(functions);
+        }
+
+        self.fetch_functions(schema).await
+    }
+
+    /// 获取指定名称的函数（带缓存，可能有多个重载）
+    pub async fn get_function(
+        &self,
+        function_name: &str,
+        schema_name: Option<&str>,
+    ) -> Result<Vec<FunctionInfo>> {
+        let functions = self.get_functions(schema_name).await?;
+
+        Ok(functions
+            .into_iter()
+            .filter(|f| f.name == function_name)
+            .collect())
+    }
+
+    /// 使指定 schema 的函数缓存失效
+    pub async fn invalidate_functions(&self, schema_name: Option<&str>) {
+        self.invalidate_action(CacheAction::Functions {
+            schema: schema_name.unwrap_or("public").to_string(),
+        })
+        .await;
     }
 
     /// 使指定表的缓存失效
     pub async fn invalidate(&self, table_name: &str, schema_name: Option<&str>) {
+        self.invalidate_action(CacheAction::Table {
+            schema: schema_name.unwrap_or("public").to_string(),
+            table: table_name.to_string(),
+        })
+        .await;
+    }
+
+    /// 使指定 schema 下的所有表、函数和 OpenAPI 文档缓存失效
+    pub async fn invalidate_schema(&self, schema_name: Option<&str>) {
+        self.invalidate_action(CacheAction::Schema {
+            schema: schema_name.unwrap_or("public").to_string(),
+        })
+        .await;
+    }
+
+    /// 丢弃内存中受影响的缓存，不影响 Redis 和其他实例
+    ///
+    /// 用于处理其他实例广播的失效消息。进行中的加载可能读到失效前的结构，
+    /// 它们的结果不再写入缓存；受影响的键不再合并到这些加载上，之后的请求重新加载
+    pub async fn evict(&self, action: &CacheAction) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+
+        match action {
+            CacheAction::Table { schema, table } => {
+                let cache_key = format!("{}.{}", schema, table);
+                self.table_flights.forget(|key| key == cache_key);
+                {
+                    let mut cache_write = self.cache.write().await;
+                    cache_write.remove(&cache_key);
+                }
+                self.invalidate_openapi(schema).await;
+            }
+            CacheAction::Functions { schema } => {
+                self.function_flights.forget(|key| key == schema);
+                {
+                    let mut functions_write = self.functions.write().await;
+                    functions_write.remove(schema);
+                }
+                self.invalidate_openapi(schema).await;
+            }
+            CacheAction::Schema { schema } => {
+                let prefix = format!("{}.", schema);
+                self.table_flights.forget(|key| key.starts_with(&prefix));
+                self.function_flights.forget(|key| key == schema);
+                {
+                    let mut cache_write = self.cache.write().await;
+                    cache_write.retain(|_, entry| entry.value.schema != *schema);
+                }
+                {
+                    let mut functions_write = self.functions.write().await;
+                    functions_write.remove(schema);
+                }
+                self.invalidate_openapi(schema).await;
+            }
+            CacheAction::All => {
+                self.table_flights.forget(|_| true);
+                self.function_flights.forget(|_| true);
+
+                let mut cache_write = self.cache.write().await;
+                cache_write.clear();
+
+                let mut functions_write = self.functions.write().await;
+                functions_write.clear();
+
+                let mut openapi_write = self.openapi.write().await;
+                openapi_write.clear();
+            }
+        }
+    }
+
+    /// 处理 DDL 通知：丢弃本实例内存和 Redis 中受影响的缓存，不通知其他实例
+    ///
+    /// 每个实例都会收到同一个通知并各自处理（重复删除没有影响）。先删除 Redis 中的旧条目
+    /// 再丢弃内存中的缓存，之后的加载不会从 Redis 读到 DDL 之前的结构
+    pub async fn evict_for_ddl(&self, action: &CacheAction) {
+        if let Some(shared) = &self.shared {
+            shared.remove(action).await;
+        }
+        self.evict(action).await;
+    }
+
+    /// 丢弃内存和 Redis 中受影响的缓存，并通知其他实例
+    async fn invalidate_action(&self, action: CacheAction) {
+        self.evict(&action).await;
+        if let Some(shared) = &self.shared {
+            shared.invalidate(&action).await;
+        }
+    }
+
+    /// 指定表是否在缓存中（包括已过期的条目）
+    pub async fn is_cached(&self, table_name: &str, schema_name: Option<&str>) -> bool {
         let schema = schema_name.unwrap_or("public");
         let cache_key = format!("{}.{}", schema, table_name);
 
-        let mut cache_write = self.cache.write().await;
-        cache_write.remove(&cache_key);
+        self.cache.read().await.contains_key(&cache_key)
+    }
+
+    /// 缓存中的所有表（包括已过期的条目），返回 (schema, 表名)
+    pub async fn cached_tables(&self) -> Vec<(String, String)> {
+        let cache_read = self.cache.read().await;
+        cache_read
+            .values()
+            .map(|entry| (entry.value.schema.clone(), entry.value.name.clone()))
+            .collect()
+    }
+
+    /// 缓存中以指定表为分区的分区表，返回 (schema, 表名)
+    pub async fn cached_partition_parents(
+        &self,
+        table_name: &str,
+        schema_name: Option<&str>,
+    ) -> Vec<(String, String)> {
+        let schema = schema_name.unwrap_or("public");
+
+        let cache_read = self.cache.read().await;
+        cache_read
+            .values()
+            .filter(|entry| {
+                entry.value.partitioning.as_ref().is_some_and(|p| {
+                    p.partitions
+                        .iter()
+                        .any(|child| child.schema == schema && child.name == table_name)
+                })
+            })
+            .map(|entry| (entry.value.schema.clone(), entry.value.name.clone()))
+            .collect()
     }
 
     /// 清空所有缓存
     pub async fn clear(&self) {
-        let mut cache_write = self.cache.write().await;
-        cache_write.clear();
+        self.invalidate_action(CacheAction::All).await;
     }
 
     /// 刷新指定表的缓存
@@ -160,21 +608,444 @@ impl SchemaCache {
         self.get_table_schema(table_name, schema_name).await
     }
 
+    /// 从数据库重新加载指定表，并覆盖 Redis 中的旧条目
+    ///
+    /// 用于处理 DDL 通知：只丢弃本实例内存中的缓存，不通知其他实例；
+    /// 加载时跳过 Redis，避免读到 DDL 之前的结构
+    pub async fn reload(&self, table_name: &str, schema_name: Option<&str>) -> Result<TableSchema> {
+        let schema = schema_name.unwrap_or("public");
+        self.evict(&CacheAction::Table {
+            schema: schema.to_string(),
+            table: table_name.to_string(),
+        })
+        .await;
+
+        if !self.config.enabled {
+            return self.load_table_schema(table_name, schema).await;
+        }
+
+        let cache = self.clone();
+        let (table_name, schema) = (table_name.to_string(), schema.to_string());
+        self.table_flights
+            .run(format!("{}.{}", schema, table_name), async move {
+                cache.load_and_cache_table(&table_name, &schema, false).await
+            })
+            .await
+    }
+
     /// 预加载所有表的 schema 到缓存
+    ///
+    /// 使用批量加载，整个 schema 只需少量目录查询。
+    /// 启用 Redis 缓存层时同时写入 Redis，并通知其他实例丢弃旧的副本
     pub async fn preload(&self, schema_name: Option<&str>) -> Result<()> {
         let schema = schema_name.unwrap_or("public");
-        let tables = inspector::get_all_tables(&self.pool, Some(schema)).await?;
+        self.load_schema(schema).await?;
+
+        if let Some(shared) = &self.shared {
+            shared
+                .invalidate_peers(&CacheAction::Schema {
+                    schema: schema.to_string(),
+                })
+                .await;
+        }
+
+        Ok(())
+    }
+
+    /// 从数据库读取整个 schema 的表和函数，写入内存缓存和 Redis
+    ///
+    /// 返回读取到的表和函数（条目数超过上限时，其中一部分可能已被淘汰出缓存）
+    async fn load_schema(&self, schema: &str) -> Result<(Vec<TableSchema>, Vec<FunctionInfo>)> {
+        let generation = self.generation();
+        let table_schemas = self.timed_load(self.load_all_table_schemas(schema)).await?;
+        if let Some(shared) = self.shared_if_current(generation) {
+            shared.put_tables(&table_schemas, self.config.ttl).await;
+        }
+
+        {
+            let mut cache_write = self.cache.write().await;
+            if self.generation() == generation {
+                for table_schema in &table_schemas {
+                    let cache_key = format!("{}.{}", schema, table_schema.name);
+                    cache_write.insert(cache_key, CacheEntry::new(table_schema.clone()));
+                }
+                self.enforce_capacity(&mut cache_write);
+            }
+        }
+
+        // 同时预加载函数
+        let functions = self.timed_load(self.load_functions(schema)).await?;
+        if let Some(shared) = self.shared_if_current(generation) {
+            shared.put_functions(schema, &functions, self.config.ttl).await;
+        }
+        {
+            let mut functions_write = self.functions.write().await;
+            if self.generation() == generation {
+                functions_write.insert(schema.to_string(), CacheEntry::new(functions.clone()));
+                self.enforce_capacity(&mut functions_write);
+            }
+        }
+        self.invalidate_openapi(schema).await;
+
+        Ok((table_schemas, functions))
+    }
+
+    /// 获取指定 schema 的 OpenAPI 文档（带缓存）
+    ///
+    /// 文档由缓存中的表结构和函数生成，生成前会先预加载整个 schema，
+    /// 保证文档包含所有表。表或函数缓存失效、刷新或预加载后，下一次请求会重新生成
+    pub async fn openapi_document(&self, schema_name: Option<&str>) -> Result<Value> {
+        let schema = schema_name.unwrap_or("public");
+
+        if !self.config.enabled {
+            let tables = self.load_all_table_schemas(schema).await?;
+            let functions = self.load_functions(schema).await?;
+            return Ok(openapi::build_openapi_document(schema, &tables, &functions));
+        }
+
+        {
+            let openapi_read = self.openapi.read().await;
+            if let Some(entry) = openapi_read.get(schema) {
+                if !entry.is_expired(self.config.ttl) {
+                    entry.touch();
+                    return Ok(entry.value.clone());
+                }
+            }
+        }
+
+        let generation = self.generation();
+        let (tables, functions) = self.load_schema(schema).await?;
+        let document = openapi::build_openapi_document(schema, &tables, &functions);
+
+        {
+            let mut openapi_write = self.openapi.write().await;
+            // 生成期间缓存被失效过，文档可能已经过时，只返回给本次请求
+            if self.generation() == generation {
+                openapi_write.insert(schema.to_string(), CacheEntry::new(document.clone()));
+                self.enforce_capacity(&mut openapi_write);
+            }
+        }
+
+        Ok(document)
+    }
+
+    /// 使指定 schema 的 OpenAPI 文档失效
+    async fn invalidate_openapi(&self, schema: &str) {
+        let mut openapi_write = self.openapi.write().await;
+        openapi_write.remove(schema);
+    }
+
+    /// 将快照中的所有表和函数加载到缓存
+    ///
+    /// 离线模式下快照同时作为数据源：缓存过期或失效后从快照重新读取
+    pub async fn load_snapshot(&self, snapshot: SchemaSnapshot) {
+        {
+            let mut cache_write = self.cache.write().await;
+            let mut functions_write = self.functions.write().await;
+            for (schema, contents) in &snapshot.schemas {
+                for table_schema in &contents.tables {
+                    let cache_key = format!("{}.{}", schema, table_schema.name);
+                    cache_write.insert(cache_key, CacheEntry::new(table_schema.clone()));
+                }
+                functions_write.insert(schema.clone(), CacheEntry::new(contents.functions.clone()));
+            }
+            self.enforce_capacity(&mut cache_write);
+            self.enforce_capacity(&mut functions_write);
+        }
+        for schema in snapshot.schemas.keys() {
+            self.invalidate_openapi(schema).await;
+        }
+
+        let mut snapshot_write = self.snapshot.write().await;
+        *snapshot_write = Some(Arc::new(snapshot));
+    }
+
+    /// 生成指定 schema 的快照
+    ///
+    /// 在线时先预加载，保证快照反映数据库的当前结构
+    pub async fn snapshot(&self, schema_names: &[String]) -> Result<SchemaSnapshot> {
+        if schema_names.is_empty() {
+            return Err(anyhow!("At least one schema is required"));
+        }
+        if let (false, Some(pool)) = (self.config.enabled, &self.pool) {
+            return snapshot::capture(pool, schema_names).await;
+        }
+
+        let mut schemas = BTreeMap::new();
+        for schema in schema_names {
+            let (tables, functions) = if self.config.enabled {
+                self.load_schema(schema).await?
+            } else {
+                (
+                    self.load_all_table_schemas(schema).await?,
+                    self.load_functions(schema).await?,
+                )
+            };
+            schemas.insert(schema.clone(), SnapshotSchema { tables, functions });
+        }
+
+        SchemaSnapshot::new(schemas)
+    }
+
+    /// 加载表结构并写入缓存，同一个表同时只有一个加载在进行
+    async fn fetch_table_schema(&self, table_name: &str, schema: &str) -> Result<TableSchema> {
+        let cache = self.clone();
+        let (table_name, schema) = (table_name.to_string(), schema.to_string());
+        self.table_flights
+            .run(format!("{}.{}", schema, table_name), async move {
+                cache.load_and_cache_table(&table_name, &schema, true).await
+            })
+            .await
+    }
+
+    /// 加载 schema 下的所有函数并写入缓存，同一个 schema 同时只有一个加载在进行
+    async fn fetch_functions(&self, schema: &str) -> Result<Vec<FunctionInfo>> {
+        let cache = self.clone();
+        let schema = schema.to_string();
+        self.function_flights
+            .run(schema.clone(), async move {
+                cache.load_and_cache_functions(&schema).await
+            })
+            .await
+    }
+
+    /// 在后台刷新过期的表结构（已有加载在进行时不重复启动）
+    fn revalidate_table(&self, table_name: &str, schema: &str) {
+        if self
+            .table_flights
+            .is_running(&format!("{}.{}", schema, table_name))
+        {
+            return;
+        }
+        let cache = self.clone();
+        let (table_name, schema) = (table_name.to_string(), schema.to_string());
+        tokio::spawn(async move {
+            if let Err(e) = cache.fetch_table_schema(&table_name, &schema).await {
+                eprintln!("⚠️  后台刷新 {}.{} 的缓存失败: {}", schema, table_name, e);
+            }
+        });
+    }
+
+    /// 在后台刷新过期的函数列表（已有加载在进行时不重复启动）
+    fn revalidate_functions(&self, schema: &str) {
+        if self.function_flights.is_running(schema) {
+            return;
+        }
+        let cache = self.clone();
+        let schema = schema.to_string();
+        tokio::spawn(async move {
+            if let Err(e) = cache.fetch_functions(&schema).await {
+                eprintln!("⚠️  后台刷新 {} 的函数缓存失败: {}", schema, e);
+            }
+        });
+    }
 
-        for table_name in tables {
-            // 忽略单个表的加载错误，继续加载其他表
-            if let Ok(table_schema) = inspector::get_table_schema(&self.pool, &table_name, Some(schema)).await {
+    /// 先读 Redis（`read_shared` 为 false 时跳过），再从数据库读取表结构，然后写入缓存
+    async fn load_and_cache_table(
+        &self,
+        table_name: &str,
+        schema: &str,
+        read_shared: bool,
+    ) -> Result<TableSchema> {
+        let generation = self.generation();
+        let shared_table = if read_shared {
+            self.shared_table(table_name, schema).await
+        } else {
+            None
+        };
+        let entry = match shared_table {
+            Some(shared_entry) => {
+                CacheMetrics::count(&self.metrics.shared_hits, 1);
+                CacheEntry::from_shared(shared_entry, self.config.ttl)
+            }
+            None => {
+                let table_schema = self
+                    .timed_load(self.load_table_schema(table_name, schema))
+                    .await?;
+                if let Some(shared) = self.shared_if_current(generation) {
+                    shared
+                        .put_tables(std::slice::from_ref(&table_schema), self.config.ttl)
+                        .await;
+                }
+                CacheEntry::new(table_schema)
+            }
+        };
+        let table_schema = entry.value.clone();
+
+        {
+            let mut cache_write = self.cache.write().await;
+            // 加载期间缓存被失效过，结果可能已经过时，只返回给等待的请求
+            if self.generation() == generation {
                 let cache_key = format!("{}.{}", schema, table_name);
-                let mut cache_write = self.cache.write().await;
-                cache_write.insert(cache_key, CacheEntry::new(table_schema));
+                cache_write.insert(cache_key, entry);
+                self.enforce_capacity(&mut cache_write);
             }
         }
+        self.invalidate_openapi(schema).await;
 
-        Ok(())
+        Ok(table_schema)
+    }
+
+    /// 先读 Redis，再从数据库读取函数，然后写入缓存
+    async fn load_and_cache_functions(&self, schema: &str) -> Result<Vec<FunctionInfo>> {
+        let generation = self.generation();
+        let entry = match self.shared_functions(schema).await {
+            Some(shared_entry) => {
+                CacheMetrics::count(&self.metrics.shared_hits, 1);
+                CacheEntry::from_shared(shared_entry, self.config.ttl)
+            }
+            None => {
+                let functions = self.timed_load(self.load_functions(schema)).await?;
+                if let Some(shared) = self.shared_if_current(generation) {
+                    shared.put_functions(schema, &functions, self.config.ttl).await;
+                }
+                CacheEntry::new(functions)
+            }
+        };
+        let functions = entry.value.clone();
+
+        {
+            let mut functions_write = self.functions.write().await;
+            if self.generation() == generation {
+                functions_write.insert(schema.to_string(), entry);
+                self.enforce_capacity(&mut functions_write);
+            }
+        }
+        self.invalidate_openapi(schema).await;
+
+        Ok(functions)
+    }
+
+    /// 执行一次加载，记录次数、失败和耗时
+    async fn timed_load<T>(&self, load: impl Future<Output = Result<T>>) -> Result<T> {
+        let started = Instant::now();
+        let result = load.await;
+
+        CacheMetrics::count(&self.metrics.loads, 1);
+        if result.is_err() {
+            CacheMetrics::count(&self.metrics.load_failures, 1);
+        }
+        self.metrics.load_micros.fetch_add(
+            u64::try_from(started.elapsed().as_micros()).unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+
+        result
+    }
+
+    /// 条目数超过上限时淘汰最久未使用的条目
+    fn enforce_capacity<T>(&self, entries: &mut HashMap<String, CacheEntry<T>>) {
+        let evicted = evict_lru(entries, self.config.max_entries);
+        CacheMetrics::count(&self.metrics.evictions, evicted);
+    }
+
+    /// 移除所有已过期（且超出 stale-while-revalidate 窗口）的条目，返回移除的数量
+    pub async fn sweep(&self) -> usize {
+        let removed = remove_expired(&mut *self.cache.write().await, &self.config)
+            + remove_expired(&mut *self.functions.write().await, &self.config)
+            + remove_expired(&mut *self.openapi.write().await, &self.config);
+        CacheMetrics::count(&self.metrics.expirations, removed);
+        removed
+    }
+
+    /// 启动定期清理过期条目的后台任务，未配置 `sweep_interval` 时返回 None
+    pub fn spawn_sweeper(&self) -> Option<JoinHandle<()>> {
+        let interval = self.config.sweep_interval?;
+        let cache = self.clone();
+        Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // 第一次 tick 立即完成
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                cache.sweep().await;
+            }
+        }))
+    }
+
+    /// 当前的失效计数
+    fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    /// 加载期间没有发生过失效时返回 Redis 缓存层，用于写入加载结果
+    fn shared_if_current(&self, generation: u64) -> Option<&SharedCache> {
+        self.shared
+            .as_ref()
+            .filter(|_| self.generation() == generation)
+    }
+
+    /// 从 Redis 读取表结构
+    async fn shared_table(
+        &self,
+        table_name: &str,
+        schema: &str,
+    ) -> Option<SharedEntry<TableSchema>> {
+        self.shared.as_ref()?.get_table(schema, table_name).await
+    }
+
+    /// 从 Redis 读取 schema 下的所有函数
+    async fn shared_functions(&self, schema: &str) -> Option<SharedEntry<Vec<FunctionInfo>>> {
+        self.shared.as_ref()?.get_functions(schema).await
+    }
+
+    /// 离线模式下的快照，未加载快照时返回错误
+    async fn offline_snapshot(&self) -> Result<Arc<SchemaSnapshot>> {
+        self.snapshot
+            .read()
+            .await
+            .clone()
+            .ok_or_else(|| anyhow!("Offline mode requires a loaded schema snapshot"))
+    }
+
+    /// 从数据库（离线模式下从快照）读取表结构
+    async fn load_table_schema(&self, table_name: &str, schema: &str) -> Result<TableSchema> {
+        if let Some(pool) = &self.pool {
+            return inspector::get_table_schema(pool, table_name, Some(schema)).await;
+        }
+        self.offline_snapshot()
+            .await?
+            .table(schema, table_name)
+            .cloned()
+            .ok_or_else(|| anyhow!("Table '{}.{}' not found in snapshot", schema, table_name))
+    }
+
+    /// 从数据库（离线模式下从快照）读取 schema 下的所有表结构
+    async fn load_all_table_schemas(&self, schema: &str) -> Result<Vec<TableSchema>> {
+        if let Some(pool) = &self.pool {
+            return inspector::get_all_table_schemas(pool, Some(schema)).await;
+        }
+        self.offline_snapshot()
+            .await?
+            .tables(schema)
+            .map(<[TableSchema]>::to_vec)
+            .ok_or_else(|| anyhow!("Schema '{}' not found in snapshot", schema))
+    }
+
+    /// 从数据库（离线模式下从快照）读取 schema 下的所有表名
+    async fn load_table_names(&self, schema: &str) -> Result<Vec<String>> {
+        if let Some(pool) = &self.pool {
+            return inspector::get_all_tables(pool, Some(schema)).await;
+        }
+        Ok(self
+            .load_all_table_schemas(schema)
+            .await?
+            .into_iter()
+            .map(|t| t.name)
+            .collect())
+    }
+
+    /// 从数据库（离线模式下从快照）读取 schema 下的所有函数
+    async fn load_functions(&self, schema: &str) -> Result<Vec<FunctionInfo>> {
+        if let Some(pool) = &self.pool {
+            return inspector::get_functions(pool, Some(schema)).await;
+        }
+        self.offline_snapshot()
+            .await?
+            .functions(schema)
+            .map(<[FunctionInfo]>::to_vec)
+            .ok_or_else(|| anyhow!("Schema '{}' not found in snapshot", schema))
     }
 
     /// 获取缓存统计信息
@@ -187,11 +1058,51 @@ impl SchemaCache {
             .filter(|entry| entry.is_expired(self.config.ttl))
             .count();
 
+        let function_entries = self.functions.read().await.len();
+        let snapshot_version = self
+            .snapshot
+            .read()
+            .await
+            .as_ref()
+            .map(|snapshot| snapshot.version.clone());
+
+        let metrics = &self.metrics;
+        let hits = CacheMetrics::get(&metrics.hits);
+        let misses = CacheMetrics::get(&metrics.misses);
+        let loads = CacheMetrics::get(&metrics.loads);
+        let load_micros = CacheMetrics::get(&metrics.load_micros);
+
         CacheStats {
             total_entries,
             active_entries: total_entries - expired_entries,
             expired_entries,
+            function_entries,
+            max_entries: self.config.max_entries,
             ttl_seconds: self.config.ttl.as_secs(),
+            hits,
+            stale_hits: CacheMetrics::get(&metrics.stale_hits),
+            misses,
+            hit_rate: if hits + misses == 0 {
+                0.0
+            } else {
+                hits as f64 / (hits + misses) as f64
+            },
+            shared_hits: CacheMetrics::get(&metrics.shared_hits),
+            loads,
+            load_failures: CacheMetrics::get(&metrics.load_failures),
+            average_load_ms: if loads == 0 {
+                0.0
+            } else {
+                load_micros as f64 / loads as f64 / 1000.0
+            },
+            evictions: CacheMetrics::get(&metrics.evictions),
+            expirations: CacheMetrics::get(&metrics.expirations),
+            offline: self.config.offline,
+            snapshot_version,
+            shared_instance_id: self
+                .shared
+                .as_ref()
+                .map(|shared| shared.instance_id().to_string()),
         }
     }
 }
@@ -205,15 +1116,186 @@ pub struct CacheStats {
     pub active_entries: usize,
     /// 已过期的条目数
     pub expired_entries: usize,
+    /// 函数缓存条目数（每个 schema 一条）
+    pub function_entries: usize,
+    /// 每类缓存的最大条目数（None 表示不限制）
+    pub max_entries: Option<usize>,
     /// TTL 秒数
     pub ttl_seconds: u64,
+    /// 内存缓存命中次数（包括返回旧值）
+    pub hits: u64,
+    /// 过期后在 stale-while-revalidate 窗口内返回旧值的次数
+    pub stale_hits: u64,
+    /// 内存缓存未命中次数
+    pub misses: u64,
+    /// 命中率（hits / (hits + misses)）
+    pub hit_rate: f64,
+    /// 内存未命中但 Redis 命中的次数
+    pub shared_hits: u64,
+    /// 从数据库（离线时从快照）加载的次数
+    pub loads: u64,
+    /// 加载失败的次数
+    pub load_failures: u64,
+    /// 平均加载耗时（毫秒）
+    pub average_load_ms: f64,
+    /// 因超出条目上限被淘汰的条目数
+    pub evictions: u64,
+    /// 过期后被定期清理移除的条目数
+    pub expirations: u64,
+    /// 是否为离线模式
+    pub offline: bool,
+    /// 已加载快照的版本哈希
+    pub snapshot_version: Option<String>,
+    /// Redis 共享缓存中当前实例的 ID（未启用时为 None）
+    pub shared_instance_id: Option<String>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::AtomicUsize;
     use std::time::Duration;
 
+    #[test]
+    fn test_lru_eviction() {
+        let mut entries = HashMap::new();
+        for key in ["a", "b", "c"] {
+            entries.insert(key.to_string(), CacheEntry::new(key));
+            std::thread::sleep(Duration::from_millis(2));
+        }
+        // 访问 a 之后，b 成为最久未使用的条目
+        if let Some(entry) = entries.get("a") {
+            entry.touch();
+        }
+
+        assert_eq!(evict_lru(&mut entries, None), 0);
+        assert_eq!(evict_lru(&mut entries, Some(3)), 0);
+        assert_eq!(evict_lru(&mut entries, Some(2)), 1);
+        assert!(entries.contains_key("a"));
+        assert!(!entries.contains_key("b"));
+        assert!(entries.contains_key("c"));
+
+        // 超出上限后一次淘汰到上限的 90%，之后的插入不再触发淘汰
+        let mut entries = HashMap::new();
+        for i in 0..101 {
+            let entry = CacheEntry::new(i);
+            entry.last_used.store(i, Ordering::Relaxed);
+            entries.insert(i.to_string(), entry);
+        }
+        assert_eq!(evict_lru(&mut entries, Some(100)), 11);
+        assert_eq!(entries.len(), 90);
+        assert!((0..11).all(|i| !entries.contains_key(&i.to_string())));
+        assert!((11..101).all(|i| entries.contains_key(&i.to_string())));
+        entries.insert("new".to_string(), CacheEntry::new(0));
+        assert_eq!(evict_lru(&mut entries, Some(100)), 0);
+    }
+
+    #[test]
+    fn test_shared_entry_ttl() {
+        let ttl = Duration::from_secs(300);
+
+        // Redis 中只剩 10 秒的条目在本地也只剩 10 秒
+        let entry = CacheEntry::from_shared(
+            SharedEntry {
+                value: 1,
+                ttl: Some(Duration::from_secs(10)),
+            },
+            ttl,
+        );
+        assert!(!entry.is_expired(ttl));
+        assert!(entry.is_expired(Duration::from_secs(280)));
+
+        let entry = CacheEntry::from_shared(SharedEntry { value: 1, ttl: None }, ttl);
+        assert!(!entry.is_expired(Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn test_remove_expired() {
+        let mut config = CacheConfig {
+            ttl: Duration::from_millis(10),
+            ..CacheConfig::default()
+        };
+        let mut entries = HashMap::new();
+        entries.insert("old".to_string(), CacheEntry::new(1));
+        std::thread::sleep(Duration::from_millis(20));
+        entries.insert("new".to_string(), CacheEntry::new(2));
+
+        // stale-while-revalidate 窗口内的旧值保留
+        config.stale_while_revalidate = Some(Duration::from_secs(60));
+        assert_eq!(remove_expired(&mut entries, &config), 0);
+
+        config.stale_while_revalidate = None;
+        assert_eq!(remove_expired(&mut entries, &config), 1);
+        assert!(entries.contains_key("new"));
+    }
+
+    #[tokio::test]
+    async fn test_single_flight() {
+        let flights = SingleFlight::<usize>::new();
+        let loads = Arc::new(AtomicUsize::new(0));
+
+        // 并发的 10 个请求只执行一次加载
+        let requests = (0..10).map(|_| {
+            let loads = loads.clone();
+            flights.run("public.users".to_string(), async move {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                Ok(loads.fetch_add(1, Ordering::SeqCst) + 1)
+            })
+        });
+        let results = futures_util::future::join_all(requests).await;
+        assert!(results.iter().all(|r| matches!(r, Ok(1))));
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+        assert!(!flights.is_running("public.users"));
+
+        // 错误同样共享给所有等待者，结束后下一次请求重新加载
+        let failing = (0..3).map(|_| {
+            flights.run("public.users".to_string(), async {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                Err(anyhow!("Table 'users' does not exist"))
+            })
+        });
+        let results = futures_util::future::join_all(failing).await;
+        assert!(results
+            .iter()
+            .all(|r| r.as_ref().is_err_and(|e| e.to_string().contains("does not exist"))));
+        assert!(matches!(
+            flights.run("public.users".to_string(), async { Ok(2) }).await,
+            Ok(2)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_single_flight_forget() {
+        let flights = SingleFlight::<usize>::new();
+        let running = ["public.users", "public.posts", "api.users"].map(|key| {
+            let flights = flights.clone();
+            tokio::spawn(async move {
+                flights
+                    .run(key.to_string(), async {
+                        tokio::time::sleep(Duration::from_millis(200)).await;
+                        Ok(1)
+                    })
+                    .await
+            })
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        // 只忘记匹配的键，其他键的加载仍然可以合并
+        flights.forget(|key| key == "public.users");
+        assert!(!flights.is_running("public.users"));
+        assert!(flights.is_running("public.posts"));
+        assert!(flights.is_running("api.users"));
+
+        flights.forget(|key| key.starts_with("public."));
+        assert!(!flights.is_running("public.posts"));
+        assert!(flights.is_running("api.users"));
+
+        // 被忘记的加载仍然把结果交给已经在等待的调用
+        for handle in running {
+            assert!(matches!(handle.await, Ok(Ok(1))));
+        }
+    }
+
     #[tokio::test]
     #[ignore] // 需要数据库连接
     async fn test_cache_basic() {
@@ -245,6 +1327,10 @@ mod tests {
             let config = CacheConfig {
                 ttl: Duration::from_millis(100), // 100ms 过期
                 enabled: true,
+                offline: false,
+                stale_while_revalidate: None,
+                max_entries: None,
+                sweep_interval: None,
             };
             
             let cache = SchemaCache::new(pool, config);
@@ -0,0 +1,1169 @@
+// Schema Diff - Schema 差异比较
+// 比较两份 schema（实时数据库、JSON 快照或另一个数据库），并可生成迁移 SQL
+
+use super::{
+    inspector,
+    snapshot::SchemaSnapshot,
+    types::{
+        ColumnInfo, ConstraintInfo, ConstraintType, ForeignKeyInfo, IdentityGeneration,
+        IndexInfo, IndexKey, RelationKind, TableSchema,
+    },
+};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+/// 两份 schema 之间的差异
+///
+/// 差异方向为 `from` -> `to`：`added_tables` 是 `to` 中新增的表
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SchemaDiff {
+    /// 新增的表（完整结构）
+    pub added_tables: Vec<TableSchema>,
+    /// 删除的表
+    pub removed_tables: Vec<TableRef>,
+    /// 发生变化的表
+    pub changed_tables: Vec<TableDiff>,
+}
+
+/// 表的引用（schema + 名称 + 类型）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableRef {
+    pub schema: String,
+    pub name: String,
+    pub kind: RelationKind,
+}
+
+/// 某个值的变化
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValueChange<T> {
+    pub from: T,
+    pub to: T,
+}
+
+/// 单个表的差异
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableDiff {
+    pub schema: String,
+    pub name: String,
+    pub kind: RelationKind,
+    /// 新增的列
+    pub added_columns: Vec<ColumnInfo>,
+    /// 删除的列名
+    pub removed_columns: Vec<String>,
+    /// 发生变化的列
+    pub changed_columns: Vec<ColumnDiff>,
+    /// 主键变化
+    pub primary_key: Option<PrimaryKeyChange>,
+    /// 新增的索引（定义变化的索引会同时出现在删除和新增中）
+    pub added_indexes: Vec<IndexInfo>,
+    /// 删除的索引名
+    pub removed_indexes: Vec<String>,
+    /// 新增的外键
+    pub added_foreign_keys: Vec<ForeignKeyInfo>,
+    /// 删除的外键约束名
+    pub removed_foreign_keys: Vec<String>,
+    /// 新增的唯一 / CHECK 约束
+    pub added_constraints: Vec<ConstraintInfo>,
+    /// 删除的唯一 / CHECK 约束名
+    pub removed_constraints: Vec<String>,
+    /// 视图定义变化（仅视图和物化视图）
+    pub view_definition: Option<ValueChange<String>>,
+}
+
+/// 列的差异
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnDiff {
+    /// 列名
+    pub name: String,
+    /// 类型变化（DDL 类型，例如 "character varying(255)"）
+    pub data_type: Option<ValueChange<String>>,
+    /// 可空性变化
+    pub is_nullable: Option<ValueChange<bool>>,
+    /// 默认值变化
+    pub default_value: Option<ValueChange<Option<String>>>,
+}
+
+/// 主键变化
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrimaryKeyChange {
+    /// 原主键约束名（原表没有主键时为 None）
+    pub constraint_name: Option<String>,
+    /// 原主键列
+    pub from: Vec<String>,
+    /// 新主键列
+    pub to: Vec<String>,
+}
+
+impl SchemaDiff {
+    /// 两份 schema 是否完全一致
+    pub fn is_empty(&self) -> bool {
+        self.added_tables.is_empty()
+            && self.removed_tables.is_empty()
+            && self.changed_tables.is_empty()
+    }
+
+    /// 生成把 `from` 迁移到 `to` 的 SQL 脚本
+    ///
+    /// 顺序：先删除外键、约束和索引，再删除/创建表、修改列，
+    /// 最后添加约束、索引和外键，避免依赖顺序问题
+    pub fn to_migration_sql(&self) -> String {
+        let mut statements: Vec<String> = Vec::new();
+
+        // 1. 删除外键、约束、索引
+        for table in &self.changed_tables {
+            let qualified = qualified_name(&table.schema, &table.name);
+            for name in table
+                .removed_foreign_keys
+                .iter()
+                .chain(&table.removed_constraints)
+            {
+                statements.push(format!(
+                    "ALTER TABLE {} DROP CONSTRAINT {};",
+                    qualified,
+                    quote_ident(name)
+                ));
+            }
+            for name in &table.removed_indexes {
+                statements.push(format!(
+                    "DROP INDEX {};",
+                    qualified_name(&table.schema, name)
+                ));
+            }
+        }
+
+        // 2. 删除表
+        for table in &self.removed_tables {
+            statements.push(format!(
+                "DROP {} {};",
+                relation_keyword(table.kind),
+                qualified_name(&table.schema, &table.name)
+            ));
+        }
+
+        // 3. 创建表
+        for table in &self.added_tables {
+            statements.extend(create_relation_sql(table));
+        }
+
+        // 4. 修改已有表
+        for table in &self.changed_tables {
+            statements.extend(alter_table_sql(table));
+        }
+
+        // 5. 最后添加外键（被引用的表此时都已存在）
+        for table in &self.added_tables {
+            for fk in &table.foreign_keys {
+                statements.push(add_foreign_key_sql(&table.schema, &table.name, fk));
+            }
+        }
+        for table in &self.changed_tables {
+            for fk in &table.added_foreign_keys {
+                statements.push(add_foreign_key_sql(&table.schema, &table.name, fk));
+            }
+        }
+
+        statements.join("\n")
+    }
+}
+
+/// 比较两份 schema，返回 `from` -> `to` 的差异
+pub fn diff_schemas(from: &[TableSchema], to: &[TableSchema]) -> SchemaDiff {
+    let from_map: HashMap<(&str, &str), &TableSchema> = from
+        .iter()
+        .map(|t| ((t.schema.as_str(), t.name.as_str()), t))
+        .collect();
+    let to_map: HashMap<(&str, &str), &TableSchema> = to
+        .iter()
+        .map(|t| ((t.schema.as_str(), t.name.as_str()), t))
+        .collect();
+
+    let mut diff = SchemaDiff::default();
+
+    for table in to {
+        match from_map.get(&(table.schema.as_str(), table.name.as_str())) {
+            None => diff.added_tables.push(table.clone()),
+            Some(old) => {
+                if let Some(table_diff) = diff_table(old, table) {
+                    diff.changed_tables.push(table_diff);
+                }
+            }
+        }
+    }
+
+    for table in from {
+        if !to_map.contains_key(&(table.schema.as_str(), table.name.as_str())) {
+            diff.removed_tables.push(TableRef {
+                schema: table.schema.clone(),
+                name: table.name.clone(),
+                kind: table.kind,
+            });
+        }
+    }
+
+    diff.added_tables
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+    diff.removed_tables
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+    diff.changed_tables
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+
+    diff
+}
+
+/// 比较两个数据库中同一个 schema 的差异
+///
+/// # Arguments
+/// * `from_pool` - 基准数据库
+/// * `to_pool` - 目标数据库
+/// * `schema_name` - Schema 名称，默认为 "public"
+pub async fn diff_databases(
+    from_pool: &PgPool,
+    to_pool: &PgPool,
+    schema_name: Option<&str>,
+) -> Result<SchemaDiff> {
+    let (from, to) = tokio::try_join!(
+        inspector::get_all_table_schemas(from_pool, schema_name),
+        inspector::get_all_table_schemas(to_pool, schema_name),
+    )?;
+
+    Ok(diff_schemas(&from, &to))
+}
+
+/// 比较 schema 快照与实时数据库的差异（快照为基准）
+///
+/// 快照应已通过 `SchemaSnapshot::verify` 校验；快照中没有指定的 schema 时返回错误
+pub async fn diff_against_snapshot(
+    pool: &PgPool,
+    schema_name: Option<&str>,
+    snapshot: &SchemaSnapshot,
+) -> Result<SchemaDiff> {
+    let schema = schema_name.unwrap_or("public");
+    let from = snapshot
+        .tables(schema)
+        .ok_or_else(|| anyhow!("Schema '{}' not found in snapshot", schema))?;
+    let live = inspector::get_all_table_schemas(pool, Some(schema)).await?;
+
+    Ok(diff_schemas(from, &live))
+}
+
+/// 比较同名表的两个版本，没有差异时返回 None
+fn diff_table(from: &TableSchema, to: &TableSchema) -> Option<TableDiff> {
+    let mut diff = TableDiff {
+        schema: to.schema.clone(),
+        name: to.name.clone(),
+        kind: to.kind,
+        added_columns: Vec::new(),
+        removed_columns: Vec::new(),
+        changed_columns: Vec::new(),
+        primary_key: None,
+        added_indexes: Vec::new(),
+        removed_indexes: Vec::new(),
+        added_foreign_keys: Vec::new(),
+        removed_foreign_keys: Vec::new(),
+        added_constraints: Vec::new(),
+        removed_constraints: Vec::new(),
+        view_definition: None,
+    };
+
+    // 列
+    for column in &to.columns {
+        match from.get_column(&column.name) {
+            None => diff.added_columns.push(column.clone()),
+            Some(old) => {
+                if let Some(column_diff) = diff_column(old, column) {
+                    diff.changed_columns.push(column_diff);
+                }
+            }
+        }
+    }
+    for column in &from.columns {
+        if !to.has_column(&column.name) {
+            diff.removed_columns.push(column.name.clone());
+        }
+    }
+
+    // 主键
+    if from.primary_keys != to.primary_keys {
+        diff.primary_key = Some(PrimaryKeyChange {
+            constraint_name: from
+                .constraints_of(ConstraintType::PrimaryKey)
+                .first()
+                .map(|c| c.name.clone()),
+            from: from.primary_keys.clone(),
+            to: to.primary_keys.clone(),
+        });
+    }
+
+    // 索引（主键索引和唯一约束自带的索引随约束变化，不单独比较）
+    let from_indexes = comparable_indexes(from);
+    let to_indexes = comparable_indexes(to);
+    for index in &to_indexes {
+        match from_indexes.iter().find(|i| i.name == index.name) {
+            Some(old) if same_index(old, index) => {}
+            Some(old) => {
+                diff.removed_indexes.push(old.name.clone());
+                diff.added_indexes.push((*index).clone());
+            }
+            None => diff.added_indexes.push((*index).clone()),
+        }
+    }
+    for index in &from_indexes {
+        if !to_indexes.iter().any(|i| i.name == index.name) {
+            diff.removed_indexes.push(index.name.clone());
+        }
+    }
+
+    // 外键
+    for fk in &to.foreign_keys {
+        match from
+            .foreign_keys
+            .iter()
+            .find(|f| f.constraint_name == fk.constraint_name)
+        {
+            Some(old) if same_foreign_key(old, fk) => {}
+            Some(old) => {
+                diff.removed_foreign_keys.push(old.constraint_name.clone());
+                diff.added_foreign_keys.push(fk.clone());
+            }
+            None => diff.added_foreign_keys.push(fk.clone()),
+        }
+    }
+    for fk in &from.foreign_keys {
+        if !to
+            .foreign_keys
+            .iter()
+            .any(|f| f.constraint_name == fk.constraint_name)
+        {
+            diff.removed_foreign_keys.push(fk.constraint_name.clone());
+        }
+    }
+
+    // 唯一 / CHECK 约束（主键和外键在上面单独比较）
+    let from_constraints = comparable_constraints(from);
+    let to_constraints = comparable_constraints(to);
+    for constraint in &to_constraints {
+        match from_constraints.iter().find(|c| c.name == constraint.name) {
+            Some(old) if old.definition == constraint.definition => {}
+            Some(old) => {
+                diff.removed_constraints.push(old.name.clone());
+                diff.added_constraints.push((*constraint).clone());
+            }
+            None => diff.added_constraints.push((*constraint).clone()),
+        }
+    }
+    for constraint in &from_constraints {
+        if !to_constraints.iter().any(|c| c.name == constraint.name) {
+            diff.removed_constraints.push(constraint.name.clone());
+        }
+    }
+
+    // 视图定义
+    if let (Some(old_view), Some(new_view)) = (&from.view, &to.view) {
+        if old_view.definition.trim() != new_view.definition.trim() {
+            diff.view_definition = Some(ValueChange {
+                from: old_view.definition.clone(),
+                to: new_view.definition.clone(),
+            });
+        }
+    }
+
+    let unchanged = diff.added_columns.is_empty()
+        && diff.removed_columns.is_empty()
+        && diff.changed_columns.is_empty()
+        && diff.primary_key.is_none()
+        && diff.added_indexes.is_empty()
+        && diff.removed_indexes.is_empty()
+        && diff.added_foreign_keys.is_empty()
+        && diff.removed_foreign_keys.is_empty()
+        && diff.added_constraints.is_empty()
+        && diff.removed_constraints.is_empty()
+        && diff.view_definition.is_none();
+
+    if unchanged {
+        None
+    } else {
+        Some(diff)
+    }
+}
+
+/// 比较同名列的两个版本，没有差异时返回 None
+fn diff_column(from: &ColumnInfo, to: &ColumnInfo) -> Option<ColumnDiff> {
+    let change = |a: String, b: String| (a != b).then_some(ValueChange { from: a, to: b });
+
+    let diff = ColumnDiff {
+        name: to.name.clone(),
+        data_type: change(from.sql_type(), to.sql_type()),
+        is_nullable: (from.is_nullable != to.is_nullable).then_some(ValueChange {
+            from: from.is_nullable,
+            to: to.is_nullable,
+        }),
+        default_value: (from.default_value != to.default_value).then(|| ValueChange {
+            from: from.default_value.clone(),
+            to: to.default_value.clone(),
+        }),
+    };
+
+    if diff.data_type.is_none() && diff.is_nullable.is_none() && diff.default_value.is_none() {
+        None
+    } else {
+        Some(diff)
+    }
+}
+
+/// 需要单独比较的索引：排除主键索引和唯一约束自带的索引
+fn comparable_indexes(table: &TableSchema) -> Vec<&IndexInfo> {
+    table
+        .indexes
+        .iter()
+        .filter(|i| !i.is_primary)
+        .filter(|i| {
+            !table
+                .constraints
+                .iter()
+                .any(|c| c.constraint_type == ConstraintType::Unique && c.name == i.name)
+        })
+        .collect()
+}
+
+/// 需要单独比较的约束：唯一约束和 CHECK 约束
+fn comparable_constraints(table: &TableSchema) -> Vec<&ConstraintInfo> {
+    table
+        .constraints
+        .iter()
+        .filter(|c| {
+            matches!(
+                c.constraint_type,
+                ConstraintType::Unique | ConstraintType::Check
+            )
+        })
+        .collect()
+}
+
+/// 两个索引的结构是否一致（忽略大小、有效性等运行时状态）
+fn same_index(a: &IndexInfo, b: &IndexInfo) -> bool {
+    if !a.definition.is_empty() && !b.definition.is_empty() {
+        return a.definition == b.definition;
+    }
+    a.columns == b.columns
+        && a.keys == b.keys
+        && a.include_columns == b.include_columns
+        && a.predicate == b.predicate
+        && a.is_unique == b.is_unique
+        && a.index_type == b.index_type
+}
+
+/// 两个外键的结构是否一致（忽略约束名以外的元数据）
+fn same_foreign_key(a: &ForeignKeyInfo, b: &ForeignKeyInfo) -> bool {
+    a.columns == b.columns
+        && a.foreign_schema == b.foreign_schema
+        && a.foreign_table_name == b.foreign_table_name
+        && a.on_delete == b.on_delete
+        && a.on_update == b.on_update
+        && a.match_type == b.match_type
+        && a.is_deferrable == b.is_deferrable
+        && a.initially_deferred == b.initially_deferred
+}
+
+/// 生成创建关系的 SQL
+fn create_relation_sql(table: &TableSchema) -> Vec<String> {
+    let qualified = qualified_name(&table.schema, &table.name);
+
+    match (table.kind, &table.view) {
+        (RelationKind::View, Some(view)) => {
+            return vec![format!(
+                "CREATE VIEW {} AS\n{}",
+                qualified,
+                terminate(&view.definition)
+            )];
+        }
+        (RelationKind::MaterializedView, Some(view)) => {
+            return vec![format!(
+                "CREATE MATERIALIZED VIEW {} AS\n{}",
+                qualified,
+                terminate(&view.definition)
+            )];
+        }
+        (RelationKind::ForeignTable, _) => {
+            return vec![format!(
+                "-- Foreign table {} must be created manually (server options are not captured)",
+                qualified
+            )];
+        }
+        _ => {}
+    }
+
+    let mut definitions: Vec<String> = table
+        .columns
+        .iter()
+        .map(|c| format!("    {}", column_definition_sql(c)))
+        .collect();
+
+    if !table.primary_keys.is_empty() {
+        definitions.push(format!(
+            "    PRIMARY KEY ({})",
+            quote_list(&table.primary_keys)
+        ));
+    }
+    for constraint in comparable_constraints(table) {
+        definitions.push(format!(
+            "    CONSTRAINT {} {}",
+            quote_ident(&constraint.name),
+            constraint.definition
+        ));
+    }
+
+    // 列默认值使用的序列（SERIAL）先于表创建，建表后再归属到列
+    let sequences: Vec<(String, String)> = table
+        .columns
+        .iter()
+        .filter_map(|c| owned_sequence_sql(&qualified, &c.name, c.default_value.as_deref()))
+        .collect();
+
+    let mut statements: Vec<String> = sequences.iter().map(|(create, _)| create.clone()).collect();
+    statements.push(format!(
+        "CREATE TABLE {} (\n{}\n);",
+        qualified,
+        definitions.join(",\n")
+    ));
+    statements.extend(sequences.into_iter().map(|(_, owned_by)| owned_by));
+
+    for index in comparable_indexes(table) {
+        statements.push(create_index_sql(&table.schema, &table.name, index));
+    }
+
+    statements
+}
+
+/// 生成修改已有表的 SQL
+fn alter_table_sql(table: &TableDiff) -> Vec<String> {
+    let qualified = qualified_name(&table.schema, &table.name);
+    let mut statements = Vec::new();
+
+    if let Some(change) = &table.view_definition {
+        statements.push(match table.kind {
+            RelationKind::MaterializedView => format!(
+                "DROP MATERIALIZED VIEW {};\nCREATE MATERIALIZED VIEW {} AS\n{}",
+                qualified,
+                qualified,
+                terminate(&change.to)
+            ),
+            _ => format!(
+                "CREATE OR REPLACE VIEW {} AS\n{}",
+                qualified,
+                terminate(&change.to)
+            ),
+        });
+    }
+
+    // 视图的列由定义决定，不能单独修改
+    if table.kind.is_view() {
+        return statements;
+    }
+
+    // 原主键先于列删除，删除主键列时约束会被一并删除
+    if let Some(constraint_name) = table
+        .primary_key
+        .as_ref()
+        .and_then(|change| change.constraint_name.as_ref())
+    {
+        statements.push(format!(
+            "ALTER TABLE {} DROP CONSTRAINT {};",
+            qualified,
+            quote_ident(constraint_name)
+        ));
+    }
+
+    for column in &table.added_columns {
+        let sequence =
+            owned_sequence_sql(&qualified, &column.name, column.default_value.as_deref());
+        if let Some((create, _)) = &sequence {
+            statements.push(create.clone());
+        }
+        statements.push(format!(
+            "ALTER TABLE {} ADD COLUMN {};",
+            qualified,
+            column_definition_sql(column)
+        ));
+        if let Some((_, owned_by)) = sequence {
+            statements.push(owned_by);
+        }
+    }
+
+    for column in &table.changed_columns {
+        let name = quote_ident(&column.name);
+        if let Some(change) = &column.data_type {
+            statements.push(format!(
+                "ALTER TABLE {} ALTER COLUMN {} TYPE {} USING {}::{};",
+                qualified, name, change.to, name, change.to
+            ));
+        }
+        if let Some(change) = &column.default_value {
+            let sequence = owned_sequence_sql(&qualified, &column.name, change.to.as_deref());
+            if let Some((create, _)) = &sequence {
+                statements.push(create.clone());
+            }
+            statements.push(match &change.to {
+                Some(default) => format!(
+                    "ALTER TABLE {} ALTER COLUMN {} SET DEFAULT {};",
+                    qualified, name, default
+                ),
+                None => format!(
+                    "ALTER TABLE {} ALTER COLUMN {} DROP DEFAULT;",
+                    qualified, name
+                ),
+            });
+            if let Some((_, owned_by)) = sequence {
+                statements.push(owned_by);
+            }
+        }
+        if let Some(change) = &column.is_nullable {
+            statements.push(format!(
+                "ALTER TABLE {} ALTER COLUMN {} {} NOT NULL;",
+                qualified,
+                name,
+                if change.to { "DROP" } else { "SET" }
+            ));
+        }
+    }
+
+    for column in &table.removed_columns {
+        statements.push(format!(
+            "ALTER TABLE {} DROP COLUMN {};",
+            qualified,
+            quote_ident(column)
+        ));
+    }
+
+    if let Some(change) = &table.primary_key {
+        if !change.to.is_empty() {
+            statements.push(format!(
+                "ALTER TABLE {} ADD PRIMARY KEY ({});",
+                qualified,
+                quote_list(&change.to)
+            ));
+        }
+    }
+
+    for constraint in &table.added_constraints {
+        statements.push(format!(
+            "ALTER TABLE {} ADD CONSTRAINT {} {};",
+            qualified,
+            quote_ident(&constraint.name),
+            constraint.definition
+        ));
+    }
+
+    for index in &table.added_indexes {
+        statements.push(create_index_sql(&table.schema, &table.name, index));
+    }
+
+    statements
+}
+
+/// 列定义 (例如: `"email" character varying(255) NOT NULL DEFAULT ''::character varying`)
+fn column_definition_sql(column: &ColumnInfo) -> String {
+    let mut definition = format!("{} {}", quote_ident(&column.name), column.sql_type());
+
+    if column.is_identity {
+        // 旧版本的快照没有记录生成方式
+        let generation = column
+            .identity_generation
+            .unwrap_or(IdentityGeneration::ByDefault);
+        definition.push_str(&format!(" GENERATED {} AS IDENTITY", generation.as_sql()));
+    } else if let Some(default) = &column.default_value {
+        definition.push_str(&format!(" DEFAULT {}", default));
+    }
+    if !column.is_nullable {
+        definition.push_str(" NOT NULL");
+    }
+
+    definition
+}
+
+/// 默认值 `nextval('...'::regclass)` 引用的序列名（已按需加引号，可能带 schema）
+fn default_sequence(default: &str) -> Option<String> {
+    let literal = default
+        .strip_prefix("nextval('")?
+        .strip_suffix("'::regclass)")?;
+    Some(literal.replace("''", "'"))
+}
+
+/// 列默认值依赖的序列：返回创建序列的 SQL 和把序列归属到该列的 SQL（与 SERIAL 相同）
+fn owned_sequence_sql(
+    qualified: &str,
+    column: &str,
+    default: Option<&str>,
+) -> Option<(String, String)> {
+    let sequence = default_sequence(default?)?;
+    Some((
+        format!("CREATE SEQUENCE IF NOT EXISTS {};", sequence),
+        format!(
+            "ALTER SEQUENCE {} OWNED BY {}.{};",
+            sequence,
+            qualified,
+            quote_ident(column)
+        ),
+    ))
+}
+
+/// 创建索引的 SQL
+///
+/// 优先使用数据库给出的完整定义，快照中没有定义时根据索引键拼接
+fn create_index_sql(schema: &str, table: &str, index: &IndexInfo) -> String {
+    if !index.definition.is_empty() {
+        return terminate(&index.definition);
+    }
+
+    let keys = if index.keys.is_empty() {
+        quote_list(&index.columns)
+    } else {
+        index
+            .keys
+            .iter()
+            .map(index_key_sql)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let mut sql = format!(
+        "CREATE {}INDEX {} ON {} USING {} ({})",
+        if index.is_unique { "UNIQUE " } else { "" },
+        quote_ident(&index.name),
+        qualified_name(schema, table),
+        index.index_type,
+        keys
+    );
+    if !index.include_columns.is_empty() {
+        sql.push_str(&format!(" INCLUDE ({})", quote_list(&index.include_columns)));
+    }
+    if let Some(predicate) = &index.predicate {
+        sql.push_str(&format!(" WHERE {}", predicate));
+    }
+    sql.push(';');
+
+    sql
+}
+
+/// 索引键的写法 (例如: `created_at DESC`, `(lower(email))`)
+fn index_key_sql(key: &IndexKey) -> String {
+    let mut sql = match (&key.column, &key.expression) {
+        (Some(column), _) => quote_ident(column),
+        (None, Some(expression)) => format!("({})", expression),
+        (None, None) => String::new(),
+    };
+    // 默认是 ASC NULLS LAST / DESC NULLS FIRST，只输出非默认部分
+    if key.descending {
+        sql.push_str(" DESC");
+    }
+    if key.nulls_first != key.descending {
+        sql.push_str(if key.nulls_first {
+            " NULLS FIRST"
+        } else {
+            " NULLS LAST"
+        });
+    }
+    sql
+}
+
+/// 添加外键的 SQL
+fn add_foreign_key_sql(schema: &str, table: &str, fk: &ForeignKeyInfo) -> String {
+    let local: Vec<String> = fk.local_columns().iter().map(|c| c.to_string()).collect();
+    let foreign: Vec<String> = fk.foreign_columns().iter().map(|c| c.to_string()).collect();
+
+    let mut sql = format!(
+        "ALTER TABLE {} ADD CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({})",
+        qualified_name(schema, table),
+        quote_ident(&fk.constraint_name),
+        quote_list(&local),
+        qualified_name(&fk.foreign_schema, &fk.foreign_table_name),
+        quote_list(&foreign)
+    );
+
+    if fk.match_type != "SIMPLE" {
+        sql.push_str(&format!(" MATCH {}", fk.match_type));
+    }
+    if let Some(on_delete) = fk.on_delete.as_deref().filter(|a| *a != "NO ACTION") {
+        sql.push_str(&format!(" ON DELETE {}", on_delete));
+    }
+    if let Some(on_update) = fk.on_update.as_deref().filter(|a| *a != "NO ACTION") {
+        sql.push_str(&format!(" ON UPDATE {}", on_update));
+    }
+    if fk.is_deferrable {
+        sql.push_str(" DEFERRABLE");
+        if fk.initially_deferred {
+            sql.push_str(" INITIALLY DEFERRED");
+        }
+    }
+    sql.push(';');
+
+    sql
+}
+
+/// DROP 语句中的关系关键字
+fn relation_keyword(kind: RelationKind) -> &'static str {
+    match kind {
+        RelationKind::View => "VIEW",
+        RelationKind::MaterializedView => "MATERIALIZED VIEW",
+        RelationKind::ForeignTable => "FOREIGN TABLE",
+        RelationKind::Table | RelationKind::PartitionedTable => "TABLE",
+    }
+}
+
+/// 确保语句以分号结尾
+fn terminate(sql: &str) -> String {
+    let trimmed = sql.trim();
+    if trimmed.ends_with(';') {
+        trimmed.to_string()
+    } else {
+        format!("{};", trimmed)
+    }
+}
+
+/// 需要加引号的 SQL 保留字（常见的列名冲突）
+const RESERVED_WORDS: &[&str] = &[
+    "all",
+    "analyse",
+    "analyze",
+    "and",
+    "any",
+    "array",
+    "as",
+    "asc",
+    "both",
+    "case",
+    "cast",
+    "check",
+    "collate",
+    "column",
+    "constraint",
+    "create",
+    "current_date",
+    "current_role",
+    "current_time",
+    "current_timestamp",
+    "current_user",
+    "default",
+    "desc",
+    "distinct",
+    "do",
+    "else",
+    "end",
+    "except",
+    "false",
+    "fetch",
+    "for",
+    "foreign",
+    "from",
+    "grant",
+    "group",
+    "having",
+    "in",
+    "initially",
+    "intersect",
+    "into",
+    "leading",
+    "limit",
+    "localtime",
+    "localtimestamp",
+    "not",
+    "null",
+    "offset",
+    "on",
+    "only",
+    "or",
+    "order",
+    "placing",
+    "primary",
+    "references",
+    "returning",
+    "select",
+    "session_user",
+    "some",
+    "symmetric",
+    "table",
+    "then",
+    "to",
+    "trailing",
+    "true",
+    "union",
+    "unique",
+    "user",
+    "using",
+    "variadic",
+    "when",
+    "where",
+    "window",
+    "with",
+];
+
+/// 按需给标识符加双引号
+pub fn quote_ident(name: &str) -> String {
+    let is_simple = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+
+    if is_simple && !RESERVED_WORDS.contains(&name) {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+}
+
+/// 带 schema 的限定名
+fn qualified_name(schema: &str, name: &str) -> String {
+    format!("{}.{}", quote_ident(schema), quote_ident(name))
+}
+
+/// 逗号分隔的标识符列表
+fn quote_list(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|n| quote_ident(n))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_schemas_have_no_diff() {
+        let tables = vec![TableSchema::test("users", vec![ColumnInfo::test("id", "integer", false)])];
+        let diff = diff_schemas(&tables, &tables);
+
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_migration_sql(), "");
+    }
+
+    #[test]
+    fn test_added_and_removed_tables() {
+        let from = vec![TableSchema::test("legacy", vec![ColumnInfo::test("id", "integer", false)])];
+        let to = vec![TableSchema::test("users", vec![ColumnInfo::test("id", "integer", false)])];
+
+        let diff = diff_schemas(&from, &to);
+
+        assert_eq!(diff.added_tables.len(), 1);
+        assert_eq!(diff.added_tables[0].name, "users");
+        assert_eq!(diff.removed_tables.len(), 1);
+        assert_eq!(diff.removed_tables[0].name, "legacy");
+
+        let sql = diff.to_migration_sql();
+        assert!(sql.contains("DROP TABLE public.legacy;"));
+        assert!(sql.contains(
+            "CREATE TABLE public.users (\n    id integer NOT NULL,\n    PRIMARY KEY (id)\n);"
+        ));
+    }
+
+    #[test]
+    fn test_column_changes() {
+        let mut email = ColumnInfo::test("email", "text", true);
+        let from = vec![TableSchema::test(
+            "users",
+            vec![
+                ColumnInfo::test("id", "integer", false),
+                email.clone(),
+                ColumnInfo::test("age", "integer", true),
+            ],
+        )];
+
+        email.is_nullable = false;
+        email.default_value = Some("''::text".to_string());
+        let mut name = ColumnInfo::test("name", "character varying", true);
+        name.max_length = Some(100);
+        let to = vec![TableSchema::test(
+            "users",
+            vec![ColumnInfo::test("id", "bigint", false), email, name],
+        )];
+
+        let diff = diff_schemas(&from, &to);
+        assert_eq!(diff.changed_tables.len(), 1);
+
+        let users = &diff.changed_tables[0];
+        assert_eq!(users.added_columns.len(), 1);
+        assert_eq!(users.removed_columns, vec!["age".to_string()]);
+        assert_eq!(users.changed_columns.len(), 2);
+
+        let id = users
+            .changed_columns
+            .iter()
+            .find(|c| c.name == "id")
+            .expect("id diff missing");
+        assert_eq!(
+            id.data_type,
+            Some(ValueChange {
+                from: "integer".to_string(),
+                to: "bigint".to_string()
+            })
+        );
+
+        let sql = diff.to_migration_sql();
+        assert!(sql.contains("ALTER TABLE public.users ADD COLUMN name character varying(100);"));
+        assert!(
+            sql.contains("ALTER TABLE public.users ALTER COLUMN id TYPE bigint USING id::bigint;")
+        );
+        assert!(sql.contains("ALTER TABLE public.users ALTER COLUMN email SET DEFAULT ''::text;"));
+        assert!(sql.contains("ALTER TABLE public.users ALTER COLUMN email SET NOT NULL;"));
+        assert!(sql.contains("ALTER TABLE public.users DROP COLUMN age;"));
+    }
+
+    #[test]
+    fn test_serial_and_identity_columns() {
+        let mut id = ColumnInfo::test("id", "integer", false);
+        id.default_value = Some("nextval('users_id_seq'::regclass)".to_string());
+        let mut code = ColumnInfo::test("code", "bigint", false);
+        code.is_identity = true;
+        code.identity_generation = Some(IdentityGeneration::Always);
+        let to = vec![TableSchema::test("users", vec![id, code])];
+
+        // 与空 schema 比较：序列先于表创建，建表后归属到列
+        let sql = diff_schemas(&[], &to).to_migration_sql();
+        assert_eq!(
+            sql,
+            "CREATE SEQUENCE IF NOT EXISTS users_id_seq;\n\
+             CREATE TABLE public.users (\n    \
+             id integer DEFAULT nextval('users_id_seq'::regclass) NOT NULL,\n    \
+             code bigint GENERATED ALWAYS AS IDENTITY NOT NULL,\n    \
+             PRIMARY KEY (id)\n);\n\
+             ALTER SEQUENCE users_id_seq OWNED BY public.users.id;"
+        );
+
+        // 新增的 SERIAL 列
+        let mut order_no = ColumnInfo::test("order_no", "integer", false);
+        order_no.default_value =
+            Some("nextval('app.\"Orders_no_seq\"'::regclass)".to_string());
+        let mut users = to[0].clone();
+        users.columns.push(order_no);
+        let sql = diff_schemas(&to, &[users]).to_migration_sql();
+        assert_eq!(
+            sql,
+            "CREATE SEQUENCE IF NOT EXISTS app.\"Orders_no_seq\";\n\
+             ALTER TABLE public.users ADD COLUMN order_no integer \
+             DEFAULT nextval('app.\"Orders_no_seq\"'::regclass) NOT NULL;\n\
+             ALTER SEQUENCE app.\"Orders_no_seq\" OWNED BY public.users.order_no;"
+        );
+    }
+
+    #[test]
+    fn test_removed_primary_key_column() {
+        let mut users = TableSchema::test(
+            "users",
+            vec![
+                ColumnInfo::test("id", "integer", false),
+                ColumnInfo::test("email", "text", false),
+            ],
+        );
+        users.constraints.push(ConstraintInfo {
+            name: "users_pkey".to_string(),
+            constraint_type: ConstraintType::PrimaryKey,
+            columns: vec!["id".to_string()],
+            expression: None,
+            definition: "PRIMARY KEY (id)".to_string(),
+        });
+        let from = vec![users];
+
+        // 主键列被删除，主键改为 email
+        let mut users = TableSchema::test("users", vec![ColumnInfo::test("email", "text", false)]);
+        users.primary_keys = vec!["email".to_string()];
+        let to = vec![users];
+
+        let sql = diff_schemas(&from, &to).to_migration_sql();
+        assert_eq!(
+            sql,
+            "ALTER TABLE public.users DROP CONSTRAINT users_pkey;\n\
+             ALTER TABLE public.users DROP COLUMN id;\n\
+             ALTER TABLE public.users ADD PRIMARY KEY (email);"
+        );
+    }
+
+    #[test]
+    fn test_constraint_and_index_changes() {
+        let from = vec![TableSchema::test("users", vec![ColumnInfo::test("id", "integer", false)])];
+
+        let mut users = TableSchema::test("users", vec![ColumnInfo::test("id", "integer", false)]);
+        users.constraints.push(ConstraintInfo {
+            name: "users_id_check".to_string(),
+            constraint_type: ConstraintType::Check,
+            columns: vec!["id".to_string()],
+            expression: Some("(id > 0)".to_string()),
+            definition: "CHECK ((id > 0))".to_string(),
+        });
+        users.indexes.push(IndexInfo {
+            name: "idx_users_id_hash".to_string(),
+            columns: vec!["id".to_string()],
+            keys: vec![],
+            include_columns: vec![],
+            predicate: None,
+            is_unique: false,
+            is_primary: false,
+            is_valid: true,
+            index_type: "hash".to_string(),
+            size_bytes: 0,
+            definition: String::new(),
+        });
+        let to = vec![users];
+
+        let diff = diff_schemas(&from, &to);
+        let sql = diff.to_migration_sql();
+
+        assert!(sql
+            .contains("ALTER TABLE public.users ADD CONSTRAINT users_id_check CHECK ((id > 0));"));
+        assert!(sql.contains("CREATE INDEX idx_users_id_hash ON public.users USING hash (id);"));
+
+        // 反向比较生成删除语句
+        let reverse = diff_schemas(&to, &from).to_migration_sql();
+        assert!(reverse.contains("ALTER TABLE public.users DROP CONSTRAINT users_id_check;"));
+        assert!(reverse.contains("DROP INDEX public.idx_users_id_hash;"));
+    }
+
+    #[test]
+    fn test_create_index_sql_from_keys() {
+        let index = IndexInfo {
+            name: "idx_events_recent".to_string(),
+            columns: vec!["created_at".to_string()],
+            keys: vec![
+                IndexKey {
+                    column: Some("created_at".to_string()),
+                    expression: None,
+                    descending: true,
+                    nulls_first: false,
+                },
+                IndexKey {
+                    column: None,
+                    expression: Some("lower(kind)".to_string()),
+                    descending: false,
+                    nulls_first: false,
+                },
+            ],
+            include_columns: vec!["payload".to_string()],
+            predicate: Some("(archived = false)".to_string()),
+            is_unique: false,
+            is_primary: false,
+            is_valid: true,
+            index_type: "btree".to_string(),
+            size_bytes: 0,
+            definition: String::new(),
+        };
+
+        assert_eq!(
+            create_index_sql("public", "events", &index),
+            "CREATE INDEX idx_events_recent ON public.events USING btree \
+             (created_at DESC NULLS LAST, (lower(kind))) INCLUDE (payload) WHERE (archived = false);"
+        );
+    }
+
+    #[test]
+    fn test_quote_ident() {
+        assert_eq!(quote_ident("users"), "users");
+        assert_eq!(quote_ident("user"), "\"user\"");
+        assert_eq!(quote_ident("CamelCase"), "\"CamelCase\"");
+        assert_eq!(quote_ident("with space"), "\"with space\"");
+        assert_eq!(quote_ident("quo\"te"), "\"quo\"\"te\"");
+    }
+}
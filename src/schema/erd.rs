@@ -0,0 +1,407 @@
+// ERD - 实体关系图导出
+// 根据表、列、主键和外键生成 Mermaid `erDiagram` 文本或 Graphviz DOT，支持只导出部分表及其 N 跳以内的相邻表
+
+use super::types::{ColumnInfo, ForeignKeyInfo, TableSchema};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, VecDeque};
+
+/// 图的输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErdFormat {
+    /// Mermaid `erDiagram`
+    Mermaid,
+    /// Graphviz DOT
+    Dot,
+}
+
+impl ErdFormat {
+    /// 从查询参数解析
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "mermaid" => Some(Self::Mermaid),
+            "dot" => Some(Self::Dot),
+            _ => None,
+        }
+    }
+
+    /// 响应的 Content-Type
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Mermaid => "text/plain; charset=utf-8",
+            Self::Dot => "text/vnd.graphviz; charset=utf-8",
+        }
+    }
+}
+
+/// 选择要导出的表
+///
+/// `roots` 为空时返回所有表；否则返回 `roots` 以及沿外键（两个方向）
+/// 不超过 `hops` 跳的相邻表，结果按表名排序
+pub fn select_tables<'a>(
+    tables: &'a [TableSchema],
+    roots: &[String],
+    hops: usize,
+) -> Result<Vec<&'a TableSchema>> {
+    let mut selected: BTreeSet<&str> = BTreeSet::new();
+
+    if roots.is_empty() {
+        selected.extend(tables.iter().map(|t| t.name.as_str()));
+    } else {
+        let mut queue = VecDeque::new();
+        for root in roots {
+            let table = tables
+                .iter()
+                .find(|t| &t.name == root)
+                .ok_or_else(|| anyhow!("Table '{}' does not exist", root))?;
+            if selected.insert(&table.name) {
+                queue.push_back((table, 0));
+            }
+        }
+
+        while let Some((table, distance)) = queue.pop_front() {
+            if distance >= hops {
+                continue;
+            }
+            for neighbor in tables.iter().filter(|other| is_adjacent(table, other)) {
+                if selected.insert(&neighbor.name) {
+                    queue.push_back((neighbor, distance + 1));
+                }
+            }
+        }
+    }
+
+    let mut result: Vec<&TableSchema> = tables
+        .iter()
+        .filter(|t| selected.contains(t.name.as_str()))
+        .collect();
+    result.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(result)
+}
+
+/// 生成指定格式的实体关系图
+pub fn render_erd(tables: &[&TableSchema], format: ErdFormat) -> String {
+    match format {
+        ErdFormat::Mermaid => to_mermaid(tables),
+        ErdFormat::Dot => to_dot(tables),
+    }
+}
+
+/// 生成 Mermaid `erDiagram`
+///
+/// 外键列都在主键中时为识别关系（实线），否则为非识别关系（虚线）；
+/// 外键列可空时父表一侧为 0..1，外键列唯一时子表一侧为 0..1
+pub fn to_mermaid(tables: &[&TableSchema]) -> String {
+    let mut out = String::from("erDiagram\n");
+
+    for table in tables {
+        out.push_str(&format!("    {} {{\n", mermaid_name(&table.name)));
+        for column in &table.columns {
+            out.push_str(&format!(
+                "        {} {}",
+                mermaid_type(column),
+                mermaid_name(&column.name)
+            ));
+            let keys = column_keys(table, column);
+            if !keys.is_empty() {
+                out.push_str(&format!(" {}", keys.join(", ")));
+            }
+            if let Some(comment) = &column.comment {
+                out.push_str(&format!(" {}", mermaid_string(comment)));
+            }
+            out.push('\n');
+        }
+        out.push_str("    }\n");
+    }
+
+    for (table, fk) in foreign_keys_between(tables) {
+        let columns = fk.local_columns();
+        let child = if table.is_unique_key(&columns) {
+            "|o"
+        } else {
+            "}o"
+        };
+        let parent = if is_optional(table, &columns) {
+            "o|"
+        } else {
+            "||"
+        };
+        let line = if columns.iter().all(|c| table.is_primary_key(c)) {
+            "--"
+        } else {
+            ".."
+        };
+        out.push_str(&format!(
+            "    {} {}{}{} {} : {}\n",
+            mermaid_name(&table.name),
+            child,
+            line,
+            parent,
+            mermaid_name(&fk.foreign_table_name),
+            mermaid_string(&fk.constraint_name)
+        ));
+    }
+
+    out
+}
+
+/// 生成 Graphviz DOT（HTML 表格节点，外键边连接到具体的列）
+pub fn to_dot(tables: &[&TableSchema]) -> String {
+    let mut out = String::from("digraph erd {\n");
+    out.push_str("    graph [rankdir=LR];\n");
+    out.push_str("    node [shape=plaintext, fontname=\"Helvetica\"];\n");
+    out.push_str("    edge [fontname=\"Helvetica\", fontsize=10, dir=both];\n");
+
+    for table in tables {
+        out.push('\n');
+        out.push_str(&format!("    {} [label=<\n", dot_id(&table.name)));
+        out.push_str("        <table border=\"0\" cellborder=\"1\" cellspacing=\"0\">\n");
+        out.push_str(&format!(
+            "            <tr><td colspan=\"3\" bgcolor=\"lightgrey\"><b>{}</b></td></tr>\n",
+            html_escape(&table.name)
+        ));
+        for column in &table.columns {
+            let name = if table.is_primary_key(&column.name) {
+                format!("<u>{}</u>", html_escape(&column.name))
+            } else {
+                html_escape(&column.name)
+            };
+            out.push_str(&format!(
+                "            <tr><td align=\"left\">{}</td><td align=\"left\" port=\"{}\">{}</td>\
+                 <td align=\"left\">{}</td></tr>\n",
+                column_keys(table, column).join(", "),
+                html_escape(&column.name),
+                name,
+                html_escape(&column.sql_type())
+            ));
+        }
+        out.push_str("        </table>\n");
+        out.push_str("    >];\n");
+    }
+
+    let relationships = foreign_keys_between(tables);
+    if !relationships.is_empty() {
+        out.push('\n');
+    }
+    for (table, fk) in relationships {
+        let columns = fk.local_columns();
+        let foreign_columns = fk.foreign_columns();
+        let tail = if table.is_unique_key(&columns) {
+            "teeodot"
+        } else {
+            "crowodot"
+        };
+        let head = if is_optional(table, &columns) {
+            "teeodot"
+        } else {
+            "teetee"
+        };
+        out.push_str(&format!(
+            "    {}:{} -> {}:{} [label={}, arrowtail={}, arrowhead={}];\n",
+            dot_id(&table.name),
+            dot_id(columns.first().copied().unwrap_or_default()),
+            dot_id(&fk.foreign_table_name),
+            dot_id(foreign_columns.first().copied().unwrap_or_default()),
+            dot_id(&fk.constraint_name),
+            tail,
+            head
+        ));
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+/// 两个表之间是否有外键（任一方向，同一 schema）
+fn is_adjacent(a: &TableSchema, b: &TableSchema) -> bool {
+    let references = |from: &TableSchema, to: &TableSchema| {
+        from.foreign_keys
+            .iter()
+            .any(|fk| fk.foreign_schema == to.schema && fk.foreign_table_name == to.name)
+    };
+    a.name != b.name && (references(a, b) || references(b, a))
+}
+
+/// 两端都在图中的外键
+fn foreign_keys_between<'a>(
+    tables: &[&'a TableSchema],
+) -> Vec<(&'a TableSchema, &'a ForeignKeyInfo)> {
+    tables
+        .iter()
+        .flat_map(|table| table.foreign_keys.iter().map(move |fk| (*table, fk)))
+        .filter(|(table, fk)| {
+            tables.iter().any(|t| {
+                t.schema == fk.foreign_schema
+                    && t.name == fk.foreign_table_name
+                    && t.schema == table.schema
+            })
+        })
+        .collect()
+}
+
+/// 外键列中是否有可空列（可空时父表一侧为 0..1）
+fn is_optional(table: &TableSchema, columns: &[&str]) -> bool {
+    columns
+        .iter()
+        .any(|c| table.get_column(c).is_none_or(|column| column.is_nullable))
+}
+
+/// 列的键标记：PK、FK、UK
+fn column_keys(table: &TableSchema, column: &ColumnInfo) -> Vec<&'static str> {
+    let mut keys = Vec::new();
+    if table.is_primary_key(&column.name) {
+        keys.push("PK");
+    }
+    if table
+        .foreign_keys
+        .iter()
+        .any(|fk| fk.local_columns().contains(&column.name.as_str()))
+    {
+        keys.push("FK");
+    }
+    if !table.is_primary_key(&column.name) && table.is_unique_key(&[column.name.as_str()]) {
+        keys.push("UK");
+    }
+    keys
+}
+
+/// Mermaid 中的类型名（不能包含空格，使用 udt 名称）
+fn mermaid_type(column: &ColumnInfo) -> String {
+    match (column.is_array(), column.udt_name.strip_prefix('_')) {
+        (true, Some(element)) => format!("{}[]", element),
+        _ => column.udt_name.clone(),
+    }
+}
+
+/// Mermaid 中的实体名和属性名，包含特殊字符时加引号
+fn mermaid_name(name: &str) -> String {
+    if !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        name.to_string()
+    } else {
+        mermaid_string(name)
+    }
+}
+
+/// Mermaid 字符串（不支持转义，双引号替换为单引号）
+fn mermaid_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "'").replace('\n', " "))
+}
+
+/// DOT 标识符（总是加引号）
+fn dot_id(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// HTML 标签中的文本转义
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(
+        name: &str,
+        columns: Vec<ColumnInfo>,
+        foreign_keys: Vec<ForeignKeyInfo>,
+    ) -> TableSchema {
+        TableSchema {
+            foreign_keys,
+            ..TableSchema::test(name, columns)
+        }
+    }
+
+    /// orgs <- users <- posts <- comments
+    fn chain() -> Vec<TableSchema> {
+        vec![
+            table(
+                "comments",
+                vec![
+                    ColumnInfo::test("id", "int4", false),
+                    ColumnInfo::test("post_id", "int4", false),
+                ],
+                vec![ForeignKeyInfo::test("comments_post_id_fkey", &[("post_id", "id")], "posts")],
+            ),
+            table("orgs", vec![ColumnInfo::test("id", "int4", false)], vec![]),
+            table(
+                "posts",
+                vec![
+                    ColumnInfo::test("id", "int4", false),
+                    ColumnInfo::test("author_id", "int4", true),
+                ],
+                vec![ForeignKeyInfo::test("posts_author_id_fkey", &[("author_id", "id")], "users")],
+            ),
+            table(
+                "users",
+                vec![ColumnInfo::test("id", "int4", false), ColumnInfo::test("org_id", "int4", false)],
+                vec![ForeignKeyInfo::test("users_org_id_fkey", &[("org_id", "id")], "orgs")],
+            ),
+        ]
+    }
+
+    fn names(tables: &[&TableSchema]) -> Vec<String> {
+        tables.iter().map(|t| t.name.clone()).collect()
+    }
+
+    #[test]
+    fn test_select_tables() {
+        let tables = chain();
+
+        let all = select_tables(&tables, &[], 0).unwrap_or_default();
+        assert_eq!(names(&all), ["comments", "orgs", "posts", "users"]);
+
+        let roots = vec!["posts".to_string()];
+        let only = select_tables(&tables, &roots, 0).unwrap_or_default();
+        assert_eq!(names(&only), ["posts"]);
+
+        let one_hop = select_tables(&tables, &roots, 1).unwrap_or_default();
+        assert_eq!(names(&one_hop), ["comments", "posts", "users"]);
+
+        let two_hops = select_tables(&tables, &roots, 2).unwrap_or_default();
+        assert_eq!(names(&two_hops), ["comments", "orgs", "posts", "users"]);
+
+        assert!(select_tables(&tables, &["missing".to_string()], 1).is_err());
+    }
+
+    #[test]
+    fn test_mermaid() {
+        let tables = chain();
+        let selected = select_tables(&tables, &["posts".to_string()], 1).unwrap_or_default();
+        let mermaid = to_mermaid(&selected);
+
+        assert!(mermaid.starts_with("erDiagram\n"));
+        assert!(
+            mermaid.contains("    posts {\n        int4 id PK\n        int4 author_id FK\n    }\n")
+        );
+        assert!(mermaid.contains("    posts }o..o| users : \"posts_author_id_fkey\"\n"));
+        assert!(mermaid.contains("    comments }o..|| posts : \"comments_post_id_fkey\"\n"));
+        // orgs 不在图中，users -> orgs 的外键不输出
+        assert!(!mermaid.contains("users_org_id_fkey"));
+    }
+
+    #[test]
+    fn test_dot() {
+        let tables = chain();
+        let selected = select_tables(&tables, &[], 0).unwrap_or_default();
+        let dot = to_dot(&selected);
+
+        assert!(dot.starts_with("digraph erd {\n"));
+        assert!(dot.contains("    \"users\" [label=<\n"));
+        assert!(dot.contains("<td align=\"left\" port=\"id\"><u>id</u></td>"));
+        assert!(dot.contains(
+            "    \"posts\":\"author_id\" -> \"users\":\"id\" \
+             [label=\"posts_author_id_fkey\", arrowtail=crowodot, arrowhead=teeodot];\n"
+        ));
+        assert!(dot.ends_with("}\n"));
+    }
+}
@@ -1,36 +1,116 @@
 // Schema Inspector - 数据库结构检查器
 // 用于读取 PostgreSQL 数据库的表结构信息
 
-use super::types::{ColumnInfo, ForeignKeyInfo, IndexInfo, SchemaOverview, TableSchema};
-use anyhow::{Context, Result};
+use super::types::{
+    ArgumentMode, ColumnInfo, ColumnPrivileges, CompositeField, ConstraintInfo, ConstraintType,
+    DomainCheck, ExtensionInfo, ForeignKeyColumn, ForeignKeyInfo, FunctionArgument, FunctionInfo,
+    FunctionKind, IdentityGeneration, IndexInfo, IndexKey, ParentTable, PartitionInfo,
+    PartitionStrategy, PartitioningInfo, PolicyCommand, PolicyInfo, Privilege, RelationKind,
+    RelationSummary, RolePrivileges, RowSecurityInfo, SchemaInfo, SchemaOverview, SequenceInfo,
+    SequenceOwner, TableSchema, TriggerEnabled, TriggerEvent, TriggerInfo, TriggerTiming,
+    UserTypeInfo, UserTypeKind, ViewInfo, Volatility,
+};
+use anyhow::{anyhow, Context, Result};
 use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+
+/// 获取数据库中所有非系统 schema
+///
+/// 排除 `pg_catalog`、`information_schema`、TOAST 和临时 schema
+pub async fn get_schemas(pool: &PgPool) -> Result<Vec<SchemaInfo>> {
+    let rows = sqlx::query(
+        "SELECT
+            n.nspname::text AS schema_name,
+            pg_get_userbyid(n.nspowner)::text AS owner,
+            COUNT(c.oid) FILTER (WHERE c.relkind IN ('r', 'p') AND NOT c.relispartition) AS table_count,
+            COUNT(c.oid) FILTER (WHERE c.relkind IN ('v', 'm')) AS view_count,
+            obj_description(n.oid, 'pg_namespace') AS comment
+         FROM pg_namespace n
+         LEFT JOIN pg_class c ON c.relnamespace = n.oid
+         WHERE n.nspname NOT IN ('pg_catalog', 'information_schema')
+           AND n.nspname NOT LIKE 'pg\\_toast%'
+           AND n.nspname NOT LIKE 'pg\\_temp\\_%'
+         GROUP BY n.oid, n.nspname, n.nspowner
+         ORDER BY n.nspname",
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to fetch schemas")?;
+
+    let schemas = rows
+        .iter()
+        .map(|row| SchemaInfo {
+            name: row.get("schema_name"),
+            owner: row.get("owner"),
+            table_count: row.get("table_count"),
+            view_count: row.get("view_count"),
+            comment: row.get("comment"),
+        })
+        .collect();
+
+    Ok(schemas)
+}
 
 /// 获取指定 schema 下的所有表名
 ///
+/// 包含普通表、视图、物化视图、外部表和分区表；分区不单独列出，见 `TableSchema::partitioning`
+///
 /// # Arguments
 /// * `pool` - PostgreSQL 连接池
 /// * `schema_name` - Schema 名称，默认为 "public"
 pub async fn get_all_tables(pool: &PgPool, schema_name: Option<&str>) -> Result<Vec<String>> {
+    let relations = get_relations(pool, schema_name).await?;
+
+    Ok(relations.into_iter().map(|r| r.name).collect())
+}
+
+/// 获取指定 schema 下的所有关系（名称 + 类型）
+///
+/// # Arguments
+/// * `pool` - PostgreSQL 连接池
+/// * `schema_name` - Schema 名称，默认为 "public"
+pub async fn get_relations(
+    pool: &PgPool,
+    schema_name: Option<&str>,
+) -> Result<Vec<RelationSummary>> {
     let schema = schema_name.unwrap_or("public");
 
     let rows = sqlx::query(
-        "SELECT table_name 
-         FROM information_schema.tables 
-         WHERE table_schema = $1 
-           AND table_type = 'BASE TABLE'
-         ORDER BY table_name",
+        "SELECT
+            c.relname AS table_name,
+            c.relkind::text AS relkind,
+            ARRAY(
+                SELECT child.relname::text
+                FROM pg_inherits i
+                JOIN pg_class child ON child.oid = i.inhrelid
+                WHERE i.inhparent = c.oid AND child.relispartition
+                ORDER BY child.relname
+            ) AS partitions
+         FROM pg_class c
+         JOIN pg_namespace n ON n.oid = c.relnamespace
+         WHERE n.nspname = $1
+           AND c.relkind IN ('r', 'v', 'm', 'f', 'p')
+           AND NOT c.relispartition
+         ORDER BY c.relname",
     )
     .bind(schema)
     .fetch_all(pool)
     .await
     .context("Failed to fetch table names")?;
 
-    let tables = rows
+    let relations = rows
         .iter()
-        .map(|row| row.get::<String, _>("table_name"))
+        .filter_map(|row| {
+            let kind = RelationKind::from_relkind(&row.get::<String, _>("relkind"))?;
+            Some(RelationSummary {
+                name: row.get("table_name"),
+                kind,
+                partitions: row.get("partitions"),
+            })
+        })
         .collect();
 
-    Ok(tables)
+    Ok(relations)
 }
 
 /// 获取 schema 概览信息
@@ -39,13 +119,50 @@ pub async fn get_schema_overview(
     schema_name: Option<&str>,
 ) -> Result<SchemaOverview> {
     let schema = schema_name.unwrap_or("public");
-    let tables = get_all_tables(pool, Some(schema)).await?;
+    let relations = get_relations(pool, Some(schema)).await?;
+    let tables: Vec<String> = relations.iter().map(|r| r.name.clone()).collect();
+    let table_count = tables.len();
+
+    Ok(SchemaOverview {
+        name: schema.to_string(),
+        tables,
+        table_count,
+        relations,
+        table_schemas: Vec::new(),
+        table_stats: Vec::new(),
+    })
+}
+
+/// 获取包含所有表完整结构的 schema 概览（使用批量加载）
+pub async fn get_schema_overview_detailed(
+    pool: &PgPool,
+    schema_name: Option<&str>,
+) -> Result<SchemaOverview> {
+    let schema = schema_name.unwrap_or("public");
+    let table_schemas = get_all_table_schemas(pool, Some(schema)).await?;
+
+    let relations: Vec<RelationSummary> = table_schemas
+        .iter()
+        .map(|t| RelationSummary {
+            name: t.name.clone(),
+            kind: t.kind,
+            partitions: t
+                .partitioning
+                .iter()
+                .flat_map(|p| p.partitions.iter().map(|child| child.name.clone()))
+                .collect(),
+        })
+        .collect();
+    let tables: Vec<String> = relations.iter().map(|r| r.name.clone()).collect();
     let table_count = tables.len();
 
     Ok(SchemaOverview {
         name: schema.to_string(),
         tables,
         table_count,
+        relations,
+        table_schemas,
+        table_stats: Vec::new(),
     })
 }
 
@@ -62,49 +179,242 @@ pub async fn get_table_schema(
 ) -> Result<TableSchema> {
     let schema = schema_name.unwrap_or("public");
 
-    // 查询列信息
-    let columns = get_columns(pool, table_name, schema).await?;
+    load_table_schemas(pool, schema, Some(table_name))
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("Relation '{}.{}' does not exist", schema, table_name))
+}
+
+/// 批量获取 schema 下所有表的完整结构信息
+///
+/// 每类元数据（关系、列、外键、索引、约束）对整个 schema 只查询一次，
+/// 然后在内存中按表组装，避免逐表、逐列查询。
+/// 分区不单独返回，而是列在所属分区表的 `partitioning` 中（按名称仍可单独查询）
+///
+/// # Arguments
+/// * `pool` - PostgreSQL 连接池
+/// * `schema_name` - Schema 名称，默认为 "public"
+pub async fn get_all_table_schemas(
+    pool: &PgPool,
+    schema_name: Option<&str>,
+) -> Result<Vec<TableSchema>> {
+    let schema = schema_name.unwrap_or("public");
+    load_table_schemas(pool, schema, None).await
+}
 
-    // 查询主键
-    let primary_keys = get_primary_keys(pool, table_name, schema).await?;
+/// 加载表结构，`table_name` 为 None 时加载整个 schema
+async fn load_table_schemas(
+    pool: &PgPool,
+    schema: &str,
+    table_name: Option<&str>,
+) -> Result<Vec<TableSchema>> {
+    // 查询关系（类型、注释、视图定义），同时确认关系存在
+    let relations = fetch_relation_details(pool, schema, table_name).await?;
+    if relations.is_empty() {
+        return Ok(Vec::new());
+    }
 
-    // 查询外键
-    let foreign_keys = get_foreign_keys(pool, table_name, schema).await?;
+    // 列、外键、索引、约束、触发器、策略、权限、分区互不依赖，并发查询
+    let (
+        mut columns,
+        mut foreign_keys,
+        mut indexes,
+        mut constraints,
+        mut triggers,
+        mut policies,
+        mut privileges,
+        (mut partitioning, mut parents),
+    ) = tokio::try_join!(
+        fetch_columns(pool, schema, table_name),
+        fetch_foreign_keys(pool, schema, table_name),
+        fetch_indexes(pool, schema, table_name),
+        fetch_constraints(pool, schema, table_name),
+        fetch_triggers(pool, schema, table_name),
+        fetch_policies(pool, schema, table_name),
+        fetch_privileges(pool, schema, table_name),
+        fetch_partitioning(pool, schema, table_name),
+    )?;
 
-    // 查询索引
-    let indexes = get_indexes(pool, table_name, schema).await?;
+    let tables = relations
+        .into_iter()
+        .map(|relation| {
+            let constraints = constraints.remove(&relation.name).unwrap_or_default();
 
-    // 查询表注释
-    let comment = get_table_comment(pool, table_name, schema).await?;
+            // 主键列取自主键约束（按约束定义顺序）
+            let primary_keys = constraints
+                .iter()
+                .find(|c| c.constraint_type == ConstraintType::PrimaryKey)
+                .map(|c| c.columns.clone())
+                .unwrap_or_default();
 
-    Ok(TableSchema {
-        name: table_name.to_string(),
-        schema: schema.to_string(),
-        columns,
-        primary_keys,
-        foreign_keys,
-        indexes,
-        comment,
-    })
+            TableSchema {
+                schema: schema.to_string(),
+                kind: relation.kind,
+                columns: columns.remove(&relation.name).unwrap_or_default(),
+                primary_keys,
+                foreign_keys: foreign_keys.remove(&relation.name).unwrap_or_default(),
+                indexes: indexes.remove(&relation.name).unwrap_or_default(),
+                constraints,
+                comment: relation.comment,
+                view: relation.view,
+                triggers: triggers.remove(&relation.name).unwrap_or_default(),
+                row_security: RowSecurityInfo {
+                    enabled: relation.rls_enabled,
+                    forced: relation.rls_forced,
+                    policies: policies.remove(&relation.name).unwrap_or_default(),
+                },
+                privileges: privileges.remove(&relation.name).unwrap_or_default(),
+                partitioning: partitioning.remove(&relation.name),
+                parents: parents.remove(&relation.name).unwrap_or_default(),
+                name: relation.name,
+            }
+        })
+        .collect();
+
+    Ok(tables)
+}
+
+/// 关系的基本信息（组装 TableSchema 用）
+struct RelationDetail {
+    name: String,
+    kind: RelationKind,
+    comment: Option<String>,
+    view: Option<ViewInfo>,
+    rls_enabled: bool,
+    rls_forced: bool,
 }
 
-/// 获取表的所有列信息
-async fn get_columns(pool: &PgPool, table_name: &str, schema: &str) -> Result<Vec<ColumnInfo>> {
+/// 查询关系类型、注释和视图定义
+///
+/// 可更新性使用与 `information_schema.views` 相同的判断方式：
+/// UPDATE 和 DELETE 都可执行才算可更新
+async fn fetch_relation_details(
+    pool: &PgPool,
+    schema: &str,
+    table_name: Option<&str>,
+) -> Result<Vec<RelationDetail>> {
     let rows = sqlx::query(
-        "SELECT 
-            column_name,
-            data_type,
-            udt_name,
-            is_nullable,
-            column_default,
-            is_identity,
-            character_maximum_length,
-            numeric_precision,
-            numeric_scale,
-            ordinal_position
-        FROM information_schema.columns
-        WHERE table_schema = $1 AND table_name = $2
-        ORDER BY ordinal_position",
+        "SELECT
+            c.relname::text AS table_name,
+            c.relkind::text AS relkind,
+            obj_description(c.oid, 'pg_class') AS comment,
+            CASE WHEN c.relkind IN ('v', 'm') THEN pg_get_viewdef(c.oid, true) END AS view_definition,
+            (pg_relation_is_updatable(c.oid, false) & 20) = 20 AS is_updatable,
+            (pg_relation_is_updatable(c.oid, false) & 8) = 8 AS is_insertable_into,
+            c.relrowsecurity AS rls_enabled,
+            c.relforcerowsecurity AS rls_forced
+         FROM pg_class c
+         JOIN pg_namespace n ON n.oid = c.relnamespace
+         WHERE n.nspname = $1
+           AND (c.relname = $2 OR ($2::text IS NULL AND NOT c.relispartition))
+           AND c.relkind IN ('r', 'v', 'm', 'f', 'p')
+         ORDER BY c.relname",
+    )
+    .bind(schema)
+    .bind(table_name)
+    .fetch_all(pool)
+    .await
+    .context("Failed to fetch relation information")?;
+
+    let relations = rows
+        .iter()
+        .filter_map(|row| {
+            let kind = RelationKind::from_relkind(&row.get::<String, _>("relkind"))?;
+            let view = if kind.is_view() {
+                Some(ViewInfo {
+                    definition: row
+                        .get::<Option<String>, _>("view_definition")
+                        .unwrap_or_default(),
+                    is_updatable: row.get("is_updatable"),
+                    is_insertable_into: row.get("is_insertable_into"),
+                })
+            } else {
+                None
+            };
+
+            Some(RelationDetail {
+                name: row.get("table_name"),
+                kind,
+                comment: row.get("comment"),
+                view,
+                rls_enabled: row.get("rls_enabled"),
+                rls_forced: row.get("rls_forced"),
+            })
+        })
+        .collect();
+
+    Ok(relations)
+}
+
+/// 获取列信息，按表名分组
+///
+/// 直接读取 `pg_attribute`（`information_schema.columns` 不包含物化视图），
+/// 类型、长度、精度的计算方式与 `information_schema.columns` 保持一致，
+/// 列注释在同一查询中读取
+async fn fetch_columns(
+    pool: &PgPool,
+    schema: &str,
+    table_name: Option<&str>,
+) -> Result<HashMap<String, Vec<ColumnInfo>>> {
+    let rows = sqlx::query(
+        "SELECT
+            c.relname::text AS table_name,
+            a.attname AS column_name,
+            CASE
+                WHEN t.typtype = 'd' THEN
+                    CASE
+                        WHEN bt.typelem <> 0 AND bt.typlen = -1 THEN 'ARRAY'
+                        WHEN nbt.nspname = 'pg_catalog' THEN format_type(t.typbasetype, NULL)
+                        ELSE 'USER-DEFINED'
+                    END
+                ELSE
+                    CASE
+                        WHEN t.typelem <> 0 AND t.typlen = -1 THEN 'ARRAY'
+                        WHEN nt.nspname = 'pg_catalog' THEN format_type(a.atttypid, NULL)
+                        ELSE 'USER-DEFINED'
+                    END
+            END AS data_type,
+            COALESCE(bt.typname, t.typname)::text AS udt_name,
+            NOT (a.attnotnull OR (t.typtype = 'd' AND t.typnotnull)) AS is_nullable,
+            CASE WHEN a.attgenerated = '' THEN pg_get_expr(ad.adbin, ad.adrelid) END AS column_default,
+            a.attidentity <> '' AS is_identity,
+            a.attidentity::text AS attidentity,
+            information_schema._pg_char_max_length(
+                information_schema._pg_truetypid(a.*, t.*),
+                information_schema._pg_truetypmod(a.*, t.*)
+            )::int4 AS character_maximum_length,
+            information_schema._pg_numeric_precision(
+                information_schema._pg_truetypid(a.*, t.*),
+                information_schema._pg_truetypmod(a.*, t.*)
+            )::int4 AS numeric_precision,
+            information_schema._pg_numeric_scale(
+                information_schema._pg_truetypid(a.*, t.*),
+                information_schema._pg_truetypmod(a.*, t.*)
+            )::int4 AS numeric_scale,
+            a.attnum::int4 AS ordinal_position,
+            col_description(a.attrelid, a.attnum) AS comment,
+            CASE WHEN et.oid IS NOT NULL THEN format_type(et.oid, NULL) END AS element_type,
+            CASE WHEN et.oid IS NOT NULL THEN GREATEST(a.attndims, 1)::int4 END AS array_dimensions,
+            CASE
+                WHEN t.typtype IN ('e', 'd', 'c') THEN t.oid
+                WHEN et.typtype IN ('e', 'd', 'c') THEN et.oid
+            END::int8 AS user_type_oid
+        FROM pg_attribute a
+        JOIN pg_class c ON c.oid = a.attrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        JOIN pg_type t ON t.oid = a.atttypid
+        JOIN pg_namespace nt ON nt.oid = t.typnamespace
+        LEFT JOIN pg_type et ON t.typelem <> 0 AND t.typlen = -1 AND et.oid = t.typelem
+        LEFT JOIN pg_attrdef ad ON ad.adrelid = a.attrelid AND ad.adnum = a.attnum
+        LEFT JOIN (pg_type bt JOIN pg_namespace nbt ON nbt.oid = bt.typnamespace)
+          ON t.typtype = 'd' AND t.typbasetype = bt.oid
+        WHERE n.nspname = $1
+          AND (c.relname = $2 OR ($2::text IS NULL AND NOT c.relispartition))
+          AND c.relkind IN ('r', 'v', 'm', 'f', 'p')
+          AND a.attnum > 0
+          AND NOT a.attisdropped
+        ORDER BY c.relname, a.attnum",
     )
     .bind(schema)
     .bind(table_name)
@@ -112,86 +422,269 @@ async fn get_columns(pool: &PgPool, table_name: &str, schema: &str) -> Result<Ve
     .await
     .context("Failed to fetch column information")?;
 
-    let mut columns = Vec::new();
+    // 解析列引用的用户定义类型（枚举、域、复合类型）
+    let mut user_type_oids: Vec<i64> = rows
+        .iter()
+        .filter_map(|row| row.get::<Option<i64>, _>("user_type_oid"))
+        .collect();
+    user_type_oids.sort_unstable();
+    user_type_oids.dedup();
 
-    for row in rows {
-        let column_name: String = row.get("column_name");
-        
-        // 查询列注释
-        let comment = get_column_comment(pool, table_name, schema, &column_name).await?;
+    let user_types = if user_type_oids.is_empty() {
+        HashMap::new()
+    } else {
+        fetch_user_types(pool, UserTypeFilter::Oids(&user_type_oids)).await?
+    };
 
-        columns.push(ColumnInfo {
-            name: column_name,
+    let mut columns: HashMap<String, Vec<ColumnInfo>> = HashMap::new();
+    for row in &rows {
+        columns.entry(row.get("table_name")).or_default().push(ColumnInfo {
+            name: row.get("column_name"),
             data_type: row.get("data_type"),
             udt_name: row.get("udt_name"),
-            is_nullable: row.get::<String, _>("is_nullable") == "YES",
+            is_nullable: row.get("is_nullable"),
             default_value: row.get("column_default"),
-            is_identity: row.get::<String, _>("is_identity") == "YES",
+            is_identity: row.get("is_identity"),
+            identity_generation: IdentityGeneration::from_attidentity(
+                &row.get::<String, _>("attidentity"),
+            ),
             max_length: row.get("character_maximum_length"),
             numeric_precision: row.get("numeric_precision"),
             numeric_scale: row.get("numeric_scale"),
             ordinal_position: row.get("ordinal_position"),
-            comment,
+            comment: row.get("comment"),
+            element_type: row.get("element_type"),
+            array_dimensions: row.get("array_dimensions"),
+            user_type: row
+                .get::<Option<i64>, _>("user_type_oid")
+                .and_then(|oid| user_types.get(&oid).cloned()),
         });
     }
 
     Ok(columns)
 }
 
-/// 获取表的主键列
-async fn get_primary_keys(pool: &PgPool, table_name: &str, schema: &str) -> Result<Vec<String>> {
-    let rows = sqlx::query(
-        "SELECT kcu.column_name
-         FROM information_schema.table_constraints tc
-         JOIN information_schema.key_column_usage kcu 
-           ON tc.constraint_name = kcu.constraint_name
-           AND tc.table_schema = kcu.table_schema
-         WHERE tc.constraint_type = 'PRIMARY KEY'
-           AND tc.table_schema = $1
-           AND tc.table_name = $2
-         ORDER BY kcu.ordinal_position",
+/// 获取指定 schema 下的所有用户定义类型（枚举、域、复合类型）
+///
+/// 表和视图隐式生成的行类型不包含在内
+///
+/// # Arguments
+/// * `pool` - PostgreSQL 连接池
+/// * `schema_name` - Schema 名称，默认为 "public"
+pub async fn get_user_types(pool: &PgPool, schema_name: Option<&str>) -> Result<Vec<UserTypeInfo>> {
+    let schema = schema_name.unwrap_or("public");
+
+    let mut types: Vec<UserTypeInfo> = fetch_user_types(pool, UserTypeFilter::Schema(schema))
+        .await?
+        .into_values()
+        .collect();
+    types.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(types)
+}
+
+/// 用户定义类型的查询范围
+enum UserTypeFilter<'a> {
+    /// 某个 schema 下独立定义的类型
+    Schema(&'a str),
+    /// 按类型 OID 查询（用于解析列类型）
+    Oids(&'a [i64]),
+}
+
+/// 查询用户定义类型及其枚举值、域约束和复合字段，按类型 OID 返回
+async fn fetch_user_types(
+    pool: &PgPool,
+    filter: UserTypeFilter<'_>,
+) -> Result<HashMap<i64, UserTypeInfo>> {
+    let base_query = "SELECT
+            t.oid::int8 AS oid,
+            n.nspname::text AS schema_name,
+            t.typname::text AS type_name,
+            t.typtype::text AS typtype,
+            CASE WHEN t.typtype = 'd' THEN format_type(t.typbasetype, t.typtypmod) END AS base_type,
+            t.typnotnull AS not_null,
+            t.typdefault AS default_value,
+            obj_description(t.oid, 'pg_type') AS comment
+        FROM pg_type t
+        JOIN pg_namespace n ON n.oid = t.typnamespace
+        LEFT JOIN pg_class c ON c.oid = t.typrelid
+        WHERE t.typtype IN ('e', 'd', 'c')";
+
+    let rows = match filter {
+        UserTypeFilter::Schema(schema) => {
+            sqlx::query(&format!(
+                "{} AND n.nspname = $1 AND (t.typtype <> 'c' OR c.relkind = 'c')",
+                base_query
+            ))
+            .bind(schema)
+            .fetch_all(pool)
+            .await
+        }
+        UserTypeFilter::Oids(oids) => {
+            sqlx::query(&format!("{} AND t.oid::int8 = ANY($1)", base_query))
+                .bind(oids)
+                .fetch_all(pool)
+                .await
+        }
+    }
+    .context("Failed to fetch user-defined types")?;
+
+    let mut types: HashMap<i64, UserTypeInfo> = HashMap::new();
+    for row in &rows {
+        let Some(kind) = UserTypeKind::from_typtype(&row.get::<String, _>("typtype")) else {
+            continue;
+        };
+        types.insert(
+            row.get("oid"),
+            UserTypeInfo {
+                schema: row.get("schema_name"),
+                name: row.get("type_name"),
+                kind,
+                enum_labels: Vec::new(),
+                base_type: row.get("base_type"),
+                not_null: row.get("not_null"),
+                default_value: row.get("default_value"),
+                checks: Vec::new(),
+                fields: Vec::new(),
+                comment: row.get("comment"),
+            },
+        );
+    }
+
+    if types.is_empty() {
+        return Ok(types);
+    }
+
+    let oids: Vec<i64> = types.keys().copied().collect();
+
+    // 枚举值（按 enumsortorder 排序）
+    let enum_rows = sqlx::query(
+        "SELECT enumtypid::int8 AS type_oid, enumlabel::text AS label
+         FROM pg_enum
+         WHERE enumtypid::int8 = ANY($1)
+         ORDER BY enumtypid, enumsortorder",
     )
-    .bind(schema)
-    .bind(table_name)
+    .bind(&oids)
     .fetch_all(pool)
     .await
-    .context("Failed to fetch primary keys")?;
+    .context("Failed to fetch enum labels")?;
 
-    let primary_keys = rows
-        .iter()
-        .map(|row| row.get::<String, _>("column_name"))
-        .collect();
+    for row in &enum_rows {
+        if let Some(t) = types.get_mut(&row.get::<i64, _>("type_oid")) {
+            t.enum_labels.push(row.get("label"));
+        }
+    }
 
-    Ok(primary_keys)
+    // 域的 CHECK 约束
+    let check_rows = sqlx::query(
+        "SELECT contypid::int8 AS type_oid, conname::text AS name,
+                pg_get_constraintdef(oid) AS expression
+         FROM pg_constraint
+         WHERE contype = 'c' AND contypid::int8 = ANY($1)
+         ORDER BY contypid, conname",
+    )
+    .bind(&oids)
+    .fetch_all(pool)
+    .await
+    .context("Failed to fetch domain constraints")?;
+
+    for row in &check_rows {
+        if let Some(t) = types.get_mut(&row.get::<i64, _>("type_oid")) {
+            t.checks.push(DomainCheck {
+                name: row.get("name"),
+                expression: row.get("expression"),
+            });
+        }
+    }
+
+    // 复合类型字段
+    let field_rows = sqlx::query(
+        "SELECT t.oid::int8 AS type_oid, a.attname::text AS name,
+                format_type(a.atttypid, a.atttypmod) AS data_type,
+                a.attnum::int4 AS position
+         FROM pg_type t
+         JOIN pg_attribute a ON a.attrelid = t.typrelid
+         WHERE t.typtype = 'c'
+           AND t.oid::int8 = ANY($1)
+           AND a.attnum > 0
+           AND NOT a.attisdropped
+         ORDER BY t.oid, a.attnum",
+    )
+    .bind(&oids)
+    .fetch_all(pool)
+    .await
+    .context("Failed to fetch composite type fields")?;
+
+    for row in &field_rows {
+        if let Some(t) = types.get_mut(&row.get::<i64, _>("type_oid")) {
+            t.fields.push(CompositeField {
+                name: row.get("name"),
+                data_type: row.get("data_type"),
+                position: row.get("position"),
+            });
+        }
+    }
+
+    Ok(types)
 }
 
-/// 获取表的外键约束
-async fn get_foreign_keys(
+/// 获取外键约束，按表名分组
+///
+/// 从 `pg_constraint` 读取，按 `conkey`/`confkey` 的顺序配对本表列和引用列，
+/// 复合外键作为一条记录返回
+async fn fetch_foreign_keys(
     pool: &PgPool,
-    table_name: &str,
     schema: &str,
-) -> Result<Vec<ForeignKeyInfo>> {
+    table_name: Option<&str>,
+) -> Result<HashMap<String, Vec<ForeignKeyInfo>>> {
     let rows = sqlx::query(
         "SELECT
-            tc.constraint_name,
-            kcu.column_name,
-            ccu.table_name AS foreign_table_name,
-            ccu.column_name AS foreign_column_name,
-            rc.delete_rule AS on_delete,
-            rc.update_rule AS on_update
-        FROM information_schema.table_constraints AS tc
-        JOIN information_schema.key_column_usage AS kcu
-          ON tc.constraint_name = kcu.constraint_name
-          AND tc.table_schema = kcu.table_schema
-        JOIN information_schema.constraint_column_usage AS ccu
-          ON ccu.constraint_name = tc.constraint_name
-          AND ccu.table_schema = tc.table_schema
-        JOIN information_schema.referential_constraints AS rc
-          ON tc.constraint_name = rc.constraint_name
-          AND tc.table_schema = rc.constraint_schema
-        WHERE tc.constraint_type = 'FOREIGN KEY'
-          AND tc.table_schema = $1
-          AND tc.table_name = $2",
+            c.relname::text AS table_name,
+            con.conname::text AS constraint_name,
+            fn.nspname::text AS foreign_schema,
+            fc.relname::text AS foreign_table_name,
+            ARRAY(
+                SELECT a.attname::text
+                FROM unnest(con.conkey) WITH ORDINALITY AS k(attnum, ord)
+                JOIN pg_attribute a ON a.attrelid = con.conrelid AND a.attnum = k.attnum
+                ORDER BY k.ord
+            ) AS column_names,
+            ARRAY(
+                SELECT a.attname::text
+                FROM unnest(con.confkey) WITH ORDINALITY AS k(attnum, ord)
+                JOIN pg_attribute a ON a.attrelid = con.confrelid AND a.attnum = k.attnum
+                ORDER BY k.ord
+            ) AS foreign_column_names,
+            CASE con.confdeltype
+                WHEN 'c' THEN 'CASCADE'
+                WHEN 'n' THEN 'SET NULL'
+                WHEN 'd' THEN 'SET DEFAULT'
+                WHEN 'r' THEN 'RESTRICT'
+                ELSE 'NO ACTION'
+            END AS on_delete,
+            CASE con.confupdtype
+                WHEN 'c' THEN 'CASCADE'
+                WHEN 'n' THEN 'SET NULL'
+                WHEN 'd' THEN 'SET DEFAULT'
+                WHEN 'r' THEN 'RESTRICT'
+                ELSE 'NO ACTION'
+            END AS on_update,
+            CASE con.confmatchtype
+                WHEN 'f' THEN 'FULL'
+                WHEN 'p' THEN 'PARTIAL'
+                ELSE 'SIMPLE'
+            END AS match_type,
+            con.condeferrable AS is_deferrable,
+            con.condeferred AS initially_deferred
+        FROM pg_constraint con
+        JOIN pg_class c ON c.oid = con.conrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        JOIN pg_class fc ON fc.oid = con.confrelid
+        JOIN pg_namespace fn ON fn.oid = fc.relnamespace
+        WHERE con.contype = 'f'
+          AND n.nspname = $1
+          AND (c.relname = $2 OR ($2::text IS NULL AND NOT c.relispartition))
+        ORDER BY c.relname, con.conname",
     )
     .bind(schema)
     .bind(table_name)
@@ -199,40 +692,91 @@ async fn get_foreign_keys(
     .await
     .context("Failed to fetch foreign keys")?;
 
-    let foreign_keys = rows
-        .iter()
-        .map(|row| ForeignKeyInfo {
-            constraint_name: row.get("constraint_name"),
-            column_name: row.get("column_name"),
-            foreign_table_name: row.get("foreign_table_name"),
-            foreign_column_name: row.get("foreign_column_name"),
-            on_delete: row.get("on_delete"),
-            on_update: row.get("on_update"),
-        })
-        .collect();
+    let mut foreign_keys: HashMap<String, Vec<ForeignKeyInfo>> = HashMap::new();
+    for row in &rows {
+        let column_names: Vec<String> = row.get("column_names");
+        let foreign_column_names: Vec<String> = row.get("foreign_column_names");
+
+        foreign_keys
+            .entry(row.get("table_name"))
+            .or_default()
+            .push(ForeignKeyInfo {
+                constraint_name: row.get("constraint_name"),
+                columns: column_names
+                    .into_iter()
+                    .zip(foreign_column_names)
+                    .map(|(column_name, foreign_column_name)| ForeignKeyColumn {
+                        column_name,
+                        foreign_column_name,
+                    })
+                    .collect(),
+                foreign_schema: row.get("foreign_schema"),
+                foreign_table_name: row.get("foreign_table_name"),
+                on_delete: row.get("on_delete"),
+                on_update: row.get("on_update"),
+                match_type: row.get("match_type"),
+                is_deferrable: row.get("is_deferrable"),
+                initially_deferred: row.get("initially_deferred"),
+            });
+    }
 
     Ok(foreign_keys)
 }
 
-/// 获取表的索引信息
-async fn get_indexes(pool: &PgPool, table_name: &str, schema: &str) -> Result<Vec<IndexInfo>> {
+/// 获取索引信息，按表名分组
+async fn fetch_indexes(
+    pool: &PgPool,
+    schema: &str,
+    table_name: Option<&str>,
+) -> Result<HashMap<String, Vec<IndexInfo>>> {
+    // indkey 中 0 表示表达式键；前 indnkeyatts 个是索引键，其余是 INCLUDE 列
+    // indoption 每个键的第 0 位表示 DESC，第 1 位表示 NULLS FIRST
     let rows = sqlx::query(
         "SELECT
-            i.relname AS index_name,
+            t.relname::text AS table_name,
+            i.relname::text AS index_name,
             ix.indisunique AS is_unique,
             ix.indisprimary AS is_primary,
-            am.amname AS index_type,
-            ARRAY_AGG(a.attname ORDER BY a.attnum) AS column_names
+            ix.indisvalid AS is_valid,
+            am.amname::text AS index_type,
+            pg_get_expr(ix.indpred, ix.indrelid, true) AS predicate,
+            pg_relation_size(i.oid) AS size_bytes,
+            pg_get_indexdef(ix.indexrelid) AS definition,
+            k.key_columns,
+            k.key_expressions,
+            k.key_descending,
+            k.key_nulls_first,
+            k.include_columns
         FROM pg_class t
         JOIN pg_index ix ON t.oid = ix.indrelid
         JOIN pg_class i ON i.oid = ix.indexrelid
         JOIN pg_am am ON i.relam = am.oid
         JOIN pg_namespace n ON n.oid = t.relnamespace
-        JOIN pg_attribute a ON a.attrelid = t.oid AND a.attnum = ANY(ix.indkey)
-        WHERE t.relkind = 'r'
+        CROSS JOIN LATERAL (
+            SELECT
+                ARRAY_AGG(a.attname::text ORDER BY key.ord)
+                    FILTER (WHERE key.ord <= ix.indnkeyatts) AS key_columns,
+                ARRAY_AGG(
+                    CASE WHEN key.attnum = 0
+                        THEN pg_get_indexdef(ix.indexrelid, key.ord::int, true)
+                    END
+                    ORDER BY key.ord
+                ) FILTER (WHERE key.ord <= ix.indnkeyatts) AS key_expressions,
+                ARRAY_AGG(COALESCE((key.option & 1) = 1, false) ORDER BY key.ord)
+                    FILTER (WHERE key.ord <= ix.indnkeyatts) AS key_descending,
+                ARRAY_AGG(COALESCE((key.option & 2) = 2, false) ORDER BY key.ord)
+                    FILTER (WHERE key.ord <= ix.indnkeyatts) AS key_nulls_first,
+                ARRAY_AGG(a.attname::text ORDER BY key.ord)
+                    FILTER (WHERE key.ord > ix.indnkeyatts) AS include_columns
+            FROM unnest(ix.indkey::int2[], ix.indoption::int2[])
+                WITH ORDINALITY AS key(attnum, option, ord)
+            LEFT JOIN pg_attribute a
+                ON a.attrelid = ix.indrelid AND a.attnum = key.attnum AND key.attnum <> 0
+        ) k
+        WHERE t.relkind IN ('r', 'm', 'p')
           AND n.nspname = $1
-          AND t.relname = $2
-        GROUP BY i.relname, ix.indisunique, ix.indisprimary, am.amname",
+          AND (t.relname = $2 OR ($2::text IS NULL AND NOT t.relispartition))
+        ORDER BY t.relname, i.relname",
     )
     .bind(schema)
     .bind(table_name)
@@ -240,73 +784,720 @@ async fn get_indexes(pool: &PgPool, table_name: &str, schema: &str) -> Result<Ve
     .await
     .context("Failed to fetch indexes")?;
 
-    let indexes = rows
-        .iter()
-        .map(|row| IndexInfo {
+    let mut indexes: HashMap<String, Vec<IndexInfo>> = HashMap::new();
+    for row in &rows {
+        let key_columns: Vec<Option<String>> =
+            row.get::<Option<Vec<Option<String>>>, _>("key_columns").unwrap_or_default();
+        let key_expressions: Vec<Option<String>> =
+            row.get::<Option<Vec<Option<String>>>, _>("key_expressions").unwrap_or_default();
+        let key_descending: Vec<bool> =
+            row.get::<Option<Vec<bool>>, _>("key_descending").unwrap_or_default();
+        let key_nulls_first: Vec<bool> =
+            row.get::<Option<Vec<bool>>, _>("key_nulls_first").unwrap_or_default();
+
+        let keys: Vec<IndexKey> = key_columns
+            .into_iter()
+            .zip(key_expressions)
+            .zip(key_descending.into_iter().zip(key_nulls_first))
+            .map(|((column, expression), (descending, nulls_first))| IndexKey {
+                column,
+                expression,
+                descending,
+                nulls_first,
+            })
+            .collect();
+
+        indexes.entry(row.get("table_name")).or_default().push(IndexInfo {
             name: row.get("index_name"),
-            columns: row.get::<Vec<String>, _>("column_names"),
+            columns: keys.iter().filter_map(|k| k.column.clone()).collect(),
+            keys,
+            include_columns: row
+                .get::<Option<Vec<String>>, _>("include_columns")
+                .unwrap_or_default(),
+            predicate: row.get("predicate"),
             is_unique: row.get("is_unique"),
             is_primary: row.get("is_primary"),
+            is_valid: row.get("is_valid"),
             index_type: row.get("index_type"),
-        })
-        .collect();
+            size_bytes: row.get("size_bytes"),
+            definition: row.get("definition"),
+        });
+    }
 
     Ok(indexes)
 }
 
-/// 获取表注释
-async fn get_table_comment(
+/// 获取触发器（不含外键等内部触发器），按表名分组
+async fn fetch_triggers(
     pool: &PgPool,
-    table_name: &str,
     schema: &str,
-) -> Result<Option<String>> {
-    let row = sqlx::query(
-        "SELECT obj_description(pg_class.oid) AS comment
-         FROM pg_class
-         JOIN pg_namespace ON pg_namespace.oid = pg_class.relnamespace
-         WHERE pg_namespace.nspname = $1 AND pg_class.relname = $2",
+    table_name: Option<&str>,
+) -> Result<HashMap<String, Vec<TriggerInfo>>> {
+    let rows = sqlx::query(
+        "SELECT
+            c.relname::text AS table_name,
+            t.tgname::text AS trigger_name,
+            t.tgtype::int AS tgtype,
+            t.tgenabled::text AS tgenabled,
+            ARRAY(
+                SELECT a.attname::text
+                FROM unnest(t.tgattr::int2[]) WITH ORDINALITY AS k(attnum, ord)
+                JOIN pg_attribute a ON a.attrelid = t.tgrelid AND a.attnum = k.attnum
+                ORDER BY k.ord
+            ) AS update_columns,
+            pn.nspname::text AS function_schema,
+            p.proname::text AS function_name,
+            pg_get_triggerdef(t.oid, true) AS definition
+        FROM pg_trigger t
+        JOIN pg_class c ON c.oid = t.tgrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        JOIN pg_proc p ON p.oid = t.tgfoid
+        JOIN pg_namespace pn ON pn.oid = p.pronamespace
+        WHERE n.nspname = $1
+          AND (c.relname = $2 OR ($2::text IS NULL AND NOT c.relispartition))
+          AND NOT t.tgisinternal
+        ORDER BY c.relname, t.tgname",
     )
     .bind(schema)
     .bind(table_name)
-    .fetch_optional(pool)
+    .fetch_all(pool)
     .await
-    .context("Failed to fetch table comment")?;
+    .context("Failed to fetch triggers")?;
+
+    let mut triggers: HashMap<String, Vec<TriggerInfo>> = HashMap::new();
+    for row in &rows {
+        let tgtype: i32 = row.get("tgtype");
+        let Some(enabled) = TriggerEnabled::from_tgenabled(&row.get::<String, _>("tgenabled"))
+        else {
+            continue;
+        };
+        let definition: String = row.get("definition");
+
+        triggers.entry(row.get("table_name")).or_default().push(TriggerInfo {
+            name: row.get("trigger_name"),
+            timing: TriggerTiming::from_tgtype(tgtype),
+            events: TriggerEvent::from_tgtype(tgtype),
+            for_each_row: tgtype & 1 != 0,
+            update_columns: row.get("update_columns"),
+            condition: trigger_condition(&definition),
+            function_schema: row.get("function_schema"),
+            function_name: row.get("function_name"),
+            enabled,
+            definition,
+        });
+    }
 
-    Ok(row.and_then(|r| r.get("comment")))
+    Ok(triggers)
 }
 
-/// 获取列注释
-async fn get_column_comment(
+/// 从触发器定义中提取 WHEN 条件
+///
+/// `pg_trigger.tgqual` 的表达式引用 OLD/NEW 伪关系，无法用 `pg_get_expr` 反解析，
+/// 只能从 `pg_get_triggerdef` 的输出中截取
+fn trigger_condition(definition: &str) -> Option<String> {
+    let start = definition.find(" WHEN (")? + " WHEN (".len();
+    let end = definition.rfind(") EXECUTE ")?;
+    definition.get(start..end).map(|c| c.to_string())
+}
+
+/// 获取 RLS 策略，按表名分组
+async fn fetch_policies(
     pool: &PgPool,
-    table_name: &str,
     schema: &str,
-    column_name: &str,
-) -> Result<Option<String>> {
-    let row = sqlx::query(
-        "SELECT col_description(
-            (SELECT oid FROM pg_class WHERE relname = $2 AND relnamespace = (
-                SELECT oid FROM pg_namespace WHERE nspname = $1
+    table_name: Option<&str>,
+) -> Result<HashMap<String, Vec<PolicyInfo>>> {
+    let rows = sqlx::query(
+        "SELECT
+            c.relname::text AS table_name,
+            pol.polname::text AS policy_name,
+            pol.polcmd::text AS polcmd,
+            pol.polpermissive AS permissive,
+            ARRAY(
+                SELECT CASE WHEN r.oid = 0 THEN 'public' ELSE pg_get_userbyid(r.oid)::text END
+                FROM unnest(pol.polroles) AS r(oid)
+                ORDER BY 1
+            ) AS roles,
+            pg_get_expr(pol.polqual, pol.polrelid, true) AS using_expression,
+            pg_get_expr(pol.polwithcheck, pol.polrelid, true) AS with_check_expression
+        FROM pg_policy pol
+        JOIN pg_class c ON c.oid = pol.polrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE n.nspname = $1
+          AND (c.relname = $2 OR ($2::text IS NULL AND NOT c.relispartition))
+        ORDER BY c.relname, pol.polname",
+    )
+    .bind(schema)
+    .bind(table_name)
+    .fetch_all(pool)
+    .await
+    .context("Failed to fetch policies")?;
+
+    let mut policies: HashMap<String, Vec<PolicyInfo>> = HashMap::new();
+    for row in &rows {
+        let Some(command) = PolicyCommand::from_polcmd(&row.get::<String, _>("polcmd")) else {
+            continue;
+        };
+
+        policies.entry(row.get("table_name")).or_default().push(PolicyInfo {
+            name: row.get("policy_name"),
+            command,
+            roles: row.get("roles"),
+            using_expression: row.get("using_expression"),
+            with_check_expression: row.get("with_check_expression"),
+            permissive: row.get("permissive"),
+        });
+    }
+
+    Ok(policies)
+}
+
+/// 获取每个角色的有效权限，按表名分组
+///
+/// 使用 `has_table_privilege` / `has_column_privilege` 计算，结果包含通过角色继承
+/// 和 PUBLIC 获得的权限（`information_schema` 的权限视图只列出直接授权，
+/// 且只对当前用户可见的角色生效）。列级权限只存在于设置了 `attacl` 的列上
+async fn fetch_privileges(
+    pool: &PgPool,
+    schema: &str,
+    table_name: Option<&str>,
+) -> Result<HashMap<String, Vec<RolePrivileges>>> {
+    let table_privilege_names: Vec<&str> = Privilege::TABLE.iter().map(|p| p.as_sql()).collect();
+    let column_privilege_names: Vec<&str> =
+        Privilege::COLUMN.iter().map(|p| p.as_sql()).collect();
+
+    let table_rows = sqlx::query(
+        "SELECT table_name, role_name, privileges
+        FROM (
+            SELECT
+                c.relname::text AS table_name,
+                r.rolname::text AS role_name,
+                ARRAY(
+                    SELECT p FROM unnest($3::text[]) AS p
+                    WHERE has_table_privilege(r.oid, c.oid, p)
+                ) AS privileges
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            CROSS JOIN pg_roles r
+            WHERE n.nspname = $1
+              AND (c.relname = $2 OR ($2::text IS NULL AND NOT c.relispartition))
+              AND c.relkind IN ('r', 'v', 'm', 'f', 'p')
+              AND r.rolname !~ '^pg_'
+        ) t
+        WHERE cardinality(privileges) > 0
+        ORDER BY table_name, role_name",
+    )
+    .bind(schema)
+    .bind(table_name)
+    .bind(&table_privilege_names)
+    .fetch_all(pool)
+    .await
+    .context("Failed to fetch table privileges")?;
+
+    let column_rows = sqlx::query(
+        "SELECT table_name, role_name, column_name, privileges
+        FROM (
+            SELECT
+                c.relname::text AS table_name,
+                r.rolname::text AS role_name,
+                a.attname::text AS column_name,
+                a.attnum,
+                ARRAY(
+                    SELECT p FROM unnest($3::text[]) AS p
+                    WHERE has_column_privilege(r.oid, c.oid, a.attnum, p)
+                      AND NOT has_table_privilege(r.oid, c.oid, p)
+                ) AS privileges
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            JOIN pg_attribute a ON a.attrelid = c.oid
+            CROSS JOIN pg_roles r
+            WHERE n.nspname = $1
+              AND (c.relname = $2 OR ($2::text IS NULL AND NOT c.relispartition))
+              AND c.relkind IN ('r', 'v', 'm', 'f', 'p')
+              AND a.attnum > 0
+              AND NOT a.attisdropped
+              AND a.attacl IS NOT NULL
+              AND r.rolname !~ '^pg_'
+        ) t
+        WHERE cardinality(privileges) > 0
+        ORDER BY table_name, role_name, attnum",
+    )
+    .bind(schema)
+    .bind(table_name)
+    .bind(&column_privilege_names)
+    .fetch_all(pool)
+    .await
+    .context("Failed to fetch column privileges")?;
+
+    let parse = |names: Vec<String>| -> Vec<Privilege> {
+        names.iter().filter_map(|n| Privilege::from_name(n)).collect()
+    };
+
+    // (表名, 角色名) -> 权限，保持查询顺序
+    let mut entries: Vec<(String, RolePrivileges)> = table_rows
+        .iter()
+        .map(|row| {
+            (
+                row.get("table_name"),
+                RolePrivileges {
+                    role: row.get("role_name"),
+                    table_privileges: parse(row.get("privileges")),
+                    column_privileges: Vec::new(),
+                },
+            )
+        })
+        .collect();
+
+    for row in &column_rows {
+        let table: String = row.get("table_name");
+        let role: String = row.get("role_name");
+        let column = ColumnPrivileges {
+            column: row.get("column_name"),
+            privileges: parse(row.get("privileges")),
+        };
+
+        match entries
+            .iter_mut()
+            .find(|(t, p)| *t == table && p.role == role)
+        {
+            Some((_, privileges)) => privileges.column_privileges.push(column),
+            None => entries.push((
+                table,
+                RolePrivileges {
+                    role,
+                    table_privileges: Vec::new(),
+                    column_privileges: vec![column],
+                },
             )),
-            (SELECT ordinal_position FROM information_schema.columns 
-             WHERE table_schema = $1 AND table_name = $2 AND column_name = $3)
-         ) AS comment",
+        }
+    }
+
+    let mut privileges: HashMap<String, Vec<RolePrivileges>> = HashMap::new();
+    for (table, role_privileges) in entries {
+        privileges.entry(table).or_default().push(role_privileges);
+    }
+    for role_privileges in privileges.values_mut() {
+        role_privileges.sort_by(|a, b| a.role.cmp(&b.role));
+    }
+
+    Ok(privileges)
+}
+
+/// 获取约束（从 `pg_constraint` 读取），按表名分组
+async fn fetch_constraints(
+    pool: &PgPool,
+    schema: &str,
+    table_name: Option<&str>,
+) -> Result<HashMap<String, Vec<ConstraintInfo>>> {
+    let rows = sqlx::query(
+        "SELECT
+            c.relname::text AS table_name,
+            con.conname::text AS constraint_name,
+            con.contype::text AS contype,
+            ARRAY(
+                SELECT a.attname::text
+                FROM unnest(con.conkey) WITH ORDINALITY AS k(attnum, ord)
+                JOIN pg_attribute a ON a.attrelid = con.conrelid AND a.attnum = k.attnum
+                ORDER BY k.ord
+            ) AS column_names,
+            CASE WHEN con.contype = 'c' THEN pg_get_expr(con.conbin, con.conrelid) END AS expression,
+            pg_get_constraintdef(con.oid) AS definition
+        FROM pg_constraint con
+        JOIN pg_class c ON c.oid = con.conrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE n.nspname = $1
+          AND (c.relname = $2 OR ($2::text IS NULL AND NOT c.relispartition))
+          AND con.contype IN ('p', 'f', 'u', 'c')
+        ORDER BY c.relname, con.contype, con.conname",
+    )
+    .bind(schema)
+    .bind(table_name)
+    .fetch_all(pool)
+    .await
+    .context("Failed to fetch constraints")?;
+
+    let mut constraints: HashMap<String, Vec<ConstraintInfo>> = HashMap::new();
+    for row in &rows {
+        let Some(constraint_type) = ConstraintType::from_contype(&row.get::<String, _>("contype"))
+        else {
+            continue;
+        };
+        constraints
+            .entry(row.get("table_name"))
+            .or_default()
+            .push(ConstraintInfo {
+                name: row.get("constraint_name"),
+                constraint_type,
+                columns: row.get::<Vec<String>, _>("column_names"),
+                expression: row.get("expression"),
+                definition: row.get("definition"),
+            });
+    }
+
+    Ok(constraints)
+}
+
+/// 分区表的分区键和子分区（按表名），以及每个表的父表（按表名）
+type PartitioningMaps = (
+    HashMap<String, PartitioningInfo>,
+    HashMap<String, Vec<ParentTable>>,
+);
+
+/// 获取分区键、子分区和继承关系（从 `pg_partitioned_table` 和 `pg_inherits` 读取）
+async fn fetch_partitioning(
+    pool: &PgPool,
+    schema: &str,
+    table_name: Option<&str>,
+) -> Result<PartitioningMaps> {
+    let key_rows = sqlx::query(
+        "SELECT
+            c.relname::text AS table_name,
+            pt.partstrat::text AS partstrat,
+            pg_get_partkeydef(c.oid) AS partition_key,
+            ARRAY(
+                SELECT a.attname::text
+                FROM unnest(pt.partattrs::int2[]) WITH ORDINALITY AS k(attnum, ord)
+                JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum = k.attnum
+                ORDER BY k.ord
+            ) AS column_names
+        FROM pg_partitioned_table pt
+        JOIN pg_class c ON c.oid = pt.partrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE n.nspname = $1
+          AND (c.relname = $2 OR ($2::text IS NULL AND NOT c.relispartition))",
     )
     .bind(schema)
     .bind(table_name)
-    .bind(column_name)
-    .fetch_optional(pool)
+    .fetch_all(pool)
+    .await
+    .context("Failed to fetch partition keys")?;
+
+    // 继承关系的两端都可能是目标表：作为父表时列出子分区，作为子表时列出父表。
+    // pg_inherits 也记录分区索引的继承，只取表
+    let inherit_rows = sqlx::query(
+        "SELECT
+            pn.nspname::text AS parent_schema,
+            parent.relname::text AS parent_name,
+            cn.nspname::text AS child_schema,
+            child.relname::text AS child_name,
+            child.relispartition AS is_partition,
+            child.relkind = 'p' AS is_partitioned,
+            pg_get_expr(child.relpartbound, child.oid) AS partition_bound
+        FROM pg_inherits i
+        JOIN pg_class parent ON parent.oid = i.inhparent
+        JOIN pg_namespace pn ON pn.oid = parent.relnamespace
+        JOIN pg_class child ON child.oid = i.inhrelid
+        JOIN pg_namespace cn ON cn.oid = child.relnamespace
+        WHERE child.relkind IN ('r', 'f', 'p')
+          AND (
+              (pn.nspname = $1 AND ($2::text IS NULL OR parent.relname = $2))
+              OR (cn.nspname = $1 AND ($2::text IS NULL OR child.relname = $2))
+          )
+        ORDER BY child.relname, i.inhseqno",
+    )
+    .bind(schema)
+    .bind(table_name)
+    .fetch_all(pool)
+    .await
+    .context("Failed to fetch table inheritance")?;
+
+    let mut children: HashMap<String, Vec<PartitionInfo>> = HashMap::new();
+    let mut parents: HashMap<String, Vec<ParentTable>> = HashMap::new();
+    for row in &inherit_rows {
+        let parent_schema: String = row.get("parent_schema");
+        let parent_name: String = row.get("parent_name");
+        let child_schema: String = row.get("child_schema");
+        let child_name: String = row.get("child_name");
+        let is_partition: bool = row.get("is_partition");
+        let bound: Option<String> = row.get("partition_bound");
+
+        if is_partition && parent_schema == schema {
+            let bound = bound.clone().unwrap_or_default();
+            children.entry(parent_name.clone()).or_default().push(PartitionInfo {
+                schema: child_schema.clone(),
+                name: child_name.clone(),
+                is_default: bound == "DEFAULT",
+                bound,
+                is_partitioned: row.get("is_partitioned"),
+            });
+        }
+        if child_schema == schema {
+            parents.entry(child_name).or_default().push(ParentTable {
+                schema: parent_schema,
+                name: parent_name,
+                partition_bound: bound.filter(|_| is_partition),
+            });
+        }
+    }
+
+    let partitioning = key_rows
+        .iter()
+        .filter_map(|row| {
+            let strategy = PartitionStrategy::from_partstrat(&row.get::<String, _>("partstrat"))?;
+            let table_name: String = row.get("table_name");
+            let mut partitions = children.remove(&table_name).unwrap_or_default();
+            partitions.sort_by(|a, b| a.name.cmp(&b.name));
+            Some((
+                table_name,
+                PartitioningInfo {
+                    strategy,
+                    key: row.get("partition_key"),
+                    columns: row.get::<Vec<String>, _>("column_names"),
+                    partitions,
+                },
+            ))
+        })
+        .collect();
+
+    Ok((partitioning, parents))
+}
+
+/// 获取指定 schema 下的所有函数和存储过程
+///
+/// 扩展安装的函数不包含在内
+///
+/// # Arguments
+/// * `pool` - PostgreSQL 连接池
+/// * `schema_name` - Schema 名称，默认为 "public"
+pub async fn get_functions(pool: &PgPool, schema_name: Option<&str>) -> Result<Vec<FunctionInfo>> {
+    let schema = schema_name.unwrap_or("public");
+    fetch_functions(pool, schema, None).await
+}
+
+/// 获取指定名称的函数（可能有多个重载）
+///
+/// # Arguments
+/// * `pool` - PostgreSQL 连接池
+/// * `function_name` - 函数名
+/// * `schema_name` - Schema 名称，默认为 "public"
+pub async fn get_function(
+    pool: &PgPool,
+    function_name: &str,
+    schema_name: Option<&str>,
+) -> Result<Vec<FunctionInfo>> {
+    let schema = schema_name.unwrap_or("public");
+    fetch_functions(pool, schema, Some(function_name)).await
+}
+
+/// 查询函数及其参数，`function_name` 为 None 时返回 schema 下的全部函数
+async fn fetch_functions(
+    pool: &PgPool,
+    schema: &str,
+    function_name: Option<&str>,
+) -> Result<Vec<FunctionInfo>> {
+    let rows = sqlx::query(
+        "SELECT
+            p.oid::int8 AS oid,
+            p.proname::text AS function_name,
+            p.prokind::text AS prokind,
+            pg_get_function_identity_arguments(p.oid) AS identity_arguments,
+            pg_get_function_result(p.oid) AS return_type,
+            p.proretset AS returns_set,
+            p.provolatile::text AS provolatile,
+            p.prosecdef AS security_definer,
+            l.lanname::text AS language,
+            obj_description(p.oid, 'pg_proc') AS comment
+        FROM pg_proc p
+        JOIN pg_namespace n ON n.oid = p.pronamespace
+        JOIN pg_language l ON l.oid = p.prolang
+        WHERE n.nspname = $1
+          AND ($2::text IS NULL OR p.proname = $2)
+          AND NOT EXISTS (
+              SELECT 1 FROM pg_depend d
+              WHERE d.classid = 'pg_proc'::regclass
+                AND d.objid = p.oid
+                AND d.deptype = 'e'
+          )
+        ORDER BY p.proname, identity_arguments",
+    )
+    .bind(schema)
+    .bind(function_name)
+    .fetch_all(pool)
     .await
-    .context("Failed to fetch column comment")?;
+    .context("Failed to fetch functions")?;
+
+    if rows.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let oids: Vec<i64> = rows.iter().map(|row| row.get("oid")).collect();
+
+    // 参数：优先使用 proallargtypes（包含 OUT 参数），否则使用 proargtypes
+    let arg_rows = sqlx::query(
+        "SELECT
+            p.oid::int8 AS function_oid,
+            NULLIF(p.proargnames[k.ord], '')::text AS arg_name,
+            COALESCE(p.proargmodes[k.ord]::text, 'i') AS arg_mode,
+            format_type(k.type_oid, NULL) AS data_type,
+            pg_get_function_arg_default(p.oid, k.ord::int4) AS default_value
+        FROM pg_proc p
+        CROSS JOIN LATERAL unnest(COALESCE(p.proallargtypes, p.proargtypes::oid[]))
+            WITH ORDINALITY AS k(type_oid, ord)
+        WHERE p.oid::int8 = ANY($1)
+        ORDER BY p.oid, k.ord",
+    )
+    .bind(&oids)
+    .fetch_all(pool)
+    .await
+    .context("Failed to fetch function arguments")?;
+
+    let mut arguments: HashMap<i64, Vec<FunctionArgument>> = HashMap::new();
+    for row in &arg_rows {
+        let Some(mode) = ArgumentMode::from_argmode(&row.get::<String, _>("arg_mode")) else {
+            continue;
+        };
+        arguments
+            .entry(row.get("function_oid"))
+            .or_default()
+            .push(FunctionArgument {
+                name: row.get("arg_name"),
+                mode,
+                data_type: row.get("data_type"),
+                default_value: row.get("default_value"),
+            });
+    }
+
+    let functions = rows
+        .iter()
+        .filter_map(|row| {
+            let kind = FunctionKind::from_prokind(&row.get::<String, _>("prokind"))?;
+            let volatility = Volatility::from_provolatile(&row.get::<String, _>("provolatile"))?;
+            Some(FunctionInfo {
+                schema: schema.to_string(),
+                name: row.get("function_name"),
+                kind,
+                arguments: arguments.remove(&row.get::<i64, _>("oid")).unwrap_or_default(),
+                identity_arguments: row.get("identity_arguments"),
+                return_type: row.get("return_type"),
+                returns_set: row.get("returns_set"),
+                volatility,
+                security_definer: row.get("security_definer"),
+                language: row.get("language"),
+                comment: row.get("comment"),
+            })
+        })
+        .collect();
+
+    Ok(functions)
+}
+
+/// 获取指定 schema 下的所有序列
+///
+/// # Arguments
+/// * `pool` - PostgreSQL 连接池
+/// * `schema_name` - Schema 名称，默认为 "public"
+pub async fn get_sequences(pool: &PgPool, schema_name: Option<&str>) -> Result<Vec<SequenceInfo>> {
+    let schema = schema_name.unwrap_or("public");
+
+    // 当前值的读取方式与 pg_sequences 视图相同：没有权限时为 NULL 而不是报错；
+    // 所属列来自自动依赖（OWNED BY / serial）或内部依赖（标识列）
+    let rows = sqlx::query(
+        "SELECT
+            c.relname::text AS sequence_name,
+            format_type(s.seqtypid, NULL) AS data_type,
+            s.seqstart AS start_value,
+            s.seqmin AS min_value,
+            s.seqmax AS max_value,
+            s.seqincrement AS increment,
+            s.seqcycle AS cycle,
+            s.seqcache AS cache_size,
+            CASE
+                WHEN has_sequence_privilege(c.oid, 'SELECT,USAGE') THEN pg_sequence_last_value(c.oid)
+            END AS last_value,
+            tc.relname::text AS owner_table,
+            ta.attname::text AS owner_column,
+            d.deptype = 'i' AS is_identity,
+            obj_description(c.oid, 'pg_class') AS comment
+        FROM pg_sequence s
+        JOIN pg_class c ON c.oid = s.seqrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        LEFT JOIN pg_depend d
+          ON d.classid = 'pg_class'::regclass
+         AND d.objid = c.oid
+         AND d.refclassid = 'pg_class'::regclass
+         AND d.refobjsubid > 0
+         AND d.deptype IN ('a', 'i')
+        LEFT JOIN pg_class tc ON tc.oid = d.refobjid
+        LEFT JOIN pg_attribute ta ON ta.attrelid = d.refobjid AND ta.attnum = d.refobjsubid
+        WHERE n.nspname = $1
+        ORDER BY c.relname",
+    )
+    .bind(schema)
+    .fetch_all(pool)
+    .await
+    .context("Failed to fetch sequences")?;
+
+    let sequences = rows
+        .iter()
+        .map(|row| {
+            let owner_table: Option<String> = row.get("owner_table");
+            let owner_column: Option<String> = row.get("owner_column");
+            SequenceInfo {
+                schema: schema.to_string(),
+                name: row.get("sequence_name"),
+                data_type: row.get("data_type"),
+                start_value: row.get("start_value"),
+                min_value: row.get("min_value"),
+                max_value: row.get("max_value"),
+                increment: row.get("increment"),
+                cycle: row.get("cycle"),
+                cache_size: row.get("cache_size"),
+                last_value: row.get("last_value"),
+                owned_by: owner_table.zip(owner_column).map(|(table, column)| SequenceOwner {
+                    table,
+                    column,
+                    is_identity: row.get::<Option<bool>, _>("is_identity").unwrap_or_default(),
+                }),
+                comment: row.get("comment"),
+            }
+        })
+        .collect();
+
+    Ok(sequences)
+}
+
+/// 获取数据库中已安装的扩展及其版本
+pub async fn get_extensions(pool: &PgPool) -> Result<Vec<ExtensionInfo>> {
+    let rows = sqlx::query(
+        "SELECT
+            e.extname::text AS extension_name,
+            e.extversion AS version,
+            n.nspname::text AS schema_name,
+            a.default_version,
+            e.extrelocatable AS relocatable,
+            obj_description(e.oid, 'pg_extension') AS comment
+        FROM pg_extension e
+        JOIN pg_namespace n ON n.oid = e.extnamespace
+        LEFT JOIN pg_available_extensions a ON a.name = e.extname
+        ORDER BY e.extname",
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to fetch extensions")?;
+
+    let extensions = rows
+        .iter()
+        .map(|row| ExtensionInfo {
+            name: row.get("extension_name"),
+            version: row.get("version"),
+            schema: row.get("schema_name"),
+            default_version: row.get("default_version"),
+            relocatable: row.get("relocatable"),
+            comment: row.get("comment"),
+        })
+        .collect();
 
-    Ok(row.and_then(|r| r.get("comment")))
+    Ok(extensions)
 }
 
 /// 检查表是否存在
 pub async fn table_exists(pool: &PgPool, table_name: &str, schema: &str) -> Result<bool> {
     let row = sqlx::query(
         "SELECT EXISTS (
-            SELECT 1 FROM information_schema.tables 
-            WHERE table_schema = $1 AND table_name = $2
+            SELECT 1 FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = $1
+              AND c.relname = $2
+              AND c.relkind IN ('r', 'v', 'm', 'f', 'p')
         ) AS exists",
     )
     .bind(schema)
@@ -0,0 +1,327 @@
+// JSON Schema - 表结构导出为 JSON Schema (draft 2020-12)
+// 供前端做表单校验，按读取、插入、更新三种场景生成
+
+use super::{
+    pg_type::{JsonType, PgType},
+    types::{ColumnInfo, CompositeField, TableSchema, UserTypeKind},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+/// JSON Schema 方言
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// 生成的 JSON Schema 对应的使用场景
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaVariant {
+    /// 查询结果中的一行：所有列都会出现
+    Read,
+    /// 插入：只有不可空且没有默认值的列是必填的
+    Insert,
+    /// 更新：所有列都是可选的
+    Update,
+}
+
+impl SchemaVariant {
+    /// 所有场景
+    pub const ALL: [SchemaVariant; 3] = [Self::Read, Self::Insert, Self::Update];
+
+    /// 从查询参数解析
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "read" => Some(Self::Read),
+            "insert" => Some(Self::Insert),
+            "update" => Some(Self::Update),
+            _ => None,
+        }
+    }
+
+    /// 场景名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Insert => "insert",
+            Self::Update => "update",
+        }
+    }
+}
+
+/// 生成表在指定场景下的 JSON Schema 文档
+pub fn table_json_schema(table: &TableSchema, variant: SchemaVariant) -> Value {
+    let mut document = Map::new();
+    document.insert("$schema".to_string(), json!(JSON_SCHEMA_DIALECT));
+    document.insert(
+        "$id".to_string(),
+        json!(format!(
+            "orpheus:schema/{}/{}/{}",
+            table.schema,
+            table.name,
+            variant.as_str()
+        )),
+    );
+    document.insert(
+        "title".to_string(),
+        json!(format!("{} ({})", table.name, variant.as_str())),
+    );
+    if let Some(comment) = &table.comment {
+        document.insert("description".to_string(), json!(comment));
+    }
+    document.insert("type".to_string(), json!("object"));
+    document.insert(
+        "properties".to_string(),
+        Value::Object(table_properties(table)),
+    );
+
+    let required: Vec<&str> = match variant {
+        SchemaVariant::Read => table.columns.iter().map(|c| c.name.as_str()).collect(),
+        SchemaVariant::Insert => table
+            .required_columns()
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect(),
+        SchemaVariant::Update => Vec::new(),
+    };
+    if !required.is_empty() {
+        document.insert("required".to_string(), json!(required));
+    }
+    // 插入和更新时不允许出现表中不存在的列
+    if variant != SchemaVariant::Read {
+        document.insert("additionalProperties".to_string(), json!(false));
+    }
+
+    Value::Object(document)
+}
+
+/// 表中每一列的属性定义
+pub fn table_properties(table: &TableSchema) -> Map<String, Value> {
+    table
+        .columns
+        .iter()
+        .map(|column| (column.name.clone(), column_json_schema(column)))
+        .collect()
+}
+
+/// 单个列的 JSON Schema（包含可空性、长度限制和注释）
+pub fn column_json_schema(column: &ColumnInfo) -> Value {
+    let fields = column
+        .user_type
+        .as_ref()
+        .filter(|t| t.kind == UserTypeKind::Composite)
+        .map(|t| t.fields.as_slice())
+        .unwrap_or_default();
+    let mut schema = pg_type_json_schema(&column.pg_type(), fields);
+
+    if column.is_nullable {
+        allow_null(&mut schema);
+    }
+    if let Some(comment) = &column.comment {
+        schema.insert("description".to_string(), json!(comment));
+    }
+
+    Value::Object(schema)
+}
+
+/// 按类型名生成 JSON Schema（用于函数参数和返回值，不包含可空性）
+pub fn type_json_schema(type_name: &str) -> Value {
+    Value::Object(pg_type_json_schema(&PgType::from_name(type_name), &[]))
+}
+
+/// PostgreSQL 类型对应的 JSON Schema
+///
+/// `fields` 为复合类型的字段定义，未知时为空
+fn pg_type_json_schema(pg_type: &PgType, fields: &[CompositeField]) -> Map<String, Value> {
+    let mut schema = Map::new();
+    let resolved = pg_type.resolve();
+
+    match resolved {
+        PgType::Array {
+            element,
+            dimensions,
+        } => {
+            let mut items = pg_type_json_schema(element, fields);
+            for _ in 1..*dimensions {
+                let mut outer = Map::new();
+                outer.insert("type".to_string(), json!("array"));
+                outer.insert("items".to_string(), Value::Object(items));
+                items = outer;
+            }
+            schema.insert("type".to_string(), json!("array"));
+            schema.insert("items".to_string(), Value::Object(items));
+        }
+        PgType::Enum { values, .. } => {
+            schema.insert("type".to_string(), json!("string"));
+            schema.insert("enum".to_string(), json!(values));
+        }
+        PgType::Composite { .. } => {
+            schema.insert("type".to_string(), json!("object"));
+            if !fields.is_empty() {
+                let properties: Map<String, Value> = fields
+                    .iter()
+                    .map(|field| {
+                        let mut field_schema =
+                            pg_type_json_schema(&PgType::from_name(&field.data_type), &[]);
+                        allow_null(&mut field_schema);
+                        (field.name.clone(), Value::Object(field_schema))
+                    })
+                    .collect();
+                schema.insert("properties".to_string(), Value::Object(properties));
+            }
+        }
+        _ => {
+            if let Some(json_type) = json_type_name(resolved.json_type()) {
+                schema.insert("type".to_string(), json!(json_type));
+            }
+        }
+    }
+
+    // 只输出 JSON Schema 规范定义的格式，避免严格模式的校验器报错；
+    // date-time 和 time 格式要求带时区偏移，不带时区的时间戳以及时间类型不输出格式
+    let format = match resolved {
+        PgType::Timestamp {
+            with_time_zone: true,
+        } => Some("date-time"),
+        PgType::Date => Some("date"),
+        PgType::Uuid => Some("uuid"),
+        _ => None,
+    };
+    if let Some(format) = format {
+        schema.insert("format".to_string(), json!(format));
+    }
+
+    match resolved {
+        PgType::SmallInt => {
+            schema.insert("minimum".to_string(), json!(i16::MIN));
+            schema.insert("maximum".to_string(), json!(i16::MAX));
+        }
+        PgType::Integer => {
+            schema.insert("minimum".to_string(), json!(i32::MIN));
+            schema.insert("maximum".to_string(), json!(i32::MAX));
+        }
+        PgType::Varchar {
+            length: Some(length),
+        }
+        | PgType::Char {
+            length: Some(length),
+        }
+        | PgType::Bit {
+            length: Some(length),
+            ..
+        } => {
+            schema.insert("maxLength".to_string(), json!(length));
+        }
+        _ => {}
+    }
+
+    schema
+}
+
+/// 允许 null：在 `type` 中加入 "null"，枚举值中加入 null
+fn allow_null(schema: &mut Map<String, Value>) {
+    match schema.get_mut("type") {
+        Some(Value::String(json_type)) => {
+            let json_type = json_type.clone();
+            schema.insert("type".to_string(), json!([json_type, "null"]));
+        }
+        Some(Value::Array(types)) if !types.contains(&json!("null")) => types.push(json!("null")),
+        // 没有类型限制（json / jsonb）时本来就允许 null
+        _ => {}
+    }
+    if let Some(Value::Array(values)) = schema.get_mut("enum") {
+        values.push(Value::Null);
+    }
+}
+
+/// JSON Schema 的类型名，任意类型返回 None
+fn json_type_name(json_type: JsonType) -> Option<&'static str> {
+    match json_type {
+        JsonType::String => Some("string"),
+        JsonType::Integer => Some("integer"),
+        JsonType::Number => Some("number"),
+        JsonType::Boolean => Some("boolean"),
+        JsonType::Object => Some("object"),
+        JsonType::Array => Some("array"),
+        JsonType::Any => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::types::UserTypeInfo;
+
+    fn users_table() -> TableSchema {
+        let mut id = ColumnInfo::test("id", "integer", false);
+        id.is_identity = true;
+        let mut email = ColumnInfo::test("email", "character varying", false);
+        email.max_length = Some(255);
+        email.comment = Some("Login email".to_string());
+        let mut created_at = ColumnInfo::test("created_at", "timestamp with time zone", false);
+        created_at.default_value = Some("now()".to_string());
+        let last_seen_at = ColumnInfo::test("last_seen_at", "timestamp without time zone", true);
+        let wake_up_at = ColumnInfo::test("wake_up_at", "time with time zone", true);
+        let mut mood = ColumnInfo::test("mood", "USER-DEFINED", true);
+        mood.user_type = Some(UserTypeInfo::test_enum("mood", &["happy", "sad"]));
+        let mut tags = ColumnInfo::test("tags", "ARRAY", true);
+        tags.udt_name = "_text".to_string();
+        tags.element_type = Some("text".to_string());
+        tags.array_dimensions = Some(1);
+
+        let mut users = TableSchema::test(
+            "users",
+            vec![
+                id,
+                email,
+                created_at,
+                last_seen_at,
+                wake_up_at,
+                mood,
+                tags,
+                ColumnInfo::test("metadata", "jsonb", true),
+            ],
+        );
+        users.comment = Some("Application users".to_string());
+        users
+    }
+
+    #[test]
+    fn test_column_schemas() {
+        let schema = table_json_schema(&users_table(), SchemaVariant::Read);
+        let properties = &schema["properties"];
+
+        assert_eq!(schema["$schema"], JSON_SCHEMA_DIALECT);
+        assert_eq!(schema["description"], "Application users");
+        assert_eq!(properties["id"]["type"], "integer");
+        assert_eq!(properties["id"]["maximum"], i32::MAX);
+        assert_eq!(properties["email"]["type"], "string");
+        assert_eq!(properties["email"]["maxLength"], 255);
+        assert_eq!(properties["email"]["description"], "Login email");
+        assert_eq!(properties["created_at"]["format"], "date-time");
+        // 不带时区的时间戳不满足 date-time 格式，时间类型同样不输出格式
+        assert_eq!(properties["last_seen_at"]["type"], json!(["string", "null"]));
+        assert!(properties["last_seen_at"].get("format").is_none());
+        assert!(properties["wake_up_at"].get("format").is_none());
+        assert_eq!(properties["mood"]["type"], json!(["string", "null"]));
+        assert_eq!(properties["mood"]["enum"], json!(["happy", "sad", null]));
+        assert_eq!(properties["tags"]["type"], json!(["array", "null"]));
+        assert_eq!(properties["tags"]["items"]["type"], "string");
+        assert_eq!(properties["metadata"], json!({}));
+    }
+
+    #[test]
+    fn test_variants() {
+        let table = users_table();
+
+        let read = table_json_schema(&table, SchemaVariant::Read);
+        assert_eq!(read["required"].as_array().map(|r| r.len()), Some(8));
+        assert!(read.get("additionalProperties").is_none());
+
+        let insert = table_json_schema(&table, SchemaVariant::Insert);
+        assert_eq!(insert["required"], json!(["email"]));
+        assert_eq!(insert["additionalProperties"], false);
+
+        let update = table_json_schema(&table, SchemaVariant::Update);
+        assert!(update.get("required").is_none());
+        assert_eq!(update["title"], "users (update)");
+    }
+}
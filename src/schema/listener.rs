@@ -0,0 +1,472 @@
+// Listener - DDL 变更监听
+// 通过 event trigger 在 DDL 命令结束时发出 NOTIFY，后台任务 LISTEN 后只让受影响的缓存条目失效或刷新
+
+use super::cache::SchemaCache;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgListener;
+use sqlx::{PgPool, Row};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// DDL 事件的通知频道
+pub const DDL_CHANNEL: &str = "orpheus_ddl";
+
+/// 重连的初始等待时间
+const RECONNECT_MIN_DELAY: Duration = Duration::from_millis(500);
+
+/// 重连的最长等待时间
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// 安装 event trigger 的 SQL（可重复执行）
+///
+/// - `ddl_command_end`：找出命令作用的表（索引、约束、默认值、触发器、策略、规则归到所属的表），
+///   分区同时通知所属的分区表
+/// - `sql_drop`：被删除的表和表上的对象直接通知；其他对象（索引、函数、类型等）
+///   只通知命令直接删除的，级联删除的由所属的表覆盖
+///
+/// 同一事务中负载相同的通知只会送达一次。event trigger 以执行 DDL 的角色调用这些函数，
+/// 所以 `orpheus` schema 对所有角色开放 USAGE，否则非超级用户的 DDL 会因权限不足失败
+const INSTALL_SQL: &str = r#"
+CREATE SCHEMA IF NOT EXISTS orpheus;
+GRANT USAGE ON SCHEMA orpheus TO PUBLIC;
+
+CREATE OR REPLACE FUNCTION orpheus.notify_ddl(
+    command_tag text,
+    object_type text,
+    schema_name text,
+    table_name text
+) RETURNS void
+LANGUAGE sql AS $$
+    SELECT pg_notify('orpheus_ddl', json_build_object(
+        'tag', command_tag,
+        'object_type', object_type,
+        'schema', schema_name,
+        'table', table_name
+    )::text)
+$$;
+
+CREATE OR REPLACE FUNCTION orpheus.on_ddl_command_end() RETURNS event_trigger
+LANGUAGE plpgsql AS $$
+DECLARE
+    cmd record;
+    rel oid;
+    target record;
+BEGIN
+    FOR cmd IN SELECT * FROM pg_event_trigger_ddl_commands() LOOP
+        CONTINUE WHEN cmd.in_extension;
+
+        rel := CASE cmd.classid
+            WHEN 'pg_class'::regclass::oid THEN (
+                SELECT COALESCE(i.indrelid, c.oid)
+                FROM pg_class c
+                LEFT JOIN pg_index i ON i.indexrelid = c.oid
+                WHERE c.oid = cmd.objid
+            )
+            WHEN 'pg_attrdef'::regclass::oid THEN
+                (SELECT adrelid FROM pg_attrdef WHERE oid = cmd.objid)
+            WHEN 'pg_constraint'::regclass::oid THEN
+                (SELECT NULLIF(conrelid, 0) FROM pg_constraint WHERE oid = cmd.objid)
+            WHEN 'pg_trigger'::regclass::oid THEN
+                (SELECT tgrelid FROM pg_trigger WHERE oid = cmd.objid)
+            WHEN 'pg_policy'::regclass::oid THEN
+                (SELECT polrelid FROM pg_policy WHERE oid = cmd.objid)
+            WHEN 'pg_rewrite'::regclass::oid THEN
+                (SELECT ev_class FROM pg_rewrite WHERE oid = cmd.objid)
+        END;
+
+        IF rel IS NULL THEN
+            PERFORM orpheus.notify_ddl(
+                cmd.command_tag,
+                cmd.object_type,
+                CASE WHEN cmd.object_type = 'schema' THEN cmd.object_identity ELSE cmd.schema_name END,
+                NULL
+            );
+        ELSE
+            FOR target IN
+                SELECT n.nspname::text AS schema_name, c.relname::text AS table_name
+                FROM pg_class c
+                JOIN pg_namespace n ON n.oid = c.relnamespace
+                WHERE c.oid = rel
+                   OR (c.relkind = 'p' AND c.oid IN (SELECT inhparent FROM pg_inherits WHERE inhrelid = rel))
+            LOOP
+                PERFORM orpheus.notify_ddl(
+                    cmd.command_tag,
+                    cmd.object_type,
+                    target.schema_name,
+                    target.table_name
+                );
+            END LOOP;
+        END IF;
+    END LOOP;
+END
+$$;
+
+CREATE OR REPLACE FUNCTION orpheus.on_sql_drop() RETURNS event_trigger
+LANGUAGE plpgsql AS $$
+DECLARE
+    obj record;
+BEGIN
+    FOR obj IN SELECT * FROM pg_event_trigger_dropped_objects() LOOP
+        CONTINUE WHEN obj.is_temporary;
+
+        IF obj.object_type IN ('table', 'view', 'materialized view', 'foreign table') THEN
+            PERFORM orpheus.notify_ddl(tg_tag, obj.object_type, obj.schema_name, obj.object_name);
+        ELSIF obj.object_type IN (
+                'table column', 'table constraint', 'default value', 'trigger', 'policy', 'rule'
+              ) AND cardinality(obj.address_names) >= 2 THEN
+            PERFORM orpheus.notify_ddl(
+                tg_tag,
+                obj.object_type,
+                obj.address_names[1],
+                obj.address_names[2]
+            );
+        ELSIF obj.original THEN
+            PERFORM orpheus.notify_ddl(
+                tg_tag,
+                obj.object_type,
+                CASE WHEN obj.object_type = 'schema' THEN obj.object_name ELSE obj.schema_name END,
+                NULL
+            );
+        END IF;
+    END LOOP;
+END
+$$;
+
+DROP EVENT TRIGGER IF EXISTS orpheus_ddl_command_end;
+CREATE EVENT TRIGGER orpheus_ddl_command_end ON ddl_command_end
+    EXECUTE FUNCTION orpheus.on_ddl_command_end();
+
+DROP EVENT TRIGGER IF EXISTS orpheus_sql_drop;
+CREATE EVENT TRIGGER orpheus_sql_drop ON sql_drop
+    EXECUTE FUNCTION orpheus.on_sql_drop();
+"#;
+
+/// 卸载 event trigger 的 SQL（保留 `orpheus` schema）
+const UNINSTALL_SQL: &str = r#"
+DROP EVENT TRIGGER IF EXISTS orpheus_ddl_command_end;
+DROP EVENT TRIGGER IF EXISTS orpheus_sql_drop;
+DROP FUNCTION IF EXISTS orpheus.on_ddl_command_end();
+DROP FUNCTION IF EXISTS orpheus.on_sql_drop();
+DROP FUNCTION IF EXISTS orpheus.notify_ddl(text, text, text, text);
+"#;
+
+/// event trigger 发出的 DDL 事件（NOTIFY 的 JSON 负载）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DdlEvent {
+    /// 命令标签 (例如: "ALTER TABLE", "DROP TABLE")
+    pub tag: String,
+    /// 对象类型 (例如: "table", "index", "function")
+    pub object_type: String,
+    /// 受影响的 schema（未知时为 None）
+    pub schema: Option<String>,
+    /// 受影响的表（对象不属于某个表时为 None）
+    pub table: Option<String>,
+}
+
+/// DDL 事件对应的缓存操作，也用作跨实例失效消息的范围
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "scope", rename_all = "snake_case")]
+pub enum CacheAction {
+    /// 单个表
+    Table { schema: String, table: String },
+    /// schema 下的函数
+    Functions { schema: String },
+    /// schema 下的所有缓存（类型、索引等影响范围不确定的对象）
+    Schema { schema: String },
+    /// 所有缓存（无法确定 schema 的命令，例如 GRANT；
+    /// 或者影响范围可能超出所在 schema 的命令，例如 ALTER TYPE、ALTER SCHEMA）
+    All,
+}
+
+impl DdlEvent {
+    /// 解析 NOTIFY 负载
+    pub fn parse(payload: &str) -> Result<Self> {
+        serde_json::from_str(payload).context("Invalid DDL event payload")
+    }
+
+    /// 是否是 DROP 命令
+    pub fn is_drop(&self) -> bool {
+        self.tag.starts_with("DROP")
+    }
+
+    /// 是否可能改变表的名称或所在的 schema（旧名称下的缓存条目需要另外清理）
+    pub fn may_rename_relation(&self) -> bool {
+        matches!(
+            self.tag.as_str(),
+            "ALTER TABLE" | "ALTER VIEW" | "ALTER MATERIALIZED VIEW" | "ALTER FOREIGN TABLE"
+        )
+    }
+
+    /// 影响范围是否可能超出事件中的 schema
+    ///
+    /// 类型和域可以被其他 schema 中的表引用（新建的类型还没有被引用）；
+    /// 重命名 schema 后，旧名称下的缓存条目无法从事件中定位
+    fn affects_other_schemas(&self) -> bool {
+        match self.object_type.as_str() {
+            "type" | "domain" | "composite type" => !self.tag.starts_with("CREATE"),
+            "schema" => self.tag == "ALTER SCHEMA",
+            _ => false,
+        }
+    }
+
+    /// 对应的缓存操作
+    pub fn action(&self) -> CacheAction {
+        if self.affects_other_schemas() {
+            return CacheAction::All;
+        }
+        match (&self.schema, &self.table) {
+            (Some(schema), Some(table)) => CacheAction::Table {
+                schema: schema.clone(),
+                table: table.clone(),
+            },
+            (Some(schema), None)
+                if matches!(
+                    self.object_type.as_str(),
+                    "function" | "procedure" | "aggregate"
+                ) =>
+            {
+                CacheAction::Functions {
+                    schema: schema.clone(),
+                }
+            }
+            (Some(schema), None) => CacheAction::Schema {
+                schema: schema.clone(),
+            },
+            (None, _) => CacheAction::All,
+        }
+    }
+}
+
+/// 安装 event trigger（需要超级用户权限，可重复执行）
+pub async fn install_ddl_trigger(pool: &PgPool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    sqlx::raw_sql(INSTALL_SQL)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to install DDL event trigger")?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// 卸载 event trigger
+pub async fn uninstall_ddl_trigger(pool: &PgPool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    sqlx::raw_sql(UNINSTALL_SQL)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to uninstall DDL event trigger")?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// event trigger 是否已安装并启用
+pub async fn ddl_trigger_installed(pool: &PgPool) -> Result<bool> {
+    let row = sqlx::query(
+        "SELECT COUNT(*) = 2 AS installed
+         FROM pg_event_trigger
+         WHERE evtname IN ('orpheus_ddl_command_end', 'orpheus_sql_drop')
+           AND evtenabled <> 'D'",
+    )
+    .fetch_one(pool)
+    .await
+    .context("Failed to check DDL event trigger")?;
+
+    Ok(row.get("installed"))
+}
+
+/// 根据 DDL 事件更新缓存
+///
+/// 每个实例都会收到同一个通知，所以只更新本实例的缓存，不广播失效消息。
+/// 表仍在缓存中时立即从数据库重新加载，否则只让其失效；
+/// 删除分区时所属分区表的分区列表也随之变化，一并失效。
+/// 事件只包含表的新名称，ALTER 之后清理缓存中已经不存在的表（重命名或移动前的旧名称）
+pub async fn apply_event(pool: &PgPool, cache: &SchemaCache, event: &DdlEvent) {
+    let action = event.action();
+    if let CacheAction::Table { schema, table } = &action {
+        if event.may_rename_relation() {
+            if let Err(e) = evict_missing_tables(pool, cache).await {
+                eprintln!("⚠️  检查已重命名的表失败，清空缓存: {}", e);
+                cache.evict_for_ddl(&CacheAction::All).await;
+            }
+        }
+        if event.is_drop() {
+            for (parent_schema, parent) in
+                cache.cached_partition_parents(table, Some(schema)).await
+            {
+                cache
+                    .evict_for_ddl(&CacheAction::Table {
+                        schema: parent_schema,
+                        table: parent,
+                    })
+                    .await;
+            }
+        } else if cache.is_cached(table, Some(schema)).await {
+            if let Err(e) = cache.reload(table, Some(schema)).await {
+                eprintln!("⚠️  刷新 {}.{} 的缓存失败: {}", schema, table, e);
+            }
+            return;
+        }
+    }
+    cache.evict_for_ddl(&action).await;
+}
+
+/// 让缓存中已经不存在的表失效
+async fn evict_missing_tables(pool: &PgPool, cache: &SchemaCache) -> Result<()> {
+    let (schemas, tables): (Vec<String>, Vec<String>) =
+        cache.cached_tables().await.into_iter().unzip();
+    if schemas.is_empty() {
+        return Ok(());
+    }
+
+    let missing = sqlx::query(
+        "SELECT cached.schema_name, cached.table_name
+         FROM unnest($1::text[], $2::text[]) AS cached(schema_name, table_name)
+         WHERE NOT EXISTS (
+             SELECT 1
+             FROM pg_class c
+             JOIN pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = cached.schema_name AND c.relname = cached.table_name
+         )",
+    )
+    .bind(&schemas)
+    .bind(&tables)
+    .fetch_all(pool)
+    .await
+    .context("Failed to check cached tables")?;
+
+    for row in missing {
+        cache
+            .evict_for_ddl(&CacheAction::Table {
+                schema: row.get("schema_name"),
+                table: row.get("table_name"),
+            })
+            .await;
+    }
+    Ok(())
+}
+
+/// 启动后台监听任务
+///
+/// 连接断开后按指数退避自动重连；断线期间的通知已经丢失，重连后清空本实例的内存缓存
+/// （不影响 Redis 和其他实例）
+pub fn spawn_ddl_listener(pool: PgPool, cache: SchemaCache) -> JoinHandle<()> {
+    tokio::spawn(async move { run_ddl_listener(pool, cache).await })
+}
+
+/// 监听循环，连接池关闭时退出
+async fn run_ddl_listener(pool: PgPool, cache: SchemaCache) {
+    let mut delay = RECONNECT_MIN_DELAY;
+    let mut reconnecting = false;
+
+    while !pool.is_closed() {
+        let mut listener = match connect_listener(&pool).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("⚠️  DDL 监听连接失败，{:?} 后重试: {}", delay, e);
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(RECONNECT_MAX_DELAY);
+                reconnecting = true;
+                continue;
+            }
+        };
+        if reconnecting {
+            cache.evict(&CacheAction::All).await;
+        }
+        delay = RECONNECT_MIN_DELAY;
+        reconnecting = true;
+
+        loop {
+            match listener.try_recv().await {
+                Ok(Some(notification)) => match DdlEvent::parse(notification.payload()) {
+                    Ok(event) => apply_event(&pool, &cache, &event).await,
+                    Err(e) => eprintln!("⚠️  {}: {}", e, notification.payload()),
+                },
+                // 连接断开后已自动重连，期间的通知可能丢失
+                Ok(None) => cache.evict(&CacheAction::All).await,
+                Err(e) => {
+                    eprintln!("⚠️  DDL 监听连接断开: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// 建立监听连接
+async fn connect_listener(pool: &PgPool) -> Result<PgListener> {
+    let mut listener = PgListener::connect_with(pool).await?;
+    listener.listen(DDL_CHANNEL).await?;
+    Ok(listener)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_actions() {
+        let alter = DdlEvent::parse(
+            r#"{"tag": "ALTER TABLE", "object_type": "table", "schema": "public", "table": "users"}"#,
+        )
+        .unwrap();
+        assert!(!alter.is_drop());
+        assert_eq!(
+            alter.action(),
+            CacheAction::Table {
+                schema: "public".to_string(),
+                table: "users".to_string(),
+            }
+        );
+
+        let function = DdlEvent::parse(
+            r#"{"tag": "CREATE FUNCTION", "object_type": "function", "schema": "api", "table": null}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            function.action(),
+            CacheAction::Functions {
+                schema: "api".to_string(),
+            }
+        );
+
+        assert!(alter.may_rename_relation());
+        assert!(!function.may_rename_relation());
+
+        // 新建的类型还没有被引用，只影响所在的 schema
+        let create_type = DdlEvent::parse(
+            r#"{"tag": "CREATE TYPE", "object_type": "type", "schema": "public", "table": null}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            create_type.action(),
+            CacheAction::Schema {
+                schema: "public".to_string(),
+            }
+        );
+
+        // 修改或删除类型、域时，引用它的表可能在其他 schema
+        let drop_type = DdlEvent::parse(
+            r#"{"tag": "DROP TYPE", "object_type": "type", "schema": "public", "table": null}"#,
+        )
+        .unwrap();
+        assert!(drop_type.is_drop());
+        assert_eq!(drop_type.action(), CacheAction::All);
+        for payload in [
+            r#"{"tag": "ALTER TYPE", "object_type": "type", "schema": "app", "table": null}"#,
+            r#"{"tag": "ALTER DOMAIN", "object_type": "type", "schema": "app", "table": null}"#,
+            r#"{"tag": "ALTER TYPE", "object_type": "composite type", "schema": "app", "table": "point"}"#,
+            r#"{"tag": "ALTER SCHEMA", "object_type": "schema", "schema": "app", "table": null}"#,
+        ] {
+            assert_eq!(DdlEvent::parse(payload).unwrap().action(), CacheAction::All);
+        }
+
+        let grant = DdlEvent::parse(
+            r#"{"tag": "GRANT", "object_type": "TABLE", "schema": null, "table": null}"#,
+        )
+        .unwrap();
+        assert_eq!(grant.action(), CacheAction::All);
+
+        assert!(DdlEvent::parse("not json").is_err());
+    }
+}
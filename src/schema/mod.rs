@@ -6,8 +6,20 @@
 // # 主要组件
 //
 // - `types`: 数据结构定义（TableSchema, ColumnInfo 等）
-// - `inspector`: 数据库结构检查器（从 information_schema 读取）
+// - `pg_type`: PostgreSQL 类型模型（PgType）及其 JSON 表示
+// - `inspector`: 数据库结构检查器（从 information_schema 和 pg_catalog 读取）
 // - `cache`: Schema 缓存层（避免频繁查询）
+// - `shared`: 多实例共享的 Redis 缓存层（跨实例失效通过 pub/sub 广播）
+// - `listener`: DDL 变更监听（event trigger + LISTEN/NOTIFY，使受影响的缓存失效）
+// - `relationships`: 基于外键的表关系图（多对一、一对多、多对多）
+// - `diff`: Schema 差异比较和迁移 SQL 生成
+// - `stats`: 表统计信息和存储大小（行数估算、死元组、VACUUM 时间）
+// - `json_schema`: 表结构导出为 JSON Schema（读取、插入、更新三种场景）
+// - `openapi`: 根据表结构和函数生成 OpenAPI 3.1 文档
+// - `typescript`: 生成 TypeScript 类型定义（Row/Insert/Update、枚举、关系）
+// - `rust_codegen`: 生成 Rust 模型代码（serde + sqlx::FromRow）
+// - `erd`: 实体关系图导出（Mermaid erDiagram、Graphviz DOT）
+// - `snapshot`: Schema 快照文件（带版本哈希，支持无数据库的离线模式）
 //
 // # 使用示例
 //
@@ -34,9 +46,25 @@
 #![allow(dead_code)]
 
 pub mod cache;
+pub mod diff;
+pub mod erd;
 pub mod inspector;
+pub mod json_schema;
+pub mod listener;
+pub mod openapi;
+pub mod pg_type;
+pub mod relationships;
+pub mod rust_codegen;
+pub mod shared;
+pub mod snapshot;
+pub mod stats;
 pub mod types;
+pub mod typescript;
 
 // 重新导出常用类型和函数
+// （二进制 crate 未使用全部导出，库 crate 需要它们）
 pub use cache::SchemaCache;
-pub use inspector::{get_all_tables, get_schema_overview, get_table_schema};
+#[allow(unused_imports)]
+pub use inspector::{
+    get_all_table_schemas, get_all_tables, get_relations, get_schema_overview, get_table_schema,
+};
@@ -0,0 +1,923 @@
+// OpenAPI - 根据表结构和函数生成 OpenAPI 3.1 文档
+// 包含每个表的 REST 路径（过滤、排序、分页参数）、请求/响应组件，以及手写的 API 端点
+
+use super::{
+    json_schema::{self, SchemaVariant},
+    types::{ArgumentMode, FunctionInfo, RelationKind, TableSchema},
+};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+/// OpenAPI 版本
+pub const OPENAPI_VERSION: &str = "3.1.0";
+
+/// 自动生成的 REST 端点前缀
+pub const REST_PREFIX: &str = "/rest/v1";
+
+/// 手写端点的描述
+struct StaticEndpoint {
+    method: &'static str,
+    path: &'static str,
+    tag: &'static str,
+    summary: &'static str,
+    /// 查询参数（名称, JSON 类型, 说明）
+    query: &'static [(&'static str, &'static str, &'static str)],
+    /// 是否有 JSON 请求体
+    body: bool,
+    /// 响应体格式
+    response: ResponseBody,
+}
+
+/// 手写端点的响应体格式
+enum ResponseBody {
+    /// `ApiResponse` 包装的 JSON
+    ApiResponse,
+    /// 不包装的 JSON 对象
+    Json,
+    /// 纯文本
+    Text,
+}
+
+const SCHEMA_PARAM: (&str, &str, &str) = ("schema", "string", "Schema 名称，默认为 public");
+
+/// 手写的端点，与 main.rs 中注册的路由保持一致
+const STATIC_ENDPOINTS: &[StaticEndpoint] = &[
+    StaticEndpoint {
+        method: "get",
+        path: "/schema/schemas",
+        tag: "schema",
+        summary: "列出所有 schema",
+        query: &[],
+        body: false,
+        response: ResponseBody::ApiResponse,
+    },
+    StaticEndpoint {
+        method: "get",
+        path: "/schema/tables",
+        tag: "schema",
+        summary: "列出所有表",
+        query: &[SCHEMA_PARAM],
+        body: false,
+        response: ResponseBody::ApiResponse,
+    },
+    StaticEndpoint {
+        method: "get",
+        path: "/schema/tables/{table_name}",
+        tag: "schema",
+        summary: "获取表结构",
+        query: &[SCHEMA_PARAM],
+        body: false,
+        response: ResponseBody::ApiResponse,
+    },
+    StaticEndpoint {
+        method: "get",
+        path: "/schema/tables/{table_name}/policies",
+        tag: "schema",
+        summary: "行级安全策略",
+        query: &[SCHEMA_PARAM],
+        body: false,
+        response: ResponseBody::ApiResponse,
+    },
+    StaticEndpoint {
+        method: "get",
+        path: "/schema/tables/{table_name}/stats",
+        tag: "schema",
+        summary: "表统计信息",
+        query: &[SCHEMA_PARAM],
+        body: false,
+        response: ResponseBody::ApiResponse,
+    },
+    StaticEndpoint {
+        method: "get",
+        path: "/schema/tables/{table_name}/jsonschema",
+        tag: "schema",
+        summary: "JSON Schema",
+        query: &[
+            SCHEMA_PARAM,
+            ("variant", "string", "read、insert 或 update，默认返回全部"),
+        ],
+        body: false,
+        response: ResponseBody::ApiResponse,
+    },
+    StaticEndpoint {
+        method: "get",
+        path: "/schema/overview",
+        tag: "schema",
+        summary: "Schema 概览",
+        query: &[
+            SCHEMA_PARAM,
+            ("detailed", "boolean", "是否包含每个表的完整结构"),
+            ("stats", "boolean", "是否包含每个表的统计信息"),
+        ],
+        body: false,
+        response: ResponseBody::ApiResponse,
+    },
+    StaticEndpoint {
+        method: "get",
+        path: "/schema/stats",
+        tag: "schema",
+        summary: "表统计信息和存储大小",
+        query: &[
+            SCHEMA_PARAM,
+            ("sort", "string", "size、rows、dead_tuples、seq_scans 或 name"),
+        ],
+        body: false,
+        response: ResponseBody::ApiResponse,
+    },
+    StaticEndpoint {
+        method: "get",
+        path: "/schema/relationships",
+        tag: "schema",
+        summary: "表关系图",
+        query: &[SCHEMA_PARAM],
+        body: false,
+        response: ResponseBody::ApiResponse,
+    },
+    StaticEndpoint {
+        method: "get",
+        path: "/schema/relationships/{table_name}",
+        tag: "schema",
+        summary: "指定表的关系",
+        query: &[SCHEMA_PARAM],
+        body: false,
+        response: ResponseBody::ApiResponse,
+    },
+    StaticEndpoint {
+        method: "get",
+        path: "/schema/types",
+        tag: "schema",
+        summary: "用户定义类型",
+        query: &[SCHEMA_PARAM],
+        body: false,
+        response: ResponseBody::ApiResponse,
+    },
+    StaticEndpoint {
+        method: "get",
+        path: "/schema/sequences",
+        tag: "schema",
+        summary: "列出所有序列",
+        query: &[SCHEMA_PARAM],
+        body: false,
+        response: ResponseBody::ApiResponse,
+    },
+    StaticEndpoint {
+        method: "get",
+        path: "/schema/extensions",
+        tag: "schema",
+        summary: "已安装的扩展",
+        query: &[],
+        body: false,
+        response: ResponseBody::ApiResponse,
+    },
+    StaticEndpoint {
+        method: "get",
+        path: "/schema/types.ts",
+        tag: "schema",
+        summary: "TypeScript 类型定义",
+        query: &[SCHEMA_PARAM],
+        body: false,
+        response: ResponseBody::Text,
+    },
+    StaticEndpoint {
+        method: "get",
+        path: "/schema/erd",
+        tag: "schema",
+        summary: "实体关系图（Mermaid 或 Graphviz DOT）",
+        query: &[
+            SCHEMA_PARAM,
+            ("format", "string", "输出格式: mermaid（默认）或 dot"),
+            ("tables", "string", "逗号分隔的表名，只导出这些表及其相邻表"),
+            ("hops", "integer", "沿外键向外扩展的跳数，默认为 1"),
+        ],
+        body: false,
+        response: ResponseBody::Text,
+    },
+    StaticEndpoint {
+        method: "get",
+        path: "/schema/functions",
+        tag: "schema",
+        summary: "列出所有函数",
+        query: &[SCHEMA_PARAM],
+        body: false,
+        response: ResponseBody::ApiResponse,
+    },
+    StaticEndpoint {
+        method: "get",
+        path: "/schema/functions/{function_name}",
+        tag: "schema",
+        summary: "获取函数信息",
+        query: &[SCHEMA_PARAM],
+        body: false,
+        response: ResponseBody::ApiResponse,
+    },
+    StaticEndpoint {
+        method: "get",
+        path: "/schema/cached/tables/{table_name}",
+        tag: "schema",
+        summary: "获取表结构（缓存）",
+        query: &[SCHEMA_PARAM],
+        body: false,
+        response: ResponseBody::ApiResponse,
+    },
+    StaticEndpoint {
+        method: "get",
+        path: "/schema/cached/functions/{function_name}",
+        tag: "schema",
+        summary: "获取函数信息（缓存）",
+        query: &[SCHEMA_PARAM],
+        body: false,
+        response: ResponseBody::ApiResponse,
+    },
+    StaticEndpoint {
+        method: "get",
+        path: "/schema/cache/stats",
+        tag: "schema",
+        summary: "缓存统计（命中率、加载次数和耗时、淘汰数）",
+        query: &[],
+        body: false,
+        response: ResponseBody::ApiResponse,
+    },
+    StaticEndpoint {
+        method: "post",
+        path: "/schema/cache/clear",
+        tag: "schema",
+        summary: "清空缓存",
+        query: &[],
+        body: false,
+        response: ResponseBody::ApiResponse,
+    },
+    StaticEndpoint {
+        method: "post",
+        path: "/schema/cache/invalidate/{table_name}",
+        tag: "schema",
+        summary: "使表缓存失效",
+        query: &[SCHEMA_PARAM],
+        body: false,
+        response: ResponseBody::ApiResponse,
+    },
+    StaticEndpoint {
+        method: "post",
+        path: "/schema/cache/preload",
+        tag: "schema",
+        summary: "预加载缓存",
+        query: &[SCHEMA_PARAM],
+        body: false,
+        response: ResponseBody::ApiResponse,
+    },
+    StaticEndpoint {
+        method: "post",
+        path: "/schema/diff",
+        tag: "schema",
+        summary: "与快照比较差异",
+        query: &[],
+        body: true,
+        response: ResponseBody::ApiResponse,
+    },
+    StaticEndpoint {
+        method: "get",
+        path: "/schema/openapi.json",
+        tag: "schema",
+        summary: "OpenAPI 文档",
+        query: &[SCHEMA_PARAM],
+        body: false,
+        response: ResponseBody::Json,
+    },
+    StaticEndpoint {
+        method: "get",
+        path: "/schema/snapshot",
+        tag: "schema",
+        summary: "下载 schema 快照",
+        query: &[("schemas", "string", "逗号分隔的 schema 名称，默认为 public")],
+        body: false,
+        response: ResponseBody::Json,
+    },
+    StaticEndpoint {
+        method: "get",
+        path: "/github/stars/{owner}/{repo}",
+        tag: "github",
+        summary: "GitHub 仓库 stars 查询",
+        query: &[],
+        body: false,
+        response: ResponseBody::ApiResponse,
+    },
+];
+
+/// 生成 OpenAPI 文档
+///
+/// # Arguments
+/// * `schema_name` - 表和函数所属的 schema
+/// * `tables` - 表结构（顺序无关，输出按表名排序）
+/// * `functions` - 函数，聚合函数、窗口函数和触发器函数不会生成 RPC 路径
+pub fn build_openapi_document(
+    schema_name: &str,
+    tables: &[TableSchema],
+    functions: &[FunctionInfo],
+) -> Value {
+    let mut tables: Vec<&TableSchema> = tables.iter().collect();
+    tables.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut paths = Map::new();
+    let mut schemas = Map::new();
+    let mut tags = Vec::new();
+
+    for table in &tables {
+        for variant in SchemaVariant::ALL {
+            schemas.insert(component_name(table, variant), component_schema(table, variant));
+        }
+        paths.insert(format!("{}/{}", REST_PREFIX, table.name), table_path_item(table));
+
+        let mut tag = json!({ "name": table.name });
+        if let (Some(comment), Value::Object(tag)) = (&table.comment, &mut tag) {
+            tag.insert("description".to_string(), json!(comment));
+        }
+        tags.push(tag);
+    }
+
+    // 重载的函数共用一个 RPC 路径
+    let mut overloads: BTreeMap<&str, Vec<&FunctionInfo>> = BTreeMap::new();
+    for function in functions.iter().filter(|f| f.is_callable()) {
+        overloads.entry(&function.name).or_default().push(function);
+    }
+    for (name, overloads) in overloads {
+        if let Some(item) = rpc_path_item(name, &overloads, &tables) {
+            paths.insert(format!("{}/rpc/{}", REST_PREFIX, name), item);
+        }
+    }
+
+    for endpoint in STATIC_ENDPOINTS {
+        if let Value::Object(item) = paths
+            .entry(endpoint.path.to_string())
+            .or_insert_with(|| json!({}))
+        {
+            item.insert(endpoint.method.to_string(), static_operation(endpoint));
+        }
+    }
+
+    schemas.insert("ApiResponse".to_string(), api_response_schema());
+
+    json!({
+        "openapi": OPENAPI_VERSION,
+        "jsonSchemaDialect": json_schema::JSON_SCHEMA_DIALECT,
+        "info": {
+            "title": "Orpheus API",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": format!("`{}` schema 的自动 REST API 和 Schema API", schema_name),
+        },
+        "tags": tags,
+        "paths": paths,
+        "components": {
+            "schemas": schemas,
+            "parameters": common_parameters(),
+            "responses": {
+                "Error": {
+                    "description": "请求失败",
+                    "content": { "application/json": { "schema": schema_ref("ApiResponse") } },
+                },
+            },
+        },
+    })
+}
+
+/// 表在指定场景下的组件名（例如 "users"、"users.insert"）
+///
+/// 组件名只能包含字母、数字、`.`、`-` 和 `_`，其他字符替换为 `_`
+pub fn component_name(table: &TableSchema, variant: SchemaVariant) -> String {
+    let name: String = table
+        .name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    match variant {
+        SchemaVariant::Read => name,
+        _ => format!("{}.{}", name, variant.as_str()),
+    }
+}
+
+/// 组件中的表结构，去掉 `$schema` 和 `$id`（由文档的 `jsonSchemaDialect` 统一指定）
+fn component_schema(table: &TableSchema, variant: SchemaVariant) -> Value {
+    let mut schema = json_schema::table_json_schema(table, variant);
+    if let Value::Object(schema) = &mut schema {
+        schema.remove("$schema");
+        schema.remove("$id");
+    }
+    schema
+}
+
+/// 是否可以 INSERT
+fn is_insertable(table: &TableSchema) -> bool {
+    match table.kind {
+        RelationKind::Table | RelationKind::PartitionedTable | RelationKind::ForeignTable => true,
+        RelationKind::View => table.view.as_ref().is_some_and(|v| v.is_insertable_into),
+        RelationKind::MaterializedView => false,
+    }
+}
+
+/// 是否可以 UPDATE 和 DELETE
+fn is_updatable(table: &TableSchema) -> bool {
+    match table.kind {
+        RelationKind::Table | RelationKind::PartitionedTable | RelationKind::ForeignTable => true,
+        RelationKind::View => table.view.as_ref().is_some_and(|v| v.is_updatable),
+        RelationKind::MaterializedView => false,
+    }
+}
+
+/// 表的 REST 路径：GET 查询，POST 插入，PATCH 更新，DELETE 删除
+fn table_path_item(table: &TableSchema) -> Value {
+    let name = &table.name;
+    let rows = json!({
+        "type": "array",
+        "items": schema_ref(&component_name(table, SchemaVariant::Read)),
+    });
+    let filters: Vec<Value> = table
+        .columns
+        .iter()
+        .map(|column| {
+            json!({
+                "name": column.name,
+                "in": "query",
+                "required": false,
+                "description": format!(
+                    "按 `{}` ({}) 过滤，例如 eq.1、gt.5、in.(1,2)、is.null",
+                    column.name,
+                    column.sql_type()
+                ),
+                "schema": { "type": "string" },
+            })
+        })
+        .collect();
+
+    let mut item = Map::new();
+
+    let mut parameters = vec![
+        parameter_ref("select"),
+        parameter_ref("order"),
+        parameter_ref("limit"),
+        parameter_ref("offset"),
+    ];
+    parameters.extend(filters.iter().cloned());
+    let mut select = json!({
+        "tags": [name],
+        "summary": format!("查询 {}", name),
+        "operationId": format!("select_{}", name),
+        "parameters": parameters,
+        "responses": {
+            "200": json_response("查询结果", &rows),
+            "default": error_response(),
+        },
+    });
+    if let (Some(comment), Value::Object(select)) = (&table.comment, &mut select) {
+        select.insert("description".to_string(), json!(comment));
+    }
+    item.insert("get".to_string(), select);
+
+    if is_insertable(table) {
+        let insert = schema_ref(&component_name(table, SchemaVariant::Insert));
+        item.insert(
+            "post".to_string(),
+            json!({
+                "tags": [name],
+                "summary": format!("插入 {}", name),
+                "operationId": format!("insert_{}", name),
+                "parameters": [parameter_ref("prefer")],
+                "requestBody": {
+                    "required": true,
+                    "content": {
+                        "application/json": {
+                            "schema": { "oneOf": [insert, { "type": "array", "items": insert }] },
+                        },
+                    },
+                },
+                "responses": {
+                    "201": json_response("插入的行", &rows),
+                    "default": error_response(),
+                },
+            }),
+        );
+    }
+
+    if is_updatable(table) {
+        let mut parameters = vec![parameter_ref("prefer")];
+        parameters.extend(filters.iter().cloned());
+        item.insert(
+            "patch".to_string(),
+            json!({
+                "tags": [name],
+                "summary": format!("更新 {}", name),
+                "operationId": format!("update_{}", name),
+                "parameters": parameters,
+                "requestBody": {
+                    "required": true,
+                    "content": {
+                        "application/json": {
+                            "schema": schema_ref(&component_name(table, SchemaVariant::Update)),
+                        },
+                    },
+                },
+                "responses": {
+                    "200": json_response("更新后的行", &rows),
+                    "default": error_response(),
+                },
+            }),
+        );
+        item.insert(
+            "delete".to_string(),
+            json!({
+                "tags": [name],
+                "summary": format!("删除 {}", name),
+                "operationId": format!("delete_{}", name),
+                "parameters": parameters,
+                "responses": {
+                    "200": json_response("删除的行", &rows),
+                    "default": error_response(),
+                },
+            }),
+        );
+    }
+
+    Value::Object(item)
+}
+
+/// 函数的 RPC 路径，所有重载都无法按参数名调用时返回 None
+fn rpc_path_item(
+    name: &str,
+    overloads: &[&FunctionInfo],
+    tables: &[&TableSchema],
+) -> Option<Value> {
+    let callable: Vec<(&FunctionInfo, Value)> = overloads
+        .iter()
+        .filter_map(|f| rpc_arguments_schema(f).map(|arguments| (*f, arguments)))
+        .collect();
+    if callable.is_empty() {
+        return None;
+    }
+
+    let arguments: Vec<Value> = callable.iter().map(|(_, a)| a.clone()).collect();
+    let mut results: Vec<Value> = Vec::new();
+    let mut returns_nothing = false;
+    for (function, _) in &callable {
+        match rpc_result_schema(function, tables) {
+            Some(result) if !results.contains(&result) => results.push(result),
+            Some(_) => {}
+            None => returns_nothing = true,
+        }
+    }
+
+    let mut responses = Map::new();
+    if !results.is_empty() {
+        responses.insert("200".to_string(), json_response("调用结果", &one_of(results)));
+    }
+    if returns_nothing {
+        responses.insert("204".to_string(), json!({ "description": "没有返回值" }));
+    }
+    responses.insert("default".to_string(), error_response());
+
+    let mut operation = json!({
+        "tags": ["rpc"],
+        "summary": format!("调用 {}", name),
+        "operationId": format!("rpc_{}", name),
+        "requestBody": {
+            "required": true,
+            "content": { "application/json": { "schema": one_of(arguments) } },
+        },
+        "responses": responses,
+    });
+    if let (Some(comment), Value::Object(operation)) = (
+        callable.iter().find_map(|(f, _)| f.comment.as_ref()),
+        &mut operation,
+    ) {
+        operation.insert("description".to_string(), json!(comment));
+    }
+
+    Some(json!({ "post": operation }))
+}
+
+/// RPC 请求体（以参数名为键），有未命名的输入参数时返回 None
+fn rpc_arguments_schema(function: &FunctionInfo) -> Option<Value> {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+
+    for argument in function.input_arguments() {
+        let name = argument.name.as_ref()?;
+        properties.insert(name.clone(), json_schema::type_json_schema(&argument.data_type));
+        if argument.default_value.is_none() {
+            required.push(name.clone());
+        }
+    }
+
+    let mut schema = json!({
+        "type": "object",
+        "properties": properties,
+        "additionalProperties": false,
+    });
+    if let (false, Value::Object(schema)) = (required.is_empty(), &mut schema) {
+        schema.insert("required".to_string(), json!(required));
+    }
+    Some(schema)
+}
+
+/// RPC 返回值，过程和返回 void 的函数为 None
+fn rpc_result_schema(function: &FunctionInfo, tables: &[&TableSchema]) -> Option<Value> {
+    let return_type = function.return_type.as_deref()?;
+    if return_type == "void" {
+        return None;
+    }
+
+    let outputs = function.output_columns();
+
+    // 多个 OUT 参数或 RETURNS TABLE 时每行是一个对象
+    let row = if outputs.len() > 1 || outputs.iter().any(|a| a.mode == ArgumentMode::Table) {
+        let properties: Map<String, Value> = outputs
+            .iter()
+            .enumerate()
+            .map(|(i, a)| {
+                let name = a.name.clone().unwrap_or_else(|| format!("column{}", i + 1));
+                (name, json_schema::type_json_schema(&a.data_type))
+            })
+            .collect();
+        json!({ "type": "object", "properties": properties })
+    } else {
+        let element = return_type.strip_prefix("SETOF ").unwrap_or(return_type);
+        match tables
+            .iter()
+            .find(|t| t.name == element || format!("{}.{}", t.schema, t.name) == element)
+        {
+            Some(table) => schema_ref(&component_name(table, SchemaVariant::Read)),
+            None => json_schema::type_json_schema(element),
+        }
+    };
+
+    Some(if function.returns_set {
+        json!({ "type": "array", "items": row })
+    } else {
+        row
+    })
+}
+
+/// 手写端点的操作定义
+fn static_operation(endpoint: &StaticEndpoint) -> Value {
+    let mut parameters: Vec<Value> = endpoint
+        .path
+        .split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+        .map(|name| {
+            json!({
+                "name": name,
+                "in": "path",
+                "required": true,
+                "schema": { "type": "string" },
+            })
+        })
+        .collect();
+    parameters.extend(endpoint.query.iter().map(|(name, json_type, description)| {
+        json!({
+            "name": name,
+            "in": "query",
+            "required": false,
+            "description": description,
+            "schema": { "type": json_type },
+        })
+    }));
+
+    let response = match endpoint.response {
+        ResponseBody::ApiResponse => {
+            json!({ "application/json": { "schema": schema_ref("ApiResponse") } })
+        }
+        ResponseBody::Json => json!({ "application/json": { "schema": { "type": "object" } } }),
+        ResponseBody::Text => json!({ "text/plain": { "schema": { "type": "string" } } }),
+    };
+
+    let mut operation = Map::new();
+    operation.insert("tags".to_string(), json!([endpoint.tag]));
+    operation.insert("summary".to_string(), json!(endpoint.summary));
+    if !parameters.is_empty() {
+        operation.insert("parameters".to_string(), json!(parameters));
+    }
+    if endpoint.body {
+        operation.insert(
+            "requestBody".to_string(),
+            json!({
+                "required": true,
+                "content": { "application/json": { "schema": { "type": "object" } } },
+            }),
+        );
+    }
+    operation.insert(
+        "responses".to_string(),
+        json!({
+            "200": { "description": "成功", "content": response },
+            "default": error_response(),
+        }),
+    );
+
+    Value::Object(operation)
+}
+
+/// 查询、分页和返回方式的公共参数
+fn common_parameters() -> Value {
+    json!({
+        "select": {
+            "name": "select",
+            "in": "query",
+            "required": false,
+            "description": "要返回的列，逗号分隔，默认为 *",
+            "schema": { "type": "string" },
+        },
+        "order": {
+            "name": "order",
+            "in": "query",
+            "required": false,
+            "description": "排序，例如 created_at.desc,id.asc",
+            "schema": { "type": "string" },
+        },
+        "limit": {
+            "name": "limit",
+            "in": "query",
+            "required": false,
+            "description": "最多返回的行数",
+            "schema": { "type": "integer", "minimum": 0 },
+        },
+        "offset": {
+            "name": "offset",
+            "in": "query",
+            "required": false,
+            "description": "跳过的行数",
+            "schema": { "type": "integer", "minimum": 0 },
+        },
+        "prefer": {
+            "name": "Prefer",
+            "in": "header",
+            "required": false,
+            "description": "是否在响应中返回受影响的行",
+            "schema": { "type": "string", "enum": ["return=representation", "return=minimal"] },
+        },
+    })
+}
+
+/// `ApiResponse` 响应包装
+fn api_response_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "code": { "type": "integer" },
+            "success": { "type": "boolean" },
+            "data": {},
+            "message": { "type": ["string", "null"] },
+        },
+        "required": ["code", "success"],
+    })
+}
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
+
+fn parameter_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/parameters/{}", name) })
+}
+
+fn error_response() -> Value {
+    json!({ "$ref": "#/components/responses/Error" })
+}
+
+fn json_response(description: &str, schema: &Value) -> Value {
+    json!({
+        "description": description,
+        "content": { "application/json": { "schema": schema } },
+    })
+}
+
+/// 只有一个候选时直接返回，否则用 oneOf 组合
+fn one_of(mut schemas: Vec<Value>) -> Value {
+    if schemas.len() == 1 {
+        schemas.pop().unwrap_or_default()
+    } else {
+        json!({ "oneOf": schemas })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::types::{
+        ColumnInfo, FunctionArgument, FunctionKind, ViewInfo, Volatility,
+    };
+
+    fn table(name: &str, kind: RelationKind) -> TableSchema {
+        let mut id = ColumnInfo::test("id", "integer", false);
+        id.is_identity = true;
+
+        let mut table = TableSchema::test(name, vec![id, ColumnInfo::test("email", "text", false)]);
+        table.kind = kind;
+        table
+    }
+
+    fn function(name: &str, arguments: Vec<FunctionArgument>, return_type: &str) -> FunctionInfo {
+        FunctionInfo {
+            schema: "public".to_string(),
+            name: name.to_string(),
+            kind: FunctionKind::Function,
+            arguments,
+            identity_arguments: String::new(),
+            return_type: Some(return_type.to_string()),
+            returns_set: return_type.starts_with("SETOF "),
+            volatility: Volatility::Stable,
+            security_definer: false,
+            language: "sql".to_string(),
+            comment: None,
+        }
+    }
+
+    fn argument(name: &str, data_type: &str) -> FunctionArgument {
+        FunctionArgument {
+            name: Some(name.to_string()),
+            mode: ArgumentMode::In,
+            data_type: data_type.to_string(),
+            default_value: None,
+        }
+    }
+
+    #[test]
+    fn test_table_paths() {
+        let mut view = table("active_users", RelationKind::View);
+        view.view = Some(ViewInfo {
+            definition: "SELECT id, email FROM users".to_string(),
+            is_updatable: false,
+            is_insertable_into: false,
+        });
+        let tables = [table("users", RelationKind::Table), view];
+        let document = build_openapi_document("public", &tables, &[]);
+
+        assert_eq!(document["openapi"], OPENAPI_VERSION);
+
+        let users = &document["paths"]["/rest/v1/users"];
+        for method in ["get", "post", "patch", "delete"] {
+            assert!(users.get(method).is_some(), "missing {}", method);
+        }
+        let parameters = users["get"]["parameters"].as_array().cloned().unwrap_or_default();
+        assert!(parameters.contains(&json!({ "$ref": "#/components/parameters/limit" })));
+        assert!(parameters.iter().any(|p| p["name"] == "email"));
+        assert_eq!(
+            users["patch"]["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/users.update"
+        );
+
+        let active_users = &document["paths"]["/rest/v1/active_users"];
+        assert!(active_users.get("get").is_some());
+        assert!(active_users.get("post").is_none());
+        assert!(active_users.get("delete").is_none());
+
+        let schemas = &document["components"]["schemas"];
+        assert_eq!(schemas["users.insert"]["required"], json!(["email"]));
+        assert!(schemas["users"].get("$schema").is_none());
+        assert!(schemas["users"].get("$id").is_none());
+    }
+
+    #[test]
+    fn test_rpc_and_static_paths() {
+        let tables = [table("users", RelationKind::Table)];
+        let mut unnamed = argument("ignored", "integer");
+        unnamed.name = None;
+        let functions = [
+            function("find_users", vec![argument("pattern", "text")], "SETOF users"),
+            function("add", vec![argument("a", "integer"), argument("b", "integer")], "integer"),
+            function("add", vec![argument("a", "numeric"), argument("b", "numeric")], "numeric"),
+            function("positional", vec![unnamed], "integer"),
+            function("audit", vec![], "trigger"),
+        ];
+        let document = build_openapi_document("public", &tables, &functions);
+        let paths = &document["paths"];
+
+        let find_users = &paths["/rest/v1/rpc/find_users"]["post"];
+        assert_eq!(
+            find_users["requestBody"]["content"]["application/json"]["schema"]["required"],
+            json!(["pattern"])
+        );
+        assert_eq!(
+            find_users["responses"]["200"]["content"]["application/json"]["schema"]["items"]["$ref"],
+            "#/components/schemas/users"
+        );
+
+        let add = &paths["/rest/v1/rpc/add"]["post"];
+        assert_eq!(
+            add["requestBody"]["content"]["application/json"]["schema"]["oneOf"]
+                .as_array()
+                .map(|s| s.len()),
+            Some(2)
+        );
+        assert!(paths.get("/rest/v1/rpc/positional").is_none());
+        assert!(paths.get("/rest/v1/rpc/audit").is_none());
+
+        assert!(paths["/schema/diff"]["post"].get("requestBody").is_some());
+        let stars = paths["/github/stars/{owner}/{repo}"]["get"]["parameters"]
+            .as_array()
+            .map(|p| p.iter().map(|p| p["name"].clone()).collect::<Vec<_>>());
+        assert_eq!(stars, Some(vec![json!("owner"), json!("repo")]));
+    }
+}
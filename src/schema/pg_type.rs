@@ -0,0 +1,614 @@
+// PgType - PostgreSQL 类型模型
+// 把 `data_type` / `udt_name` / `format_type` 输出的类型名解析为强类型枚举，
+// 并给出每种类型的标准 JSON 表示
+
+use super::types::{ColumnInfo, UserTypeKind};
+use serde::{Deserialize, Serialize};
+
+/// PostgreSQL 列类型
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PgType {
+    SmallInt,
+    Integer,
+    BigInt,
+    Real,
+    DoublePrecision,
+    /// numeric / decimal，未声明精度时为 None
+    Numeric {
+        precision: Option<i32>,
+        scale: Option<i32>,
+    },
+    Money,
+    Boolean,
+    Text,
+    /// character varying(n)
+    Varchar {
+        length: Option<i32>,
+    },
+    /// character(n)
+    Char {
+        length: Option<i32>,
+    },
+    Bytea,
+    Uuid,
+    Json,
+    Jsonb,
+    Xml,
+    Date,
+    Time {
+        with_time_zone: bool,
+    },
+    Timestamp {
+        with_time_zone: bool,
+    },
+    Interval,
+    Inet,
+    Cidr,
+    MacAddr,
+    MacAddr8,
+    /// bit(n) / bit varying(n)
+    Bit {
+        length: Option<i32>,
+        varying: bool,
+    },
+    TsVector,
+    TsQuery,
+    Oid,
+    /// 几何类型 (point, line, lseg, box, path, polygon, circle)
+    Geometric {
+        name: String,
+    },
+    /// 范围类型和多范围类型 (例如: int4range, tstzmultirange)
+    Range {
+        name: String,
+        subtype: Box<PgType>,
+        multirange: bool,
+    },
+    /// 数组
+    Array {
+        element: Box<PgType>,
+        dimensions: i32,
+    },
+    /// 枚举类型
+    Enum {
+        schema: String,
+        name: String,
+        values: Vec<String>,
+    },
+    /// 域类型
+    Domain {
+        schema: String,
+        name: String,
+        base: Box<PgType>,
+    },
+    /// 复合类型
+    Composite {
+        schema: String,
+        name: String,
+    },
+    /// 其他类型（扩展类型等），保留原始类型名
+    Other {
+        name: String,
+    },
+}
+
+/// JSON 值类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JsonType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    Object,
+    Array,
+    /// 任意 JSON 值（json / jsonb）
+    Any,
+}
+
+impl PgType {
+    /// 解析类型名
+    ///
+    /// 同时支持 `format_type` 输出（例如 "character varying(20)"、
+    /// "timestamp(3) with time zone"、"integer[]"）和 `udt_name`（例如 "int4"、"_text"）
+    pub fn from_name(name: &str) -> Self {
+        let name = name.trim();
+
+        // 数组: "integer[]" / "integer[][]"
+        if let Some(element) = name.strip_suffix("[]") {
+            let mut element = element;
+            let mut dimensions = 1;
+            while let Some(inner) = element.strip_suffix("[]") {
+                element = inner;
+                dimensions += 1;
+            }
+            return Self::Array {
+                element: Box::new(Self::from_name(element)),
+                dimensions,
+            };
+        }
+
+        // udt_name 形式的数组: "_int4"
+        if let Some(element) = name.strip_prefix('_') {
+            return Self::Array {
+                element: Box::new(Self::from_name(element)),
+                dimensions: 1,
+            };
+        }
+
+        let (base, modifiers) = split_modifiers(name);
+        let first = modifiers.first().copied();
+
+        match base.to_ascii_lowercase().as_str() {
+            "smallint" | "int2" => Self::SmallInt,
+            "integer" | "int" | "int4" => Self::Integer,
+            "bigint" | "int8" => Self::BigInt,
+            "real" | "float4" => Self::Real,
+            "double precision" | "float8" => Self::DoublePrecision,
+            "numeric" | "decimal" => Self::Numeric {
+                precision: first,
+                scale: first.map(|_| modifiers.get(1).copied().unwrap_or(0)),
+            },
+            "money" => Self::Money,
+            "boolean" | "bool" => Self::Boolean,
+            "text" | "name" | "citext" => Self::Text,
+            "character varying" | "varchar" => Self::Varchar { length: first },
+            "character" | "char" | "bpchar" => Self::Char { length: first },
+            "bytea" => Self::Bytea,
+            "uuid" => Self::Uuid,
+            "json" => Self::Json,
+            "jsonb" => Self::Jsonb,
+            "xml" => Self::Xml,
+            "date" => Self::Date,
+            "time" | "time without time zone" => Self::Time {
+                with_time_zone: false,
+            },
+            "timetz" | "time with time zone" => Self::Time {
+                with_time_zone: true,
+            },
+            "timestamp" | "timestamp without time zone" => Self::Timestamp {
+                with_time_zone: false,
+            },
+            "timestamptz" | "timestamp with time zone" => Self::Timestamp {
+                with_time_zone: true,
+            },
+            "interval" => Self::Interval,
+            "inet" => Self::Inet,
+            "cidr" => Self::Cidr,
+            "macaddr" => Self::MacAddr,
+            "macaddr8" => Self::MacAddr8,
+            "bit" => Self::Bit {
+                length: first,
+                varying: false,
+            },
+            "bit varying" | "varbit" => Self::Bit {
+                length: first,
+                varying: true,
+            },
+            "tsvector" => Self::TsVector,
+            "tsquery" => Self::TsQuery,
+            "oid" => Self::Oid,
+            "point" | "line" | "lseg" | "box" | "path" | "polygon" | "circle" => Self::Geometric {
+                name: base.to_ascii_lowercase(),
+            },
+            other => match range_subtype(other) {
+                Some((subtype, multirange)) => Self::Range {
+                    name: other.to_string(),
+                    subtype: Box::new(subtype),
+                    multirange,
+                },
+                None => Self::Other {
+                    name: name.to_string(),
+                },
+            },
+        }
+    }
+
+    /// 根据 `information_schema.columns` 风格的字段解析类型
+    ///
+    /// `data_type` 不带长度和精度，这些信息来自单独的字段
+    pub fn from_parts(
+        data_type: &str,
+        udt_name: &str,
+        max_length: Option<i32>,
+        numeric_precision: Option<i32>,
+        numeric_scale: Option<i32>,
+    ) -> Self {
+        let name = if data_type == "USER-DEFINED" {
+            udt_name
+        } else {
+            data_type
+        };
+
+        match Self::from_name(name) {
+            Self::Varchar { .. } => Self::Varchar { length: max_length },
+            Self::Char { .. } => Self::Char { length: max_length },
+            Self::Bit { varying, .. } => Self::Bit {
+                length: max_length,
+                varying,
+            },
+            Self::Numeric { .. } => Self::Numeric {
+                precision: numeric_precision,
+                scale: numeric_precision.and(numeric_scale),
+            },
+            other => other,
+        }
+    }
+
+    /// 去掉域的包装，返回实际存储的类型
+    pub fn resolve(&self) -> &PgType {
+        match self {
+            Self::Domain { base, .. } => base.resolve(),
+            other => other,
+        }
+    }
+
+    /// 是否是数组类型（包括基于数组的域）
+    pub fn is_array(&self) -> bool {
+        matches!(self.resolve(), Self::Array { .. })
+    }
+
+    /// 是否是数值类型
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self.resolve(),
+            Self::SmallInt
+                | Self::Integer
+                | Self::BigInt
+                | Self::Real
+                | Self::DoublePrecision
+                | Self::Numeric { .. }
+                | Self::Money
+        )
+    }
+
+    /// 是否是整数类型
+    pub fn is_integer(&self) -> bool {
+        matches!(
+            self.resolve(),
+            Self::SmallInt | Self::Integer | Self::BigInt
+        )
+    }
+
+    /// 是否是字符串类型
+    pub fn is_text(&self) -> bool {
+        matches!(
+            self.resolve(),
+            Self::Text | Self::Varchar { .. } | Self::Char { .. }
+        )
+    }
+
+    /// 是否是日期/时间类型
+    pub fn is_temporal(&self) -> bool {
+        matches!(
+            self.resolve(),
+            Self::Date | Self::Time { .. } | Self::Timestamp { .. } | Self::Interval
+        )
+    }
+
+    /// 是否是布尔类型
+    pub fn is_boolean(&self) -> bool {
+        matches!(self.resolve(), Self::Boolean)
+    }
+
+    /// 是否是 JSON 类型
+    pub fn is_json(&self) -> bool {
+        matches!(self.resolve(), Self::Json | Self::Jsonb)
+    }
+
+    /// 标准 JSON 表示的值类型（与 PostgreSQL `to_json` 的输出一致）
+    ///
+    /// - 整数、浮点数、numeric 输出为 JSON 数字
+    /// - money、bytea（`\x` 十六进制）、时间、网络地址、范围等输出为字符串
+    /// - 数组输出为 JSON 数组，复合类型输出为对象
+    pub fn json_type(&self) -> JsonType {
+        match self.resolve() {
+            Self::SmallInt | Self::Integer | Self::BigInt | Self::Oid => JsonType::Integer,
+            Self::Real | Self::DoublePrecision | Self::Numeric { .. } => JsonType::Number,
+            Self::Boolean => JsonType::Boolean,
+            Self::Json | Self::Jsonb => JsonType::Any,
+            Self::Array { .. } => JsonType::Array,
+            Self::Composite { .. } => JsonType::Object,
+            _ => JsonType::String,
+        }
+    }
+
+    /// JSON 字符串/数字的格式提示（JSON Schema / OpenAPI 的 `format`）
+    pub fn json_format(&self) -> Option<&'static str> {
+        match self.resolve() {
+            Self::SmallInt => Some("int16"),
+            Self::Integer => Some("int32"),
+            Self::BigInt => Some("int64"),
+            Self::Oid => Some("uint32"),
+            Self::Real => Some("float"),
+            Self::DoublePrecision => Some("double"),
+            Self::Numeric { .. } => Some("decimal"),
+            Self::Uuid => Some("uuid"),
+            Self::Date => Some("date"),
+            Self::Time { .. } => Some("time"),
+            Self::Timestamp { .. } => Some("date-time"),
+            Self::Interval => Some("interval"),
+            Self::Bytea => Some("byte"),
+            Self::Inet => Some("ip"),
+            Self::Cidr => Some("cidr"),
+            Self::MacAddr | Self::MacAddr8 => Some("mac"),
+            Self::Money => Some("money"),
+            _ => None,
+        }
+    }
+
+    /// 字符串类型的最大长度
+    pub fn max_length(&self) -> Option<i32> {
+        match self.resolve() {
+            Self::Varchar { length } | Self::Char { length } => *length,
+            _ => None,
+        }
+    }
+
+    /// 枚举（或枚举数组的元素）的可选值
+    pub fn enum_values(&self) -> Option<&[String]> {
+        match self.resolve() {
+            Self::Enum { values, .. } => Some(values),
+            Self::Array { element, .. } => element.enum_values(),
+            _ => None,
+        }
+    }
+}
+
+impl ColumnInfo {
+    /// 解析列的强类型
+    pub fn pg_type(&self) -> PgType {
+        let base = match &self.user_type {
+            Some(user_type) => match user_type.kind {
+                UserTypeKind::Enum => PgType::Enum {
+                    schema: user_type.schema.clone(),
+                    name: user_type.name.clone(),
+                    values: user_type.enum_labels.clone(),
+                },
+                UserTypeKind::Domain => PgType::Domain {
+                    schema: user_type.schema.clone(),
+                    name: user_type.name.clone(),
+                    base: Box::new(
+                        user_type
+                            .base_type
+                            .as_deref()
+                            .map(PgType::from_name)
+                            .unwrap_or_else(|| {
+                                PgType::from_parts(
+                                    &self.data_type,
+                                    &self.udt_name,
+                                    self.max_length,
+                                    self.numeric_precision,
+                                    self.numeric_scale,
+                                )
+                            }),
+                    ),
+                },
+                UserTypeKind::Composite => PgType::Composite {
+                    schema: user_type.schema.clone(),
+                    name: user_type.name.clone(),
+                },
+            },
+            None if self.is_array() => match &self.element_type {
+                Some(element) => PgType::from_name(element),
+                None => PgType::from_name(self.udt_name.trim_start_matches('_')),
+            },
+            None => {
+                return PgType::from_parts(
+                    &self.data_type,
+                    &self.udt_name,
+                    self.max_length,
+                    self.numeric_precision,
+                    self.numeric_scale,
+                )
+            }
+        };
+
+        if self.is_array() {
+            PgType::Array {
+                element: Box::new(base),
+                dimensions: self.array_dimensions.unwrap_or(1).max(1),
+            }
+        } else {
+            base
+        }
+    }
+}
+
+/// 拆分类型修饰符: "timestamp(3) with time zone" -> ("timestamp with time zone", [3])
+fn split_modifiers(name: &str) -> (String, Vec<i32>) {
+    let (Some(open), Some(close)) = (name.find('('), name.find(')')) else {
+        return (name.to_string(), Vec::new());
+    };
+    if close < open {
+        return (name.to_string(), Vec::new());
+    }
+
+    let modifiers = name
+        .get(open + 1..close)
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|m| m.trim().parse().ok())
+        .collect();
+    let base = format!(
+        "{} {}",
+        name.get(..open).unwrap_or_default().trim(),
+        name.get(close + 1..).unwrap_or_default().trim()
+    );
+
+    (base.trim().to_string(), modifiers)
+}
+
+/// 内置范围类型的元素类型，第二个值表示是否是多范围类型
+fn range_subtype(name: &str) -> Option<(PgType, bool)> {
+    let (range, multirange) = match name.strip_suffix("multirange") {
+        Some(prefix) => (prefix, true),
+        None => (name.strip_suffix("range")?, false),
+    };
+
+    let subtype = match range {
+        "int4" => PgType::Integer,
+        "int8" => PgType::BigInt,
+        "num" => PgType::Numeric {
+            precision: None,
+            scale: None,
+        },
+        "ts" => PgType::Timestamp {
+            with_time_zone: false,
+        },
+        "tstz" => PgType::Timestamp {
+            with_time_zone: true,
+        },
+        "date" => PgType::Date,
+        _ => return None,
+    };
+
+    Some((subtype, multirange))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::types::UserTypeInfo;
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(PgType::from_name("int4"), PgType::Integer);
+        assert_eq!(PgType::from_name("integer"), PgType::Integer);
+        assert_eq!(
+            PgType::from_name("character varying(20)"),
+            PgType::Varchar { length: Some(20) }
+        );
+        assert_eq!(
+            PgType::from_name("numeric(10,2)"),
+            PgType::Numeric {
+                precision: Some(10),
+                scale: Some(2)
+            }
+        );
+        assert_eq!(
+            PgType::from_name("timestamp(3) with time zone"),
+            PgType::Timestamp {
+                with_time_zone: true
+            }
+        );
+        assert_eq!(
+            PgType::from_name("time with time zone"),
+            PgType::Time {
+                with_time_zone: true
+            }
+        );
+        assert_eq!(
+            PgType::from_name("text[][]"),
+            PgType::Array {
+                element: Box::new(PgType::Text),
+                dimensions: 2
+            }
+        );
+        assert_eq!(
+            PgType::from_name("_uuid"),
+            PgType::Array {
+                element: Box::new(PgType::Uuid),
+                dimensions: 1
+            }
+        );
+        assert_eq!(
+            PgType::from_name("tstzmultirange"),
+            PgType::Range {
+                name: "tstzmultirange".to_string(),
+                subtype: Box::new(PgType::Timestamp {
+                    with_time_zone: true
+                }),
+                multirange: true
+            }
+        );
+        assert_eq!(
+            PgType::from_name("geometry"),
+            PgType::Other {
+                name: "geometry".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_classification_and_json() {
+        for (name, json_type) in [
+            ("interval", JsonType::String),
+            ("uuid", JsonType::String),
+            ("bytea", JsonType::String),
+            ("inet", JsonType::String),
+            ("money", JsonType::String),
+            ("int8", JsonType::Integer),
+            ("numeric", JsonType::Number),
+            ("jsonb", JsonType::Any),
+            ("daterange", JsonType::String),
+            ("boolean[]", JsonType::Array),
+        ] {
+            assert_eq!(PgType::from_name(name).json_type(), json_type, "{}", name);
+        }
+
+        assert!(PgType::from_name("time with time zone").is_temporal());
+        assert!(PgType::from_name("interval").is_temporal());
+        assert!(PgType::from_name("money").is_numeric());
+        assert!(!PgType::from_name("uuid").is_text());
+        assert!(PgType::from_name("bpchar").is_text());
+        assert_eq!(PgType::from_name("timestamptz").json_format(), Some("date-time"));
+    }
+
+    #[test]
+    fn test_column_pg_type() {
+        let mut column = ColumnInfo::test("email", "character varying", true);
+        column.udt_name = "varchar".to_string();
+        column.max_length = Some(255);
+        assert_eq!(column.pg_type(), PgType::Varchar { length: Some(255) });
+
+        // 枚举数组
+        let moods = ColumnInfo {
+            name: "moods".to_string(),
+            data_type: "ARRAY".to_string(),
+            udt_name: "_mood".to_string(),
+            max_length: None,
+            element_type: Some("mood".to_string()),
+            array_dimensions: Some(1),
+            user_type: Some(UserTypeInfo::test_enum("mood", &["happy", "sad"])),
+            ..column.clone()
+        };
+        let pg_type = moods.pg_type();
+        assert!(pg_type.is_array());
+        assert_eq!(pg_type.enum_values().map(|v| v.len()), Some(2));
+
+        // 域解析到基础类型
+        let amount = ColumnInfo {
+            name: "amount".to_string(),
+            data_type: "numeric".to_string(),
+            udt_name: "numeric".to_string(),
+            max_length: None,
+            numeric_precision: Some(12),
+            numeric_scale: Some(2),
+            user_type: Some(UserTypeInfo {
+                schema: "public".to_string(),
+                name: "positive_amount".to_string(),
+                kind: UserTypeKind::Domain,
+                enum_labels: vec![],
+                base_type: Some("numeric(12,2)".to_string()),
+                not_null: false,
+                default_value: None,
+                checks: vec![],
+                fields: vec![],
+                comment: None,
+            }),
+            ..column
+        };
+        let pg_type = amount.pg_type();
+        assert!(matches!(pg_type, PgType::Domain { .. }));
+        assert!(pg_type.is_numeric());
+        assert_eq!(
+            pg_type.resolve(),
+            &PgType::Numeric {
+                precision: Some(12),
+                scale: Some(2)
+            }
+        );
+    }
+}
@@ -0,0 +1,330 @@
+// Relationships - 表关系图
+// 基于外键构建整个 schema 的关系图，推导反向关系和多对多关系
+
+use super::{inspector, types::ConstraintType, types::TableSchema};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+/// 关系类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RelationshipType {
+    /// 本表通过外键引用另一张表
+    ManyToOne,
+    /// 另一张表通过外键引用本表
+    OneToMany,
+    /// 两张表通过中间表关联
+    ManyToMany,
+}
+
+/// 多对多关系中的中间表信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JunctionInfo {
+    /// 中间表所属的 schema
+    pub schema: String,
+    /// 中间表名
+    pub table: String,
+    /// 中间表中引用本表的列
+    pub source_columns: Vec<String>,
+    /// 中间表中引用目标表的列
+    pub target_columns: Vec<String>,
+}
+
+/// 一条表关系
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Relationship {
+    /// 关系类型
+    pub relationship_type: RelationshipType,
+    /// 产生该关系的外键约束名（多对多关系为中间表引用目标表的外键）
+    pub constraint_name: String,
+    /// 本表参与关联的列
+    pub columns: Vec<String>,
+    /// 关联表所属的 schema
+    pub foreign_schema: String,
+    /// 关联表名
+    pub foreign_table: String,
+    /// 关联表参与关联的列（与 `columns` 一一对应）
+    pub foreign_columns: Vec<String>,
+    /// 中间表（仅多对多关系）
+    pub junction: Option<JunctionInfo>,
+}
+
+/// 单个表的所有关系
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableRelationships {
+    /// 表名
+    pub table: String,
+    /// 关系列表
+    pub relationships: Vec<Relationship>,
+}
+
+/// 整个 schema 的关系图
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelationshipGraph {
+    /// Schema 名称
+    pub schema: String,
+    /// 每个表的关系（按表名排序）
+    pub tables: Vec<TableRelationships>,
+}
+
+impl RelationshipGraph {
+    /// 获取指定表的关系
+    pub fn get_table(&self, table_name: &str) -> Option<&TableRelationships> {
+        self.tables.iter().find(|t| t.table == table_name)
+    }
+}
+
+impl TableRelationships {
+    /// 获取指定类型的关系
+    pub fn of_type(&self, relationship_type: RelationshipType) -> Vec<&Relationship> {
+        self.relationships
+            .iter()
+            .filter(|r| r.relationship_type == relationship_type)
+            .collect()
+    }
+}
+
+/// 从数据库读取 schema 并构建关系图
+///
+/// # Arguments
+/// * `pool` - PostgreSQL 连接池
+/// * `schema_name` - Schema 名称，默认为 "public"
+pub async fn get_relationship_graph(
+    pool: &PgPool,
+    schema_name: Option<&str>,
+) -> Result<RelationshipGraph> {
+    let schema = schema_name.unwrap_or("public");
+    let tables = inspector::get_all_table_schemas(pool, Some(schema)).await?;
+
+    Ok(build_relationship_graph(schema, &tables))
+}
+
+/// 根据表结构构建关系图
+///
+/// - 每个外键在本表产生一条多对一关系，在被引用表产生一条一对多关系
+/// - 中间表（两个外键的列都包含在主键或同一个唯一约束中）
+///   在两端的表上各产生一条多对多关系
+pub fn build_relationship_graph(schema: &str, tables: &[TableSchema]) -> RelationshipGraph {
+    let mut graph_tables: Vec<TableRelationships> = tables
+        .iter()
+        .map(|t| TableRelationships {
+            table: t.name.clone(),
+            relationships: Vec::new(),
+        })
+        .collect();
+    graph_tables.sort_by(|a, b| a.table.cmp(&b.table));
+
+    // 只有同一 schema 内的表才能挂载反向关系
+    let add_to = |graph_tables: &mut Vec<TableRelationships>,
+                  table_schema: &str,
+                  table_name: &str,
+                  relationship: Relationship| {
+        if table_schema != schema {
+            return;
+        }
+        if let Some(entry) = graph_tables.iter_mut().find(|t| t.table == table_name) {
+            entry.relationships.push(relationship);
+        }
+    };
+
+    for table in tables {
+        for fk in &table.foreign_keys {
+            let local_columns = to_strings(&fk.local_columns());
+            let foreign_columns = to_strings(&fk.foreign_columns());
+
+            // 多对一：本表 -> 被引用表
+            add_to(
+                &mut graph_tables,
+                &table.schema,
+                &table.name,
+                Relationship {
+                    relationship_type: RelationshipType::ManyToOne,
+                    constraint_name: fk.constraint_name.clone(),
+                    columns: local_columns.clone(),
+                    foreign_schema: fk.foreign_schema.clone(),
+                    foreign_table: fk.foreign_table_name.clone(),
+                    foreign_columns: foreign_columns.clone(),
+                    junction: None,
+                },
+            );
+
+            // 一对多：被引用表 -> 本表
+            add_to(
+                &mut graph_tables,
+                &fk.foreign_schema,
+                &fk.foreign_table_name,
+                Relationship {
+                    relationship_type: RelationshipType::OneToMany,
+                    constraint_name: fk.constraint_name.clone(),
+                    columns: foreign_columns,
+                    foreign_schema: table.schema.clone(),
+                    foreign_table: table.name.clone(),
+                    foreign_columns: local_columns,
+                    junction: None,
+                },
+            );
+        }
+
+        // 多对多：通过中间表关联的两端
+        for (i, source_fk) in table.foreign_keys.iter().enumerate() {
+            for (j, target_fk) in table.foreign_keys.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                let source_columns = source_fk.local_columns();
+                let target_columns = target_fk.local_columns();
+                if !is_junction_pair(table, &source_columns, &target_columns) {
+                    continue;
+                }
+
+                add_to(
+                    &mut graph_tables,
+                    &source_fk.foreign_schema,
+                    &source_fk.foreign_table_name,
+                    Relationship {
+                        relationship_type: RelationshipType::ManyToMany,
+                        constraint_name: target_fk.constraint_name.clone(),
+                        columns: to_strings(&source_fk.foreign_columns()),
+                        foreign_schema: target_fk.foreign_schema.clone(),
+                        foreign_table: target_fk.foreign_table_name.clone(),
+                        foreign_columns: to_strings(&target_fk.foreign_columns()),
+                        junction: Some(JunctionInfo {
+                            schema: table.schema.clone(),
+                            table: table.name.clone(),
+                            source_columns: to_strings(&source_columns),
+                            target_columns: to_strings(&target_columns),
+                        }),
+                    },
+                );
+            }
+        }
+    }
+
+    RelationshipGraph {
+        schema: schema.to_string(),
+        tables: graph_tables,
+    }
+}
+
+/// 判断两个外键是否构成中间表：
+/// 两组外键列互不重叠，且都包含在主键或同一个唯一约束中
+fn is_junction_pair(
+    table: &TableSchema,
+    source_columns: &[&str],
+    target_columns: &[&str],
+) -> bool {
+    if source_columns.iter().any(|c| target_columns.contains(c)) {
+        return false;
+    }
+
+    let covers = |key_columns: &[String]| {
+        source_columns
+            .iter()
+            .chain(target_columns)
+            .all(|c| key_columns.iter().any(|k| k == c))
+    };
+
+    (!table.primary_keys.is_empty() && covers(&table.primary_keys))
+        || table
+            .constraints_of(ConstraintType::Unique)
+            .iter()
+            .any(|c| covers(&c.columns))
+}
+
+/// 将列名切片转换为 `Vec<String>`
+fn to_strings(columns: &[&str]) -> Vec<String> {
+    columns.iter().map(|c| c.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::types::ForeignKeyInfo;
+
+    fn table(name: &str, primary_keys: &[&str], foreign_keys: Vec<ForeignKeyInfo>) -> TableSchema {
+        TableSchema {
+            primary_keys: to_strings(primary_keys),
+            foreign_keys,
+            ..TableSchema::test(name, vec![])
+        }
+    }
+
+    #[test]
+    fn test_many_to_one_and_reverse() {
+        let tables = vec![
+            table("users", &["id"], vec![]),
+            table(
+                "posts",
+                &["id"],
+                vec![ForeignKeyInfo::test("posts_author_id_fkey", &[("author_id", "id")], "users")],
+            ),
+        ];
+
+        let graph = build_relationship_graph("public", &tables);
+
+        let posts = graph.get_table("posts").expect("posts missing");
+        let many_to_one = posts.of_type(RelationshipType::ManyToOne);
+        assert_eq!(many_to_one.len(), 1);
+        assert_eq!(many_to_one[0].foreign_table, "users");
+        assert_eq!(many_to_one[0].columns, vec!["author_id".to_string()]);
+
+        let users = graph.get_table("users").expect("users missing");
+        let one_to_many = users.of_type(RelationshipType::OneToMany);
+        assert_eq!(one_to_many.len(), 1);
+        assert_eq!(one_to_many[0].foreign_table, "posts");
+        assert_eq!(one_to_many[0].columns, vec!["id".to_string()]);
+        assert_eq!(one_to_many[0].foreign_columns, vec!["author_id".to_string()]);
+        assert!(users.of_type(RelationshipType::ManyToMany).is_empty());
+    }
+
+    #[test]
+    fn test_many_to_many_via_junction() {
+        let tables = vec![
+            table("posts", &["id"], vec![]),
+            table("tags", &["id"], vec![]),
+            table(
+                "post_tags",
+                &["post_id", "tag_id"],
+                vec![
+                    ForeignKeyInfo::test("post_tags_post_id_fkey", &[("post_id", "id")], "posts"),
+                    ForeignKeyInfo::test("post_tags_tag_id_fkey", &[("tag_id", "id")], "tags"),
+                ],
+            ),
+        ];
+
+        let graph = build_relationship_graph("public", &tables);
+
+        let posts = graph.get_table("posts").expect("posts missing");
+        let many_to_many = posts.of_type(RelationshipType::ManyToMany);
+        assert_eq!(many_to_many.len(), 1);
+        assert_eq!(many_to_many[0].foreign_table, "tags");
+        let junction = many_to_many[0].junction.as_ref().expect("junction missing");
+        assert_eq!(junction.table, "post_tags");
+        assert_eq!(junction.source_columns, vec!["post_id".to_string()]);
+        assert_eq!(junction.target_columns, vec!["tag_id".to_string()]);
+
+        let tags = graph.get_table("tags").expect("tags missing");
+        assert_eq!(tags.of_type(RelationshipType::ManyToMany).len(), 1);
+    }
+
+    #[test]
+    fn test_two_foreign_keys_without_composite_key_is_not_junction() {
+        let tables = vec![
+            table("users", &["id"], vec![]),
+            table(
+                "messages",
+                &["id"],
+                vec![
+                    ForeignKeyInfo::test("messages_sender_fkey", &[("sender_id", "id")], "users"),
+                    ForeignKeyInfo::test("messages_recipient_fkey", &[("recipient_id", "id")], "users"),
+                ],
+            ),
+        ];
+
+        let graph = build_relationship_graph("public", &tables);
+
+        let users = graph.get_table("users").expect("users missing");
+        assert_eq!(users.of_type(RelationshipType::OneToMany).len(), 2);
+        assert!(users.of_type(RelationshipType::ManyToMany).is_empty());
+    }
+}
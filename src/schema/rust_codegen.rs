@@ -0,0 +1,589 @@
+// Rust Codegen - 根据表结构生成 Rust 模型代码
+// 每个表生成一个带 serde 和 `sqlx::FromRow` derive 的结构体，枚举类型生成 `sqlx::Type` 枚举
+
+use super::{
+    pg_type::PgType,
+    types::{ColumnInfo, TableSchema},
+};
+use anyhow::{bail, Context, Result};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// 生成文件的头部说明
+const HEADER: &str = "// 由 `orpheus codegen` 根据数据库结构自动生成，请勿手动修改
+//
+// 依赖: serde (derive)、serde_json、chrono、uuid、rust_decimal，
+// sqlx 需要开启 postgres、chrono、uuid、rust_decimal、json 特性
+";
+
+/// Rust 关键字，用作字段名时需要写成 `r#name`
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where",
+    "while", "abstract", "become", "box", "do", "final", "gen", "macro", "override", "priv",
+    "try", "typeof", "unsized", "virtual", "yield",
+];
+
+/// 不能作为原始标识符的关键字，用作字段名时加下划线后缀
+const RESERVED: &[&str] = &["crate", "self", "Self", "super"];
+
+/// 生成的源文件
+#[derive(Debug, Clone)]
+pub struct GeneratedFile {
+    /// 文件名（例如 "users.rs"、"mod.rs"）
+    pub file_name: String,
+    /// 文件内容
+    pub contents: String,
+}
+
+/// 列对应的 Rust 类型
+struct RustType {
+    /// 类型表达式（例如 "i32"、"Vec<String>"、"Mood"）
+    name: String,
+    /// 引用的枚举类型名
+    enum_name: Option<String>,
+    /// sqlx 不能直接解码、需要在查询中转换为 text 的类型
+    needs_text_cast: bool,
+}
+
+/// 生成整个 schema 的模型代码
+///
+/// 每个表（或视图）一个文件，枚举统一放在 `enums.rs`，`mod.rs` 负责声明和重新导出。
+/// 不同的表或枚举转换后得到相同的 Rust 名称时返回错误
+pub fn generate_rust_models(tables: &[TableSchema]) -> Result<Vec<GeneratedFile>> {
+    let mut tables: Vec<&TableSchema> = tables.iter().collect();
+    tables.sort_by(|a, b| a.name.cmp(&b.name));
+
+    // 所有列使用的枚举（类型名 -> 枚举值）
+    let mut enums: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for column in tables.iter().flat_map(|t| &t.columns) {
+        if let PgType::Enum { name, values, .. } = element_type(&column.pg_type()) {
+            enums.entry(name.clone()).or_insert_with(|| values.clone());
+        }
+    }
+
+    // 枚举和结构体都由 `mod.rs` 重新导出，共用同一个命名空间
+    ensure_unique(
+        "type",
+        enums
+            .keys()
+            .map(|name| (type_name(name), format!("enum type '{}'", name)))
+            .chain(
+                tables
+                    .iter()
+                    .map(|t| (type_name(&t.name), format!("table '{}'", t.name))),
+            ),
+    )?;
+    ensure_unique(
+        "module",
+        (!enums.is_empty())
+            .then(|| ("enums".to_string(), "the enums module".to_string()))
+            .into_iter()
+            .chain(
+                tables
+                    .iter()
+                    .map(|t| (module_name(&t.name), format!("table '{}'", t.name))),
+            ),
+    )?;
+
+    let mut files = Vec::new();
+    let mut mod_rs = String::from(HEADER);
+    mod_rs.push('\n');
+
+    if !enums.is_empty() {
+        files.push(GeneratedFile {
+            file_name: "enums.rs".to_string(),
+            contents: generate_enums(&enums),
+        });
+        mod_rs.push_str("pub mod enums;\n");
+    }
+    for table in &tables {
+        mod_rs.push_str(&format!("pub mod {};\n", module_name(&table.name)));
+    }
+
+    mod_rs.push('\n');
+    if !enums.is_empty() {
+        mod_rs.push_str("pub use enums::*;\n");
+    }
+    for table in &tables {
+        mod_rs.push_str(&format!(
+            "pub use {}::{};\n",
+            module_name(&table.name),
+            type_name(&table.name)
+        ));
+        files.push(GeneratedFile {
+            file_name: format!("{}.rs", module_name(&table.name)),
+            contents: generate_rust_model(table)?,
+        });
+    }
+
+    files.push(GeneratedFile {
+        file_name: "mod.rs".to_string(),
+        contents: mod_rs,
+    });
+    Ok(files)
+}
+
+/// 生成单个表的结构体
+///
+/// 不同的列转换后得到相同的字段名时返回错误
+pub fn generate_rust_model(table: &TableSchema) -> Result<String> {
+    ensure_unique(
+        "field",
+        table.columns.iter().map(|column| {
+            (
+                field_name(&column.name).trim_start_matches("r#").to_string(),
+                format!("column '{}'", column.name),
+            )
+        }),
+    )
+    .with_context(|| format!("Failed to generate model for {}.{}", table.schema, table.name))?;
+
+    let columns: Vec<(&ColumnInfo, RustType)> = table
+        .columns
+        .iter()
+        .map(|column| (column, rust_type(column)))
+        .collect();
+    let enum_imports: BTreeSet<&str> = columns
+        .iter()
+        .filter_map(|(_, rust_type)| rust_type.enum_name.as_deref())
+        .collect();
+
+    let mut out = String::from(HEADER);
+    out.push('\n');
+    let imports: Vec<&str> = enum_imports.into_iter().collect();
+    match imports.as_slice() {
+        [] => {}
+        [name] => out.push_str(&format!("use super::enums::{};\n", name)),
+        _ => out.push_str(&format!("use super::enums::{{{}}};\n", imports.join(", "))),
+    }
+    out.push_str("use serde::{Deserialize, Serialize};\n\n");
+
+    match &table.comment {
+        Some(comment) => doc_comment(&mut out, "", comment),
+        None => out.push_str(&format!("/// `{}.{}`\n", table.schema, table.name)),
+    }
+    out.push_str("#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]\n");
+    out.push_str(&format!("pub struct {} {{\n", type_name(&table.name)));
+
+    for (column, rust_type) in &columns {
+        if let Some(comment) = &column.comment {
+            doc_comment(&mut out, "    ", comment);
+        }
+        if rust_type.needs_text_cast {
+            if column.comment.is_some() {
+                out.push_str("    ///\n");
+            }
+            out.push_str(&format!(
+                "    /// `{}` 类型，查询时需要转换为 text（`{}::text`）\n",
+                column.sql_type(),
+                column.name
+            ));
+        }
+
+        let field = field_name(&column.name);
+        if field.trim_start_matches("r#") != column.name {
+            out.push_str(&format!(
+                "    #[serde(rename = {name:?})]\n    #[sqlx(rename = {name:?})]\n",
+                name = column.name
+            ));
+        }
+
+        let field_type = if column.is_nullable {
+            format!("Option<{}>", rust_type.name)
+        } else {
+            rust_type.name.clone()
+        };
+        out.push_str(&format!("    pub {}: {},\n", field, field_type));
+    }
+
+    out.push_str("}\n");
+    Ok(out)
+}
+
+/// 生成 `enums.rs`
+fn generate_enums(enums: &BTreeMap<String, Vec<String>>) -> String {
+    let mut out = String::from(HEADER);
+    out.push('\n');
+    out.push_str("use serde::{Deserialize, Serialize};\n");
+
+    for (name, values) in enums {
+        let rust_name = type_name(name);
+        out.push('\n');
+        out.push_str(&format!("/// 枚举类型 `{}`\n", name));
+        out.push_str(
+            "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]\n",
+        );
+        out.push_str(&format!("#[sqlx(type_name = {:?})]\n", name));
+        out.push_str(&format!("pub enum {} {{\n", rust_name));
+        for (value, variant) in values.iter().zip(variant_names(values)) {
+            out.push_str(&format!(
+                "    #[serde(rename = {value:?})]\n    #[sqlx(rename = {value:?})]\n"
+            ));
+            out.push_str(&format!("    {},\n", variant));
+        }
+        out.push_str("}\n");
+    }
+
+    out
+}
+
+/// 把生成的文件写入目录（目录不存在时创建），返回写入的文件路径
+pub fn write_rust_models(out_dir: &Path, files: &[GeneratedFile]) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(out_dir)
+        .with_context(|| format!("Failed to create directory {}", out_dir.display()))?;
+
+    files
+        .iter()
+        .map(|file| {
+            let path = out_dir.join(&file.file_name);
+            std::fs::write(&path, &file.contents)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/// 列对应的 Rust 类型（不含 Option）
+fn rust_type(column: &ColumnInfo) -> RustType {
+    let pg_type = column.pg_type();
+    let mut enum_name = None;
+    let mut needs_text_cast = false;
+    let name = type_expression(&pg_type, &mut enum_name, &mut needs_text_cast);
+
+    // 无法解码的元素类型只能整体转换为 text，数组也随之变成 String
+    let name = if needs_text_cast && pg_type.is_array() {
+        "String".to_string()
+    } else {
+        name
+    };
+
+    RustType {
+        name,
+        enum_name,
+        needs_text_cast,
+    }
+}
+
+fn type_expression(
+    pg_type: &PgType,
+    enum_name: &mut Option<String>,
+    needs_text_cast: &mut bool,
+) -> String {
+    let name = match pg_type.resolve() {
+        PgType::SmallInt => "i16",
+        PgType::Integer => "i32",
+        PgType::BigInt => "i64",
+        PgType::Real => "f32",
+        PgType::DoublePrecision => "f64",
+        PgType::Numeric { .. } => "rust_decimal::Decimal",
+        PgType::Boolean => "bool",
+        PgType::Text | PgType::Varchar { .. } | PgType::Char { .. } => "String",
+        PgType::Bytea => "Vec<u8>",
+        PgType::Uuid => "uuid::Uuid",
+        PgType::Json | PgType::Jsonb => "serde_json::Value",
+        PgType::Date => "chrono::NaiveDate",
+        PgType::Time {
+            with_time_zone: false,
+        } => "chrono::NaiveTime",
+        PgType::Timestamp {
+            with_time_zone: false,
+        } => "chrono::NaiveDateTime",
+        PgType::Timestamp {
+            with_time_zone: true,
+        } => "chrono::DateTime<chrono::Utc>",
+        PgType::Array {
+            element,
+            dimensions,
+        } => {
+            let mut name = type_expression(element, enum_name, needs_text_cast);
+            for _ in 0..*dimensions {
+                name = format!("Vec<{}>", name);
+            }
+            return name;
+        }
+        PgType::Enum { name, .. } => {
+            let rust_name = type_name(name);
+            *enum_name = Some(rust_name.clone());
+            return rust_name;
+        }
+        // money、interval、timetz、网络地址、范围、复合类型等
+        // 没有同时支持 serde 和 sqlx 的 Rust 类型，按 text 读取
+        _ => {
+            *needs_text_cast = true;
+            "String"
+        }
+    };
+
+    name.to_string()
+}
+
+/// 数组的元素类型（非数组返回自身）
+fn element_type(pg_type: &PgType) -> &PgType {
+    match pg_type.resolve() {
+        PgType::Array { element, .. } => element_type(element),
+        other => other,
+    }
+}
+
+/// 结构体和枚举名：PascalCase（例如 "order_items" -> "OrderItems"）
+fn type_name(name: &str) -> String {
+    let result = pascal_case(name);
+    match result.chars().next() {
+        None => "Unnamed".to_string(),
+        Some(c) if c.is_ascii_digit() => format!("_{}", result),
+        Some(_) => result,
+    }
+}
+
+/// 按非 ASCII 字母数字字符分词，各词首字母大写后拼接（没有 ASCII 字母数字时为空）
+fn pascal_case(name: &str) -> String {
+    let mut result = String::new();
+    for word in name.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            result.push(first.to_ascii_uppercase());
+            result.push_str(chars.as_str());
+        }
+    }
+    result
+}
+
+/// 枚举值对应的变体名，保证互不相同
+///
+/// 没有 ASCII 字母数字的值（例如 "待处理"）命名为 `Value<序号>`，转换后重名的值加上序号
+/// （例如 "a-b" 和 "a_b" 变为 `AB1` 和 `AB2`），序号为值在枚举中的位置（从 1 开始）
+fn variant_names(values: &[String]) -> Vec<String> {
+    let bases: Vec<String> = values.iter().map(|value| pascal_case(value)).collect();
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for base in &bases {
+        *counts.entry(base.as_str()).or_default() += 1;
+    }
+
+    let mut used = HashSet::new();
+    bases
+        .iter()
+        .enumerate()
+        .map(|(i, base)| {
+            let mut name = if base.is_empty() {
+                format!("Value{}", i + 1)
+            } else if counts.get(base.as_str()).is_some_and(|&count| count > 1) {
+                format!("{}{}", base, i + 1)
+            } else {
+                base.clone()
+            };
+            if name.starts_with(|c: char| c.is_ascii_digit()) {
+                name.insert(0, '_');
+            }
+            // 加上序号后仍可能与其他值的名称相同
+            while !used.insert(name.clone()) {
+                name.push('_');
+            }
+            name
+        })
+        .collect()
+}
+
+/// 检查生成的名称互不相同，`names` 为 (名称, 来源说明)
+fn ensure_unique(kind: &str, names: impl IntoIterator<Item = (String, String)>) -> Result<()> {
+    let mut seen: HashMap<String, String> = HashMap::new();
+    for (name, source) in names {
+        if let Some(previous) = seen.get(&name) {
+            bail!(
+                "{} and {} both map to Rust {} '{}'",
+                previous,
+                source,
+                kind,
+                name
+            );
+        }
+        seen.insert(name, source);
+    }
+    Ok(())
+}
+
+/// 模块名：snake_case
+fn module_name(name: &str) -> String {
+    let module = snake_case(name);
+    if KEYWORDS.contains(&module.as_str()) || RESERVED.contains(&module.as_str()) {
+        format!("{}_", module)
+    } else {
+        module
+    }
+}
+
+/// 字段名：snake_case，关键字使用原始标识符
+fn field_name(name: &str) -> String {
+    let field = snake_case(name);
+    if RESERVED.contains(&field.as_str()) {
+        format!("{}_", field)
+    } else if KEYWORDS.contains(&field.as_str()) {
+        format!("r#{}", field)
+    } else {
+        field
+    }
+}
+
+/// 转换为合法的 snake_case 标识符（非字母数字字符替换为下划线）
+fn snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 && !result.ends_with('_') {
+                result.push('_');
+            }
+            result.push(c.to_ascii_lowercase());
+        } else if c.is_ascii_alphanumeric() || c == '_' {
+            result.push(c);
+        } else if !result.ends_with('_') {
+            result.push('_');
+        }
+    }
+
+    match result.chars().next() {
+        // 没有 ASCII 字母数字时只剩下划线，不是合法的标识符
+        None => "unnamed".to_string(),
+        Some(_) if result.chars().all(|c| c == '_') => "unnamed".to_string(),
+        Some(c) if c.is_ascii_digit() => format!("_{}", result),
+        Some(_) => result,
+    }
+}
+
+/// 输出文档注释
+fn doc_comment(out: &mut String, indent: &str, comment: &str) {
+    for line in comment.lines() {
+        out.push_str(indent);
+        out.push_str("///");
+        if !line.is_empty() {
+            out.push(' ');
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::types::UserTypeInfo;
+
+    fn order_items() -> TableSchema {
+        let mut status = ColumnInfo::test("status", "USER-DEFINED", false);
+        status.user_type = Some(UserTypeInfo::test_enum("order_status", &["pending", "in-transit"]));
+        let mut price = ColumnInfo::test("price", "numeric", false);
+        price.comment = Some("Unit price".to_string());
+        let mut tags = ColumnInfo::test("tags", "ARRAY", true);
+        tags.udt_name = "_text".to_string();
+        tags.element_type = Some("text".to_string());
+        tags.array_dimensions = Some(1);
+
+        let mut order_items = TableSchema::test(
+            "order_items",
+            vec![
+                ColumnInfo::test("id", "uuid", false),
+                ColumnInfo::test("type", "text", true),
+                ColumnInfo::test("createdAt", "timestamp with time zone", false),
+                price,
+                status,
+                tags,
+                ColumnInfo::test("metadata", "jsonb", true),
+                ColumnInfo::test("duration", "interval", true),
+            ],
+        );
+        order_items.comment = Some("Line items of an order".to_string());
+        order_items
+    }
+
+    #[test]
+    fn test_generate_struct() {
+        let code = generate_rust_model(&order_items()).unwrap();
+
+        assert!(code.contains("use super::enums::OrderStatus;\n"));
+        assert!(code.contains("/// Line items of an order\n"));
+        assert!(code.contains("#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]\n"));
+        assert!(code.contains("pub struct OrderItems {\n"));
+        assert!(code.contains("    pub id: uuid::Uuid,\n"));
+        assert!(code.contains("    pub r#type: Option<String>,\n"));
+        assert!(code.contains(
+            "    #[serde(rename = \"createdAt\")]\n    #[sqlx(rename = \"createdAt\")]\n    \
+             pub created_at: chrono::DateTime<chrono::Utc>,\n"
+        ));
+        assert!(code.contains("    /// Unit price\n    pub price: rust_decimal::Decimal,\n"));
+        assert!(code.contains("    pub status: OrderStatus,\n"));
+        assert!(code.contains("    pub tags: Option<Vec<String>>,\n"));
+        assert!(code.contains("    pub metadata: Option<serde_json::Value>,\n"));
+        assert!(code.contains("`duration::text`"));
+        assert!(code.contains("    pub duration: Option<String>,\n"));
+    }
+
+    #[test]
+    fn test_generate_files() {
+        let files = generate_rust_models(&[order_items()]).unwrap();
+        let names: Vec<&str> = files.iter().map(|f| f.file_name.as_str()).collect();
+        assert_eq!(names, ["enums.rs", "order_items.rs", "mod.rs"]);
+
+        let contents = |name: &str| {
+            files
+                .iter()
+                .find(|f| f.file_name == name)
+                .map(|f| f.contents.clone())
+                .unwrap_or_default()
+        };
+
+        let enums = contents("enums.rs");
+        assert!(enums.contains("#[sqlx(type_name = \"order_status\")]\n"));
+        assert!(enums.contains("pub enum OrderStatus {\n"));
+        assert!(enums.contains(
+            "    #[serde(rename = \"in-transit\")]\n    #[sqlx(rename = \"in-transit\")]\n    InTransit,\n"
+        ));
+
+        let mod_rs = contents("mod.rs");
+        assert!(mod_rs.contains("pub mod enums;\npub mod order_items;\n"));
+        assert!(mod_rs.contains("pub use order_items::OrderItems;\n"));
+    }
+
+    #[test]
+    fn test_enum_variant_collisions() {
+        let values = ["待处理", "已发货", "a-b", "a_b", "ok", "value1"].map(String::from);
+        assert_eq!(
+            variant_names(&values),
+            ["Value1", "Value2", "AB3", "AB4", "Ok", "Value1_"]
+        );
+        assert_eq!(variant_names(&["1st".to_string()]), ["_1st"]);
+    }
+
+    #[test]
+    fn test_name_collisions() {
+        let error = |tables: &[TableSchema]| {
+            generate_rust_models(tables)
+                .map(|_| ())
+                .unwrap_err()
+                .to_string()
+        };
+
+        let dashed = TableSchema::test("order-items", vec![ColumnInfo::test("id", "uuid", false)]);
+        assert_eq!(
+            error(&[order_items(), dashed]),
+            "table 'order-items' and table 'order_items' both map to Rust type 'OrderItems'"
+        );
+
+        // 与 `pub use enums::*` 导出的枚举重名
+        let status = TableSchema::test("order_status", vec![ColumnInfo::test("id", "uuid", false)]);
+        assert_eq!(
+            error(&[order_items(), status]),
+            "enum type 'order_status' and table 'order_status' both map to Rust type 'OrderStatus'"
+        );
+
+        let columns = TableSchema::test(
+            "users",
+            vec![
+                ColumnInfo::test("名前", "text", false),
+                ColumnInfo::test("氏名", "text", false),
+            ],
+        );
+        assert!(format!("{:#}", generate_rust_models(&[columns]).unwrap_err()).contains(
+            "column '名前' and column '氏名' both map to Rust field 'unnamed'"
+        ));
+    }
+}
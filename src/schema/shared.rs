@@ -0,0 +1,572 @@
+// Shared - 多实例共享的 Redis 缓存层
+// 作为 SchemaCache 内存缓存之后的第二层：内存未命中时先读 Redis，再查数据库；
+// 任一实例刷新或清空缓存时删除 Redis 中的条目，并通过 pub/sub 通知其他实例丢弃内存中的副本
+
+use super::{
+    cache::SchemaCache,
+    listener::CacheAction,
+    types::{FunctionInfo, TableSchema},
+};
+use anyhow::{Context, Result};
+use futures_util::StreamExt;
+use redis::aio::ConnectionManager;
+use redis::{AsyncCommands, Client, ErrorKind, RedisError, RedisResult};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::future::Future;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// 默认的键前缀
+pub const DEFAULT_NAMESPACE: &str = "orpheus:schema";
+
+/// 重连的初始等待时间
+const RECONNECT_MIN_DELAY: Duration = Duration::from_millis(500);
+
+/// 重连的最长等待时间
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// 建立连接的超时时间
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 单次读写的超时时间，超时后本次请求直接查数据库
+const OPERATION_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// 删除（可能需要 SCAN 大量键）的超时时间
+const DELETE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 每条 DEL 命令删除的键数
+const DELETE_BATCH_SIZE: usize = 500;
+
+/// 通过 pub/sub 广播的失效消息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvalidationMessage {
+    /// 发出消息的实例，实例忽略自己发出的消息
+    pub origin: String,
+    /// 需要丢弃的缓存
+    pub action: CacheAction,
+}
+
+/// 从 Redis 读取的条目
+#[derive(Debug, Clone)]
+pub struct SharedEntry<T> {
+    pub value: T,
+    /// Redis 中剩余的过期时间（键没有设置过期时间时为 None）
+    pub ttl: Option<Duration>,
+}
+
+/// 熔断器：连接出错或超时后在冷却期内不再访问 Redis
+///
+/// 冷却期从 `RECONNECT_MIN_DELAY` 开始，连续失败时翻倍，最长 `RECONNECT_MAX_DELAY`，
+/// 任一操作成功后复位。只在打开和恢复时打印日志，冷却期内的请求直接跳过 Redis
+#[derive(Debug, Default)]
+struct CircuitBreaker {
+    open_until: Option<Instant>,
+    cooldown: Option<Duration>,
+}
+
+impl CircuitBreaker {
+    /// 是否处于冷却期
+    fn is_open(&self, now: Instant) -> bool {
+        self.open_until.is_some_and(|until| now < until)
+    }
+
+    /// 记录一次失败，返回新的冷却期
+    fn trip(&mut self, now: Instant) -> Duration {
+        let cooldown = self.cooldown.map_or(RECONNECT_MIN_DELAY, |cooldown| {
+            (cooldown * 2).min(RECONNECT_MAX_DELAY)
+        });
+        self.cooldown = Some(cooldown);
+        self.open_until = Some(now + cooldown);
+        cooldown
+    }
+
+    /// 是否因为失败打开过且尚未恢复
+    fn is_tripped(&self) -> bool {
+        self.cooldown.is_some()
+    }
+
+    /// 记录一次成功，返回是否从失败中恢复
+    fn reset(&mut self) -> bool {
+        let recovered = self.is_tripped();
+        self.open_until = None;
+        self.cooldown = None;
+        recovered
+    }
+}
+
+/// Redis 缓存层
+///
+/// Redis 不可用时读写立即失败并只打印警告，SchemaCache 退化为只有内存缓存，
+/// 请求不会因此失败或等待重连
+#[derive(Clone)]
+pub struct SharedCache {
+    client: Client,
+    /// 由 `spawn_connector` 在后台建立，之后由 ConnectionManager 自动重连（不重试）
+    connection: Arc<RwLock<Option<ConnectionManager>>>,
+    breaker: Arc<Mutex<CircuitBreaker>>,
+    namespace: String,
+    instance_id: String,
+}
+
+impl SharedCache {
+    /// 创建 Redis 缓存层（不会立即连接）
+    ///
+    /// # Arguments
+    /// * `client` - Redis 客户端
+    /// * `namespace` - 键和频道的前缀，连接不同数据库的实例应使用不同的前缀
+    pub fn new(client: Client, namespace: &str) -> Self {
+        Self {
+            client,
+            connection: Arc::new(RwLock::new(None)),
+            breaker: Arc::new(Mutex::new(CircuitBreaker::default())),
+            namespace: namespace.to_string(),
+            instance_id: uuid::Uuid::new_v4().to_string(),
+        }
+    }
+
+    /// 启动后台任务建立连接，失败时按指数退避重试直到成功
+    ///
+    /// 连接建立之前所有读写都直接失败，请求不会等待连接
+    pub fn spawn_connector(&self) -> JoinHandle<()> {
+        let shared = self.clone();
+        tokio::spawn(async move {
+            let mut delay = RECONNECT_MIN_DELAY;
+            loop {
+                match shared.connect().await {
+                    Ok(conn) => {
+                        *shared
+                            .connection
+                            .write()
+                            .unwrap_or_else(PoisonError::into_inner) = Some(conn);
+                        return;
+                    }
+                    Err(e) => {
+                        eprintln!("⚠️  Redis 连接失败，{:?} 后重试: {}", delay, e);
+                        tokio::time::sleep(delay).await;
+                        delay = (delay * 2).min(RECONNECT_MAX_DELAY);
+                    }
+                }
+            }
+        })
+    }
+
+    /// 当前实例的 ID
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    /// 失效消息的频道
+    pub fn channel(&self) -> String {
+        format!("{}:invalidate", self.namespace)
+    }
+
+    /// 读取表结构，未命中或 Redis 不可用时返回 None
+    pub async fn get_table(
+        &self,
+        schema: &str,
+        table_name: &str,
+    ) -> Option<SharedEntry<TableSchema>> {
+        self.get_json(&self.table_key(schema, table_name)).await
+    }
+
+    /// 读取 schema 下的所有函数，未命中或 Redis 不可用时返回 None
+    pub async fn get_functions(&self, schema: &str) -> Option<SharedEntry<Vec<FunctionInfo>>> {
+        self.get_json(&self.functions_key(schema)).await
+    }
+
+    /// 写入表结构
+    pub async fn put_tables(&self, tables: &[TableSchema], ttl: Duration) {
+        if tables.is_empty() {
+            return;
+        }
+        let mut pipe = redis::pipe();
+        for table in tables {
+            match serde_json::to_string(table) {
+                Ok(json) => {
+                    pipe.set_ex(
+                        self.table_key(&table.schema, &table.name),
+                        json,
+                        ttl_seconds(ttl),
+                    )
+                    .ignore();
+                }
+                Err(e) => eprintln!("⚠️  序列化 {}.{} 失败: {}", table.schema, table.name, e),
+            }
+        }
+        if let Err(e) = self.query(&pipe).await {
+            report("写入 Redis 缓存失败", &e);
+        }
+    }
+
+    /// 写入 schema 下的所有函数
+    pub async fn put_functions(&self, schema: &str, functions: &[FunctionInfo], ttl: Duration) {
+        let json = match serde_json::to_string(functions) {
+            Ok(json) => json,
+            Err(e) => {
+                eprintln!("⚠️  序列化 {} 的函数失败: {}", schema, e);
+                return;
+            }
+        };
+        let mut pipe = redis::pipe();
+        pipe.set_ex(self.functions_key(schema), json, ttl_seconds(ttl))
+            .ignore();
+        if let Err(e) = self.query(&pipe).await {
+            report("写入 Redis 缓存失败", &e);
+        }
+    }
+
+    /// 删除 Redis 中受影响的条目，并通知其他实例
+    pub async fn invalidate(&self, action: &CacheAction) {
+        self.remove(action).await;
+        self.invalidate_peers(action).await;
+    }
+
+    /// 只删除 Redis 中受影响的条目，不通知其他实例
+    pub async fn remove(&self, action: &CacheAction) {
+        if let Err(e) = self.delete(action).await {
+            report("删除 Redis 缓存失败", &e);
+        }
+    }
+
+    /// 只通知其他实例丢弃内存中的副本（Redis 中已经是最新的条目）
+    pub async fn invalidate_peers(&self, action: &CacheAction) {
+        if let Err(e) = self.publish(action).await {
+            match e.downcast_ref::<RedisError>() {
+                Some(redis_error) => report("广播缓存失效消息失败", redis_error),
+                None => eprintln!("⚠️  广播缓存失效消息失败: {}", e),
+            }
+        }
+    }
+
+    /// 表结构的键
+    fn table_key(&self, schema: &str, table_name: &str) -> String {
+        format!("{}:table:{}.{}", self.namespace, schema, table_name)
+    }
+
+    /// 函数列表的键
+    fn functions_key(&self, schema: &str) -> String {
+        format!("{}:functions:{}", self.namespace, schema)
+    }
+
+    /// 受影响条目的键，或者用于 SCAN 的匹配模式
+    fn keys_for(&self, action: &CacheAction) -> (Vec<String>, Option<String>) {
+        match action {
+            CacheAction::Table { schema, table } => (vec![self.table_key(schema, table)], None),
+            CacheAction::Functions { schema } => (vec![self.functions_key(schema)], None),
+            CacheAction::Schema { schema } => (
+                vec![self.functions_key(schema)],
+                Some(format!(
+                    "{}:table:{}.*",
+                    escape_pattern(&self.namespace),
+                    escape_pattern(schema)
+                )),
+            ),
+            CacheAction::All => (
+                vec![],
+                Some(format!("{}:*", escape_pattern(&self.namespace))),
+            ),
+        }
+    }
+
+    /// 建立连接（只尝试一次，之后断线时 ConnectionManager 也只重连一次）
+    async fn connect(&self) -> RedisResult<ConnectionManager> {
+        tokio::time::timeout(
+            CONNECT_TIMEOUT,
+            ConnectionManager::new_with_backoff(self.client.clone(), 2, 100, 0),
+        )
+        .await
+        .unwrap_or_else(|_| Err((ErrorKind::IoError, "Redis connect timed out").into()))
+    }
+
+    /// 获取连接，尚未连接或熔断器打开时立即失败
+    fn connection(&self) -> RedisResult<ConnectionManager> {
+        if self.lock_breaker().is_open(Instant::now()) {
+            return Err((ErrorKind::IoError, "Redis circuit breaker is open").into());
+        }
+        self.connection
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+            .ok_or_else(|| (ErrorKind::IoError, "Redis is not connected").into())
+    }
+
+    /// 在超时时间内执行操作，连接错误和超时会打开熔断器
+    async fn guarded<T>(
+        &self,
+        timeout: Duration,
+        operation: impl Future<Output = RedisResult<T>>,
+    ) -> RedisResult<T> {
+        let result = tokio::time::timeout(timeout, operation)
+            .await
+            .unwrap_or_else(|_| Err((ErrorKind::IoError, "Redis operation timed out").into()));
+        match &result {
+            Ok(_) => {
+                if self.lock_breaker().reset() {
+                    eprintln!("✅ Redis 已恢复，重新使用共享缓存");
+                }
+            }
+            Err(e) if is_connection_error(e) => {
+                let mut breaker = self.lock_breaker();
+                let already_tripped = breaker.is_tripped();
+                breaker.trip(Instant::now());
+                drop(breaker);
+                if !already_tripped {
+                    eprintln!("⚠️  Redis 不可用，恢复之前跳过共享缓存: {}", e);
+                }
+            }
+            Err(_) => {}
+        }
+        result
+    }
+
+    fn lock_breaker(&self) -> std::sync::MutexGuard<'_, CircuitBreaker> {
+        self.breaker.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// 执行 pipeline
+    async fn query(&self, pipe: &redis::Pipeline) -> RedisResult<()> {
+        let mut conn = self.connection()?;
+        self.guarded(OPERATION_TIMEOUT, pipe.query_async(&mut conn))
+            .await
+    }
+
+    /// 读取并解析 JSON 值，同时读取剩余的过期时间
+    async fn get_json<T: DeserializeOwned>(&self, key: &str) -> Option<SharedEntry<T>> {
+        let mut pipe = redis::pipe();
+        pipe.get(key).pttl(key);
+        let (json, pttl): (Option<String>, i64) = match self.connection() {
+            Ok(mut conn) => self.guarded(OPERATION_TIMEOUT, pipe.query_async(&mut conn)).await,
+            Err(e) => Err(e),
+        }
+        .unwrap_or_else(|e| {
+            report(&format!("读取 Redis 缓存 {} 失败", key), &e);
+            (None, -2)
+        });
+
+        // 结构定义变化后旧的条目无法解析，当作未命中
+        let value = serde_json::from_str(&json?).ok()?;
+        Some(SharedEntry {
+            value,
+            // PTTL 为 -1（没有过期时间）或 -2（键不存在）时为负数
+            ttl: u64::try_from(pttl).ok().map(Duration::from_millis),
+        })
+    }
+
+    /// 删除受影响的条目
+    async fn delete(&self, action: &CacheAction) -> RedisResult<()> {
+        let (mut keys, pattern) = self.keys_for(action);
+        let mut conn = self.connection()?;
+
+        self.guarded(DELETE_TIMEOUT, async move {
+            if let Some(pattern) = pattern {
+                let mut iter = conn.scan_match::<_, String>(pattern).await?;
+                while let Some(key) = iter.next_item().await {
+                    keys.push(key);
+                }
+            }
+            for batch in keys.chunks(DELETE_BATCH_SIZE) {
+                conn.del::<_, ()>(batch).await?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    /// 广播失效消息
+    async fn publish(&self, action: &CacheAction) -> Result<()> {
+        let message = serde_json::to_string(&InvalidationMessage {
+            origin: self.instance_id.clone(),
+            action: action.clone(),
+        })?;
+        let mut conn = self.connection()?;
+        self.guarded(
+            OPERATION_TIMEOUT,
+            conn.publish::<_, _, ()>(self.channel(), message),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// 启动后台订阅任务，收到其他实例的失效消息后丢弃内存中的副本
+///
+/// 连接断开后按指数退避自动重连；断线期间的消息已经丢失，重连后清空内存缓存
+pub fn spawn_invalidation_subscriber(shared: SharedCache, cache: SchemaCache) -> JoinHandle<()> {
+    tokio::spawn(async move { run_subscriber(shared, cache).await })
+}
+
+/// 订阅循环
+async fn run_subscriber(shared: SharedCache, cache: SchemaCache) {
+    let channel = shared.channel();
+    let mut delay = RECONNECT_MIN_DELAY;
+    let mut reconnecting = false;
+
+    loop {
+        let mut pubsub = match subscribe(&shared.client, &channel).await {
+            Ok(pubsub) => pubsub,
+            Err(e) => {
+                eprintln!("⚠️  Redis 订阅连接失败，{:?} 后重试: {}", delay, e);
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(RECONNECT_MAX_DELAY);
+                reconnecting = true;
+                continue;
+            }
+        };
+        if reconnecting {
+            cache.evict(&CacheAction::All).await;
+        }
+        delay = RECONNECT_MIN_DELAY;
+        reconnecting = true;
+
+        let mut messages = pubsub.on_message();
+        while let Some(msg) = messages.next().await {
+            let payload: String = match msg.get_payload() {
+                Ok(payload) => payload,
+                Err(e) => {
+                    eprintln!("⚠️  无法读取缓存失效消息: {}", e);
+                    continue;
+                }
+            };
+            match serde_json::from_str::<InvalidationMessage>(&payload) {
+                Ok(message) if message.origin == shared.instance_id => {}
+                Ok(message) => cache.evict(&message.action).await,
+                Err(e) => eprintln!("⚠️  无效的缓存失效消息: {}: {}", e, payload),
+            }
+        }
+        eprintln!("⚠️  Redis 订阅连接断开");
+    }
+}
+
+/// 建立订阅连接
+async fn subscribe(client: &Client, channel: &str) -> Result<redis::aio::PubSub> {
+    let mut pubsub = client
+        .get_async_connection()
+        .await
+        .context("Failed to connect to Redis")?
+        .into_pubsub();
+    pubsub.subscribe(channel).await?;
+    Ok(pubsub)
+}
+
+/// 打印 Redis 命令的错误
+///
+/// 连接不可用（包括熔断器打开、尚未连接）时不打印，由熔断器在打开和恢复时统一打印
+fn report(message: &str, error: &RedisError) {
+    if !is_connection_error(error) {
+        eprintln!("⚠️  {}: {}", message, error);
+    }
+}
+
+/// 是否为连接不可用导致的错误（而不是命令本身的错误）
+fn is_connection_error(error: &RedisError) -> bool {
+    error.is_io_error()
+        || error.is_timeout()
+        || error.is_connection_dropped()
+        || error.is_connection_refusal()
+}
+
+/// Redis 的过期时间至少为 1 秒
+fn ttl_seconds(ttl: Duration) -> usize {
+    usize::try_from(ttl.as_secs()).unwrap_or(usize::MAX).max(1)
+}
+
+/// 转义 SCAN 匹配模式中的特殊字符
+fn escape_pattern(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shared() -> SharedCache {
+        SharedCache::new(Client::open("redis://127.0.0.1/").unwrap(), "test:schema")
+    }
+
+    #[test]
+    fn test_invalidation_keys() {
+        let shared = shared();
+        assert_eq!(shared.channel(), "test:schema:invalidate");
+
+        let table = CacheAction::Table {
+            schema: "public".to_string(),
+            table: "users".to_string(),
+        };
+        assert_eq!(
+            shared.keys_for(&table),
+            (vec!["test:schema:table:public.users".to_string()], None)
+        );
+
+        let schema = CacheAction::Schema {
+            schema: "my*schema".to_string(),
+        };
+        assert_eq!(
+            shared.keys_for(&schema),
+            (
+                vec!["test:schema:functions:my*schema".to_string()],
+                Some("test:schema:table:my\\*schema.*".to_string())
+            )
+        );
+        assert_eq!(
+            shared.keys_for(&CacheAction::All),
+            (vec![], Some("test:schema:*".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_circuit_breaker() {
+        let now = Instant::now();
+        let mut breaker = CircuitBreaker::default();
+        assert!(!breaker.is_open(now));
+
+        assert_eq!(breaker.trip(now), RECONNECT_MIN_DELAY);
+        assert!(breaker.is_open(now));
+        assert!(!breaker.is_open(now + RECONNECT_MIN_DELAY));
+        assert_eq!(breaker.trip(now), RECONNECT_MIN_DELAY * 2);
+        for _ in 0..10 {
+            breaker.trip(now);
+        }
+        assert_eq!(breaker.trip(now), RECONNECT_MAX_DELAY);
+
+        assert!(breaker.is_tripped());
+        assert!(breaker.reset());
+        assert!(!breaker.is_tripped());
+        assert!(!breaker.reset());
+        assert!(!breaker.is_open(now));
+        assert_eq!(breaker.trip(now), RECONNECT_MIN_DELAY);
+    }
+
+    #[tokio::test]
+    async fn test_fails_fast_before_connecting() {
+        let shared = shared();
+        let started = Instant::now();
+        assert!(shared.get_table("public", "users").await.is_none());
+        shared.invalidate(&CacheAction::All).await;
+        assert!(started.elapsed() < Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_invalidation_message() {
+        let message = InvalidationMessage {
+            origin: shared().instance_id().to_string(),
+            action: CacheAction::Functions {
+                schema: "api".to_string(),
+            },
+        };
+        let json = serde_json::to_string(&message).unwrap();
+        assert!(json.contains(r#""scope":"functions""#));
+        assert_eq!(
+            serde_json::from_str::<InvalidationMessage>(&json).unwrap(),
+            message
+        );
+        assert_ne!(shared().instance_id(), shared().instance_id());
+    }
+}
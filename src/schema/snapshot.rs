@@ -0,0 +1,222 @@
+// Snapshot - Schema 快照文件
+// 将若干 schema 的完整结构（表、视图、函数）序列化为带版本哈希的 JSON 文件，
+// 之后可以在没有数据库的环境中加载到 SchemaCache（代码生成、文档、CI 测试）
+
+use super::{
+    inspector,
+    types::{FunctionInfo, TableSchema},
+};
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// 快照文件格式版本，格式不兼容时递增
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// Schema 快照
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaSnapshot {
+    /// 快照文件格式版本
+    pub format_version: u32,
+    /// 内容哈希（`schemas` 的 SHA-256），结构相同的快照版本相同
+    pub version: String,
+    /// 生成时间
+    pub created_at: DateTime<Utc>,
+    /// 按 schema 名分组的结构
+    pub schemas: BTreeMap<String, SnapshotSchema>,
+}
+
+/// 快照中单个 schema 的结构
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SnapshotSchema {
+    /// 所有表和视图，按名称排序
+    pub tables: Vec<TableSchema>,
+    /// 所有函数和存储过程，按名称和参数排序
+    #[serde(default)]
+    pub functions: Vec<FunctionInfo>,
+}
+
+impl SchemaSnapshot {
+    /// 创建快照并计算版本哈希
+    pub fn new(mut schemas: BTreeMap<String, SnapshotSchema>) -> Result<Self> {
+        for schema in schemas.values_mut() {
+            schema.tables.sort_by(|a, b| a.name.cmp(&b.name));
+            schema.functions.sort_by(|a, b| {
+                (&a.name, &a.identity_arguments).cmp(&(&b.name, &b.identity_arguments))
+            });
+        }
+
+        Ok(Self {
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            version: compute_version(&schemas)?,
+            created_at: Utc::now(),
+            schemas,
+        })
+    }
+
+    /// 检查格式版本和内容哈希（文件被手工修改过时哈希不匹配）
+    pub fn verify(&self) -> Result<()> {
+        if self.format_version != SNAPSHOT_FORMAT_VERSION {
+            bail!(
+                "Unsupported snapshot format version {} (expected {})",
+                self.format_version,
+                SNAPSHOT_FORMAT_VERSION
+            );
+        }
+        let version = compute_version(&self.schemas)?;
+        if version != self.version {
+            bail!(
+                "Snapshot version mismatch: file says {}, content hashes to {}",
+                self.version,
+                version
+            );
+        }
+        Ok(())
+    }
+
+    /// 快照中包含的 schema 名称
+    pub fn schema_names(&self) -> Vec<&str> {
+        self.schemas.keys().map(String::as_str).collect()
+    }
+
+    /// 指定 schema 的所有表，schema 不在快照中时返回 None
+    pub fn tables(&self, schema_name: &str) -> Option<&[TableSchema]> {
+        self.schemas.get(schema_name).map(|s| s.tables.as_slice())
+    }
+
+    /// 指定 schema 的所有函数，schema 不在快照中时返回 None
+    pub fn functions(&self, schema_name: &str) -> Option<&[FunctionInfo]> {
+        self.schemas.get(schema_name).map(|s| s.functions.as_slice())
+    }
+
+    /// 查找指定的表
+    pub fn table(&self, schema_name: &str, table_name: &str) -> Option<&TableSchema> {
+        self.tables(schema_name)?
+            .iter()
+            .find(|t| t.name == table_name)
+    }
+
+    /// 所有表的数量
+    pub fn table_count(&self) -> usize {
+        self.schemas.values().map(|s| s.tables.len()).sum()
+    }
+
+    /// 从 JSON 解析并校验
+    pub fn from_json(json: &str) -> Result<Self> {
+        let snapshot: Self = serde_json::from_str(json).context("Invalid schema snapshot")?;
+        snapshot.verify()?;
+        Ok(snapshot)
+    }
+
+    /// 序列化为格式化的 JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// 从文件读取并校验
+    pub fn read_from_file(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read snapshot {}", path.display()))?;
+        Self::from_json(&json).with_context(|| format!("Failed to load snapshot {}", path.display()))
+    }
+
+    /// 写入文件（父目录不存在时自动创建）
+    pub fn write_to_file(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+        }
+        std::fs::write(path, self.to_json()? + "\n")
+            .with_context(|| format!("Failed to write snapshot {}", path.display()))
+    }
+}
+
+/// 从数据库读取指定 schema 的结构生成快照
+pub async fn capture(pool: &PgPool, schema_names: &[String]) -> Result<SchemaSnapshot> {
+    if schema_names.is_empty() {
+        return Err(anyhow!("At least one schema is required"));
+    }
+
+    let mut schemas = BTreeMap::new();
+    for schema in schema_names {
+        let (tables, functions) = tokio::try_join!(
+            inspector::get_all_table_schemas(pool, Some(schema)),
+            inspector::get_functions(pool, Some(schema)),
+        )?;
+        schemas.insert(schema.clone(), SnapshotSchema { tables, functions });
+    }
+
+    SchemaSnapshot::new(schemas)
+}
+
+/// 内容哈希：`schemas` 序列化后的 SHA-256（十六进制）
+fn compute_version(schemas: &BTreeMap<String, SnapshotSchema>) -> Result<String> {
+    let bytes = serde_json::to_vec(schemas)?;
+    Ok(hex::encode(Sha256::digest(&bytes)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::types::ColumnInfo;
+
+    fn table(name: &str) -> TableSchema {
+        let mut id = ColumnInfo::test("id", "integer", false);
+        id.udt_name = "int4".to_string();
+        id.is_identity = true;
+        TableSchema::test(name, vec![id])
+    }
+
+    fn snapshot(tables: Vec<TableSchema>) -> SchemaSnapshot {
+        let schemas = BTreeMap::from([(
+            "public".to_string(),
+            SnapshotSchema {
+                tables,
+                functions: vec![],
+            },
+        )]);
+        SchemaSnapshot::new(schemas).unwrap()
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let original = snapshot(vec![table("users"), table("posts")]);
+        let json = original.to_json().unwrap();
+        let loaded = SchemaSnapshot::from_json(&json).unwrap();
+
+        assert_eq!(loaded.version, original.version);
+        assert_eq!(loaded.schema_names(), ["public"]);
+        assert_eq!(loaded.table_count(), 2);
+        // 表按名称排序
+        assert_eq!(
+            loaded.tables("public").map(|t| t.first().map(|t| t.name.clone())),
+            Some(Some("posts".to_string()))
+        );
+        assert!(loaded.table("public", "users").is_some());
+        assert!(loaded.table("other", "users").is_none());
+    }
+
+    #[test]
+    fn test_snapshot_version() {
+        // 版本只取决于内容，与表的顺序和生成时间无关
+        let a = snapshot(vec![table("users"), table("posts")]);
+        let b = snapshot(vec![table("posts"), table("users")]);
+        assert_eq!(a.version, b.version);
+        assert_eq!(a.version.len(), 64);
+
+        let c = snapshot(vec![table("users")]);
+        assert_ne!(a.version, c.version);
+
+        // 内容被修改后校验失败
+        let mut tampered = a.clone();
+        if let Some(schema) = tampered.schemas.get_mut("public") {
+            schema.tables.pop();
+        }
+        assert!(tampered.verify().is_err());
+        assert!(a.verify().is_ok());
+    }
+}
@@ -0,0 +1,281 @@
+// Table Stats - 表统计信息和存储大小
+// 从 pg_class 和 pg_stat_user_tables 读取行数估算、磁盘占用、死元组、VACUUM/ANALYZE 时间和扫描次数
+
+use super::types::RelationKind;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+
+/// 表的统计信息和存储大小
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableStats {
+    /// 表名
+    pub name: String,
+    /// 表所属的 schema
+    pub schema: String,
+    /// 关系类型（普通表、物化视图、分区表）
+    pub kind: RelationKind,
+    /// 估算行数（`pg_class.reltuples`），从未 ANALYZE 过时为 None
+    pub estimated_rows: Option<i64>,
+    /// 总占用（表 + 索引 + TOAST）
+    pub total_bytes: i64,
+    /// 表本身的占用（含 FSM/VM，不含索引和 TOAST）
+    pub table_bytes: i64,
+    /// 所有索引的占用
+    pub index_bytes: i64,
+    /// TOAST 表（含其索引）的占用
+    pub toast_bytes: i64,
+    /// 活元组数
+    pub live_tuples: i64,
+    /// 死元组数
+    pub dead_tuples: i64,
+    /// 上次 ANALYZE 之后修改的行数
+    pub modifications_since_analyze: i64,
+    /// 顺序扫描次数
+    pub seq_scans: i64,
+    /// 顺序扫描读取的行数
+    pub seq_tuples_read: i64,
+    /// 索引扫描次数（没有索引时为 None）
+    pub index_scans: Option<i64>,
+    /// 上次手动 VACUUM 时间
+    pub last_vacuum: Option<DateTime<Utc>>,
+    /// 上次自动 VACUUM 时间
+    pub last_autovacuum: Option<DateTime<Utc>>,
+    /// 上次手动 ANALYZE 时间
+    pub last_analyze: Option<DateTime<Utc>>,
+    /// 上次自动 ANALYZE 时间
+    pub last_autoanalyze: Option<DateTime<Utc>>,
+}
+
+/// 统计信息的排序方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StatsSort {
+    /// 按总占用降序
+    Size,
+    /// 按估算行数降序
+    Rows,
+    /// 按死元组数降序
+    DeadTuples,
+    /// 按顺序扫描次数降序
+    SeqScans,
+    /// 按表名升序
+    Name,
+}
+
+impl TableStats {
+    /// 死元组占全部元组的比例
+    pub fn dead_tuple_ratio(&self) -> f64 {
+        let total = self.live_tuples + self.dead_tuples;
+        if total == 0 {
+            0.0
+        } else {
+            self.dead_tuples as f64 / total as f64
+        }
+    }
+
+    /// 是否需要 VACUUM
+    ///
+    /// 使用 autovacuum 的默认阈值：死元组超过 50 + 20% 的估算行数
+    pub fn needs_vacuum(&self) -> bool {
+        let rows = self.estimated_rows.unwrap_or(self.live_tuples).max(0);
+        self.dead_tuples as f64 > 50.0 + 0.2 * rows as f64
+    }
+
+    /// 最近一次 VACUUM（手动或自动）的时间
+    pub fn last_vacuumed(&self) -> Option<DateTime<Utc>> {
+        self.last_vacuum.max(self.last_autovacuum)
+    }
+
+    /// 最近一次 ANALYZE（手动或自动）的时间
+    pub fn last_analyzed(&self) -> Option<DateTime<Utc>> {
+        self.last_analyze.max(self.last_autoanalyze)
+    }
+}
+
+impl StatsSort {
+    /// 从查询参数解析排序方式
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "size" => Some(Self::Size),
+            "rows" => Some(Self::Rows),
+            "dead_tuples" => Some(Self::DeadTuples),
+            "seq_scans" => Some(Self::SeqScans),
+            "name" => Some(Self::Name),
+            _ => None,
+        }
+    }
+}
+
+/// 按指定方式排序（数值降序，表名升序）
+pub fn sort_stats(stats: &mut [TableStats], sort: StatsSort) {
+    match sort {
+        StatsSort::Size => stats.sort_by_key(|s| std::cmp::Reverse(s.total_bytes)),
+        StatsSort::Rows => stats.sort_by_key(|s| std::cmp::Reverse(s.estimated_rows)),
+        StatsSort::DeadTuples => stats.sort_by_key(|s| std::cmp::Reverse(s.dead_tuples)),
+        StatsSort::SeqScans => stats.sort_by_key(|s| std::cmp::Reverse(s.seq_scans)),
+        StatsSort::Name => stats.sort_by(|a, b| a.name.cmp(&b.name)),
+    }
+}
+
+/// 获取 schema 下所有表的统计信息（按表名排序）
+///
+/// # Arguments
+/// * `pool` - PostgreSQL 连接池
+/// * `schema_name` - Schema 名称，默认为 "public"
+pub async fn get_all_table_stats(
+    pool: &PgPool,
+    schema_name: Option<&str>,
+) -> Result<Vec<TableStats>> {
+    fetch_table_stats(pool, schema_name.unwrap_or("public"), None).await
+}
+
+/// 获取指定表的统计信息
+pub async fn get_table_stats(
+    pool: &PgPool,
+    table_name: &str,
+    schema_name: Option<&str>,
+) -> Result<TableStats> {
+    let schema = schema_name.unwrap_or("public");
+
+    fetch_table_stats(pool, schema, Some(table_name))
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("Relation '{}.{}' does not exist", schema, table_name))
+}
+
+/// 查询统计信息，`table_name` 为 None 时查询整个 schema
+///
+/// 视图和外部表没有存储，不包含在内。统计计数来自统计收集器，
+/// 在数据库重启或 `pg_stat_reset()` 后会清零
+async fn fetch_table_stats(
+    pool: &PgPool,
+    schema: &str,
+    table_name: Option<&str>,
+) -> Result<Vec<TableStats>> {
+    let rows = sqlx::query(
+        "SELECT
+            c.relname::text AS table_name,
+            c.relkind::text AS relkind,
+            CASE WHEN c.reltuples < 0 THEN NULL ELSE c.reltuples::int8 END AS estimated_rows,
+            pg_total_relation_size(c.oid) AS total_bytes,
+            pg_indexes_size(c.oid) AS index_bytes,
+            CASE WHEN c.reltoastrelid = 0 THEN 0
+                 ELSE pg_total_relation_size(c.reltoastrelid)
+            END AS toast_bytes,
+            COALESCE(s.n_live_tup, 0) AS live_tuples,
+            COALESCE(s.n_dead_tup, 0) AS dead_tuples,
+            COALESCE(s.n_mod_since_analyze, 0) AS modifications_since_analyze,
+            COALESCE(s.seq_scan, 0) AS seq_scans,
+            COALESCE(s.seq_tup_read, 0) AS seq_tuples_read,
+            s.idx_scan AS index_scans,
+            s.last_vacuum,
+            s.last_autovacuum,
+            s.last_analyze,
+            s.last_autoanalyze
+        FROM pg_class c
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        LEFT JOIN pg_stat_user_tables s ON s.relid = c.oid
+        WHERE n.nspname = $1
+          AND ($2::text IS NULL OR c.relname = $2)
+          AND c.relkind IN ('r', 'm', 'p')
+        ORDER BY c.relname",
+    )
+    .bind(schema)
+    .bind(table_name)
+    .fetch_all(pool)
+    .await
+    .context("Failed to fetch table statistics")?;
+
+    let stats = rows
+        .iter()
+        .filter_map(|row| {
+            let kind = RelationKind::from_relkind(&row.get::<String, _>("relkind"))?;
+            let total_bytes: i64 = row.get("total_bytes");
+            let index_bytes: i64 = row.get("index_bytes");
+            let toast_bytes: i64 = row.get("toast_bytes");
+
+            Some(TableStats {
+                name: row.get("table_name"),
+                schema: schema.to_string(),
+                kind,
+                estimated_rows: row.get("estimated_rows"),
+                total_bytes,
+                table_bytes: total_bytes - index_bytes - toast_bytes,
+                index_bytes,
+                toast_bytes,
+                live_tuples: row.get("live_tuples"),
+                dead_tuples: row.get("dead_tuples"),
+                modifications_since_analyze: row.get("modifications_since_analyze"),
+                seq_scans: row.get("seq_scans"),
+                seq_tuples_read: row.get("seq_tuples_read"),
+                index_scans: row.get("index_scans"),
+                last_vacuum: row.get("last_vacuum"),
+                last_autovacuum: row.get("last_autovacuum"),
+                last_analyze: row.get("last_analyze"),
+                last_autoanalyze: row.get("last_autoanalyze"),
+            })
+        })
+        .collect();
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(name: &str, total_bytes: i64, live_tuples: i64, dead_tuples: i64) -> TableStats {
+        TableStats {
+            name: name.to_string(),
+            schema: "public".to_string(),
+            kind: RelationKind::Table,
+            estimated_rows: Some(live_tuples),
+            total_bytes,
+            table_bytes: total_bytes,
+            index_bytes: 0,
+            toast_bytes: 0,
+            live_tuples,
+            dead_tuples,
+            modifications_since_analyze: 0,
+            seq_scans: 0,
+            seq_tuples_read: 0,
+            index_scans: None,
+            last_vacuum: None,
+            last_autovacuum: None,
+            last_analyze: None,
+            last_autoanalyze: None,
+        }
+    }
+
+    #[test]
+    fn test_needs_vacuum() {
+        assert!(!stats("small", 8192, 100, 60).needs_vacuum());
+        assert!(stats("bloated", 8192, 100, 80).needs_vacuum());
+        assert!(!stats("large", 8192, 10_000, 1_000).needs_vacuum());
+        assert_eq!(stats("empty", 0, 0, 0).dead_tuple_ratio(), 0.0);
+        assert_eq!(stats("half", 0, 50, 50).dead_tuple_ratio(), 0.5);
+    }
+
+    #[test]
+    fn test_sort_stats() {
+        let mut all = vec![
+            stats("b", 100, 5, 9),
+            stats("a", 300, 1, 0),
+            stats("c", 200, 9, 3),
+        ];
+
+        sort_stats(&mut all, StatsSort::Size);
+        assert_eq!(all.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), ["a", "c", "b"]);
+
+        sort_stats(&mut all, StatsSort::DeadTuples);
+        assert_eq!(all.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), ["b", "c", "a"]);
+
+        sort_stats(&mut all, StatsSort::Name);
+        assert_eq!(all.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), ["a", "b", "c"]);
+
+        assert_eq!(StatsSort::parse("rows"), Some(StatsSort::Rows));
+        assert_eq!(StatsSort::parse("bogus"), None);
+    }
+}
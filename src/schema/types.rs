@@ -1,6 +1,7 @@
 // Schema 类型定义
 // 用于表示数据库结构信息
 
+use super::stats::TableStats;
 use serde::{Deserialize, Serialize};
 
 /// 表的完整结构信息
@@ -10,6 +11,9 @@ pub struct TableSchema {
     pub name: String,
     /// 表所属的 schema (通常是 "public")
     pub schema: String,
+    /// 关系类型（普通表、视图、物化视图等）
+    #[serde(default)]
+    pub kind: RelationKind,
     /// 列信息列表
     pub columns: Vec<ColumnInfo>,
     /// 主键列名列表
@@ -18,8 +22,68 @@ pub struct TableSchema {
     pub foreign_keys: Vec<ForeignKeyInfo>,
     /// 索引列表
     pub indexes: Vec<IndexInfo>,
+    /// 约束列表（主键、外键、唯一、CHECK）
+    #[serde(default)]
+    pub constraints: Vec<ConstraintInfo>,
     /// 表注释
     pub comment: Option<String>,
+    /// 视图信息（仅视图和物化视图有值）
+    #[serde(default)]
+    pub view: Option<ViewInfo>,
+    /// 触发器列表（不含外键等内部触发器）
+    #[serde(default)]
+    pub triggers: Vec<TriggerInfo>,
+    /// 行级安全（RLS）状态和策略
+    #[serde(default)]
+    pub row_security: RowSecurityInfo,
+    /// 每个角色的有效权限（考虑角色继承和 PUBLIC，没有任何权限的角色不列出）
+    #[serde(default)]
+    pub privileges: Vec<RolePrivileges>,
+    /// 分区键和子分区（仅分区表有值）
+    #[serde(default)]
+    pub partitioning: Option<PartitioningInfo>,
+    /// 父表：所属的分区表，或 `INHERITS` 继承的父表
+    #[serde(default)]
+    pub parents: Vec<ParentTable>,
+}
+
+/// 关系类型，对应 `pg_class.relkind`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RelationKind {
+    /// 普通表 (`r`)
+    #[default]
+    Table,
+    /// 视图 (`v`)
+    View,
+    /// 物化视图 (`m`)
+    MaterializedView,
+    /// 外部表 (`f`)
+    ForeignTable,
+    /// 分区表的父表 (`p`)
+    PartitionedTable,
+}
+
+/// 视图定义信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ViewInfo {
+    /// 视图定义 SQL（`pg_get_viewdef` 的输出）
+    pub definition: String,
+    /// 是否可自动更新（UPDATE 和 DELETE 都可直接作用于视图）
+    pub is_updatable: bool,
+    /// 是否可直接 INSERT
+    pub is_insertable_into: bool,
+}
+
+/// 关系概要信息（名称 + 类型）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelationSummary {
+    /// 关系名称
+    pub name: String,
+    /// 关系类型
+    pub kind: RelationKind,
+    /// 子分区名称（仅分区表有值，分区本身不作为单独的关系列出）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub partitions: Vec<String>,
 }
 
 /// 列信息
@@ -37,6 +101,9 @@ pub struct ColumnInfo {
     pub default_value: Option<String>,
     /// 是否是自增列 (SERIAL, IDENTITY)
     pub is_identity: bool,
+    /// 标识列的生成方式（仅 IDENTITY 列；不输出空值，旧版本快照的哈希保持不变）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity_generation: Option<IdentityGeneration>,
     /// 最大长度（对于 varchar 等类型）
     pub max_length: Option<i32>,
     /// 数值精度（对于 numeric, decimal）
@@ -47,23 +114,120 @@ pub struct ColumnInfo {
     pub ordinal_position: i32,
     /// 列注释
     pub comment: Option<String>,
+    /// 数组元素类型（仅数组列）
+    #[serde(default)]
+    pub element_type: Option<String>,
+    /// 数组维度（仅数组列）
+    #[serde(default)]
+    pub array_dimensions: Option<i32>,
+    /// 用户定义类型详情（枚举、域、复合类型，或它们的数组）
+    #[serde(default)]
+    pub user_type: Option<UserTypeInfo>,
+}
+
+/// 标识列的生成方式，对应 `pg_attribute.attidentity`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IdentityGeneration {
+    /// `GENERATED ALWAYS` (`a`)
+    Always,
+    /// `GENERATED BY DEFAULT` (`d`)
+    ByDefault,
+}
+
+/// 用户定义类型的种类，对应 `pg_type.typtype`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UserTypeKind {
+    /// 枚举类型 (`e`)
+    Enum,
+    /// 域类型 (`d`)
+    Domain,
+    /// 复合类型 (`c`)
+    Composite,
+}
+
+/// 用户定义类型信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserTypeInfo {
+    /// 类型所属的 schema
+    pub schema: String,
+    /// 类型名称
+    pub name: String,
+    /// 类型种类
+    pub kind: UserTypeKind,
+    /// 枚举值（按定义顺序，仅枚举类型）
+    #[serde(default)]
+    pub enum_labels: Vec<String>,
+    /// 域的基础类型（仅域类型）
+    #[serde(default)]
+    pub base_type: Option<String>,
+    /// 域是否声明了 NOT NULL
+    #[serde(default)]
+    pub not_null: bool,
+    /// 域的默认值
+    #[serde(default)]
+    pub default_value: Option<String>,
+    /// 域的 CHECK 约束
+    #[serde(default)]
+    pub checks: Vec<DomainCheck>,
+    /// 复合类型的字段（按定义顺序）
+    #[serde(default)]
+    pub fields: Vec<CompositeField>,
+    /// 类型注释
+    pub comment: Option<String>,
+}
+
+/// 域类型的 CHECK 约束
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DomainCheck {
+    /// 约束名称
+    pub name: String,
+    /// 约束定义 (例如: "CHECK (VALUE > 0)")
+    pub expression: String,
+}
+
+/// 复合类型字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompositeField {
+    /// 字段名
+    pub name: String,
+    /// 字段类型 (例如: "text", "numeric(10,2)")
+    pub data_type: String,
+    /// 字段位置（1-based）
+    pub position: i32,
 }
 
 /// 外键约束信息
+///
+/// 一个外键约束对应一条记录，复合外键的列对按约束定义顺序排列
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ForeignKeyInfo {
     /// 约束名称
     pub constraint_name: String,
-    /// 本表的列名
-    pub column_name: String,
+    /// 本表列与引用列的对应关系（按约束定义顺序）
+    pub columns: Vec<ForeignKeyColumn>,
+    /// 引用表所属的 schema
+    pub foreign_schema: String,
     /// 引用的表名
     pub foreign_table_name: String,
-    /// 引用的列名
-    pub foreign_column_name: String,
     /// 删除时的行为 (CASCADE, SET NULL, etc.)
     pub on_delete: Option<String>,
     /// 更新时的行为
     pub on_update: Option<String>,
+    /// 匹配类型 (SIMPLE, FULL, PARTIAL)
+    pub match_type: String,
+    /// 是否可延迟检查
+    pub is_deferrable: bool,
+    /// 是否默认延迟到事务提交时检查
+    pub initially_deferred: bool,
+}
+
+/// 外键中的一对列
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForeignKeyColumn {
+    /// 本表的列名
+    pub column_name: String,
+    /// 引用的列名
+    pub foreign_column_name: String,
 }
 
 /// 索引信息
@@ -71,14 +235,49 @@ pub struct ForeignKeyInfo {
 pub struct IndexInfo {
     /// 索引名称
     pub name: String,
-    /// 索引包含的列名列表
+    /// 索引键中的列名列表（按索引定义顺序，不含表达式和 INCLUDE 列）
     pub columns: Vec<String>,
+    /// 索引键（按索引定义顺序，包含表达式键）
+    #[serde(default)]
+    pub keys: Vec<IndexKey>,
+    /// INCLUDE 子句中的附加列
+    #[serde(default)]
+    pub include_columns: Vec<String>,
+    /// 部分索引的 WHERE 条件
+    #[serde(default)]
+    pub predicate: Option<String>,
     /// 是否是唯一索引
     pub is_unique: bool,
     /// 是否是主键索引
     pub is_primary: bool,
+    /// 索引是否有效（CREATE INDEX CONCURRENTLY 失败时为 false）
+    #[serde(default = "default_true")]
+    pub is_valid: bool,
     /// 索引类型 (btree, hash, gist, etc.)
     pub index_type: String,
+    /// 索引占用的磁盘空间（字节）
+    #[serde(default)]
+    pub size_bytes: i64,
+    /// 完整的索引定义 (`pg_get_indexdef`)
+    #[serde(default)]
+    pub definition: String,
+}
+
+/// 索引中的一个键
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexKey {
+    /// 列名（表达式键为 None）
+    pub column: Option<String>,
+    /// 表达式文本（普通列为 None）
+    pub expression: Option<String>,
+    /// 是否降序 (DESC)
+    pub descending: bool,
+    /// NULL 是否排在前面 (NULLS FIRST)
+    pub nulls_first: bool,
+}
+
+fn default_true() -> bool {
+    true
 }
 
 /// 约束类型枚举
@@ -90,18 +289,545 @@ pub enum ConstraintType {
     Check,
 }
 
+/// 表约束信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConstraintInfo {
+    /// 约束名称
+    pub name: String,
+    /// 约束类型
+    pub constraint_type: ConstraintType,
+    /// 约束涉及的列（按约束定义顺序）
+    pub columns: Vec<String>,
+    /// CHECK 约束的表达式 (例如: "(price > (0)::numeric)")
+    pub expression: Option<String>,
+    /// 完整的约束定义 (例如: "UNIQUE (email)")
+    pub definition: String,
+}
+
+impl ConstraintType {
+    /// 从 `pg_constraint.contype` 字符解析约束类型
+    pub fn from_contype(contype: &str) -> Option<Self> {
+        match contype {
+            "p" => Some(Self::PrimaryKey),
+            "f" => Some(Self::ForeignKey),
+            "u" => Some(Self::Unique),
+            "c" => Some(Self::Check),
+            _ => None,
+        }
+    }
+}
+
+/// 触发时机
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TriggerTiming {
+    Before,
+    After,
+    InsteadOf,
+}
+
+/// 触发事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TriggerEvent {
+    Insert,
+    Update,
+    Delete,
+    Truncate,
+}
+
+/// 触发器启用状态，对应 `pg_trigger.tgenabled`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TriggerEnabled {
+    /// 默认启用 (`O`)，`session_replication_role = replica` 时不触发
+    Origin,
+    /// 仅在 replica 模式下触发 (`R`)
+    Replica,
+    /// 总是触发 (`A`)
+    Always,
+    /// 已禁用 (`D`)
+    Disabled,
+}
+
+/// 触发器信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerInfo {
+    /// 触发器名称
+    pub name: String,
+    /// 触发时机
+    pub timing: TriggerTiming,
+    /// 触发事件
+    pub events: Vec<TriggerEvent>,
+    /// 是否是行级触发器（FOR EACH ROW），否则为语句级
+    pub for_each_row: bool,
+    /// `UPDATE OF` 指定的列
+    pub update_columns: Vec<String>,
+    /// WHEN 条件
+    pub condition: Option<String>,
+    /// 触发器函数所属的 schema
+    pub function_schema: String,
+    /// 触发器函数名
+    pub function_name: String,
+    /// 启用状态
+    pub enabled: TriggerEnabled,
+    /// 完整的触发器定义 (`pg_get_triggerdef`)
+    pub definition: String,
+}
+
+/// 行级安全（RLS）信息
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RowSecurityInfo {
+    /// 是否启用 RLS（`ENABLE ROW LEVEL SECURITY`）
+    pub enabled: bool,
+    /// 是否对表的所有者也强制 RLS（`FORCE ROW LEVEL SECURITY`）
+    pub forced: bool,
+    /// 策略列表
+    pub policies: Vec<PolicyInfo>,
+}
+
+/// 策略适用的命令，对应 `pg_policy.polcmd`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PolicyCommand {
+    All,
+    Select,
+    Insert,
+    Update,
+    Delete,
+}
+
+/// RLS 策略信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyInfo {
+    /// 策略名称
+    pub name: String,
+    /// 适用的命令
+    pub command: PolicyCommand,
+    /// 适用的角色（"public" 表示所有角色）
+    pub roles: Vec<String>,
+    /// USING 表达式（过滤可见/可修改的已有行）
+    pub using_expression: Option<String>,
+    /// WITH CHECK 表达式（校验新写入的行）
+    pub with_check_expression: Option<String>,
+    /// 是否是 PERMISSIVE 策略（否则为 RESTRICTIVE）
+    pub permissive: bool,
+}
+
+/// 分区策略，对应 `pg_partitioned_table.partstrat`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PartitionStrategy {
+    Range,
+    List,
+    Hash,
+}
+
+/// 分区表的分区键和子分区
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartitioningInfo {
+    /// 分区策略
+    pub strategy: PartitionStrategy,
+    /// 分区键定义（`pg_get_partkeydef` 的输出，例如 "RANGE (created_at)"）
+    pub key: String,
+    /// 分区键中的列（表达式不包含在内）
+    pub columns: Vec<String>,
+    /// 子分区，按名称排序
+    pub partitions: Vec<PartitionInfo>,
+}
+
+/// 分区表的一个子分区
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartitionInfo {
+    /// 分区所属的 schema
+    pub schema: String,
+    /// 分区名
+    pub name: String,
+    /// 分区边界 (例如: "FOR VALUES FROM ('2024-01-01') TO ('2024-02-01')", "DEFAULT")
+    pub bound: String,
+    /// 是否是默认分区
+    pub is_default: bool,
+    /// 分区本身是否也是分区表（多级分区）
+    pub is_partitioned: bool,
+}
+
+/// 父表信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParentTable {
+    /// 父表所属的 schema
+    pub schema: String,
+    /// 父表名
+    pub name: String,
+    /// 分区边界（本表是父表的分区时有值，`INHERITS` 继承时为 None）
+    pub partition_bound: Option<String>,
+}
+
+impl PartitionStrategy {
+    /// 从 `pg_partitioned_table.partstrat` 字符解析分区策略
+    pub fn from_partstrat(partstrat: &str) -> Option<Self> {
+        match partstrat {
+            "r" => Some(Self::Range),
+            "l" => Some(Self::List),
+            "h" => Some(Self::Hash),
+            _ => None,
+        }
+    }
+}
+
+impl TriggerTiming {
+    /// 从 `pg_trigger.tgtype` 位掩码解析触发时机
+    pub fn from_tgtype(tgtype: i32) -> Self {
+        if tgtype & 64 != 0 {
+            Self::InsteadOf
+        } else if tgtype & 2 != 0 {
+            Self::Before
+        } else {
+            Self::After
+        }
+    }
+}
+
+impl TriggerEvent {
+    /// 从 `pg_trigger.tgtype` 位掩码解析触发事件
+    pub fn from_tgtype(tgtype: i32) -> Vec<Self> {
+        [
+            (4, Self::Insert),
+            (16, Self::Update),
+            (8, Self::Delete),
+            (32, Self::Truncate),
+        ]
+        .into_iter()
+        .filter(|(bit, _)| tgtype & bit != 0)
+        .map(|(_, event)| event)
+        .collect()
+    }
+}
+
+impl TriggerEnabled {
+    /// 从 `pg_trigger.tgenabled` 字符解析启用状态
+    pub fn from_tgenabled(tgenabled: &str) -> Option<Self> {
+        match tgenabled {
+            "O" => Some(Self::Origin),
+            "R" => Some(Self::Replica),
+            "A" => Some(Self::Always),
+            "D" => Some(Self::Disabled),
+            _ => None,
+        }
+    }
+}
+
+impl PolicyCommand {
+    /// 从 `pg_policy.polcmd` 字符解析命令
+    pub fn from_polcmd(polcmd: &str) -> Option<Self> {
+        match polcmd {
+            "*" => Some(Self::All),
+            "r" => Some(Self::Select),
+            "a" => Some(Self::Insert),
+            "w" => Some(Self::Update),
+            "d" => Some(Self::Delete),
+            _ => None,
+        }
+    }
+}
+
+/// 表和列的权限类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Privilege {
+    Select,
+    Insert,
+    Update,
+    Delete,
+    Truncate,
+    References,
+    Trigger,
+}
+
+/// 单个角色在某个表上的有效权限
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RolePrivileges {
+    /// 角色名
+    pub role: String,
+    /// 表级权限（对所有列生效）
+    pub table_privileges: Vec<Privilege>,
+    /// 只授予了部分列的权限（不重复表级权限）
+    pub column_privileges: Vec<ColumnPrivileges>,
+}
+
+/// 单个列上额外授予的权限
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnPrivileges {
+    /// 列名
+    pub column: String,
+    /// 权限列表（只可能是 SELECT / INSERT / UPDATE / REFERENCES）
+    pub privileges: Vec<Privilege>,
+}
+
+impl Privilege {
+    /// 表级权限（`has_table_privilege` 可检查的全部权限）
+    pub const TABLE: [Privilege; 7] = [
+        Self::Select,
+        Self::Insert,
+        Self::Update,
+        Self::Delete,
+        Self::Truncate,
+        Self::References,
+        Self::Trigger,
+    ];
+
+    /// 可以单独授予到列的权限
+    pub const COLUMN: [Privilege; 4] = [
+        Self::Select,
+        Self::Insert,
+        Self::Update,
+        Self::References,
+    ];
+
+    /// 从 SQL 权限名解析 (例如: "SELECT")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "SELECT" => Some(Self::Select),
+            "INSERT" => Some(Self::Insert),
+            "UPDATE" => Some(Self::Update),
+            "DELETE" => Some(Self::Delete),
+            "TRUNCATE" => Some(Self::Truncate),
+            "REFERENCES" => Some(Self::References),
+            "TRIGGER" => Some(Self::Trigger),
+            _ => None,
+        }
+    }
+
+    /// SQL 权限名
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            Self::Select => "SELECT",
+            Self::Insert => "INSERT",
+            Self::Update => "UPDATE",
+            Self::Delete => "DELETE",
+            Self::Truncate => "TRUNCATE",
+            Self::References => "REFERENCES",
+            Self::Trigger => "TRIGGER",
+        }
+    }
+}
+
+impl RolePrivileges {
+    /// 角色是否拥有指定权限
+    ///
+    /// 指定列时，表级权限或该列上的列级权限都算拥有
+    pub fn can(&self, privilege: Privilege, column: Option<&str>) -> bool {
+        if self.table_privileges.contains(&privilege) {
+            return true;
+        }
+        column.is_some_and(|column| {
+            self.column_privileges
+                .iter()
+                .any(|c| c.column == column && c.privileges.contains(&privilege))
+        })
+    }
+}
+
+/// 函数种类，对应 `pg_proc.prokind`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FunctionKind {
+    /// 普通函数 (`f`)
+    Function,
+    /// 存储过程 (`p`)
+    Procedure,
+    /// 聚合函数 (`a`)
+    Aggregate,
+    /// 窗口函数 (`w`)
+    Window,
+}
+
+/// 函数易变性，对应 `pg_proc.provolatile`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Volatility {
+    Immutable,
+    Stable,
+    Volatile,
+}
+
+/// 参数模式，对应 `pg_proc.proargmodes`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ArgumentMode {
+    In,
+    Out,
+    InOut,
+    Variadic,
+    /// `RETURNS TABLE(...)` 中的输出列
+    Table,
+}
+
+/// 函数参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionArgument {
+    /// 参数名（未命名参数为 None）
+    pub name: Option<String>,
+    /// 参数模式
+    pub mode: ArgumentMode,
+    /// 参数类型 (例如: "integer", "text[]")
+    pub data_type: String,
+    /// 默认值表达式
+    pub default_value: Option<String>,
+}
+
+/// 存储函数/过程信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionInfo {
+    /// 函数所属的 schema
+    pub schema: String,
+    /// 函数名
+    pub name: String,
+    /// 函数种类
+    pub kind: FunctionKind,
+    /// 参数列表（包含 OUT 和 TABLE 参数，按定义顺序）
+    pub arguments: Vec<FunctionArgument>,
+    /// 用于区分重载的参数签名 (例如: "user_id integer, active boolean")
+    pub identity_arguments: String,
+    /// 返回类型 (例如: "integer", "SETOF users", "TABLE(id integer)")，过程为 None
+    pub return_type: Option<String>,
+    /// 是否返回集合（SETOF / TABLE）
+    pub returns_set: bool,
+    /// 易变性
+    pub volatility: Volatility,
+    /// 是否是 SECURITY DEFINER
+    pub security_definer: bool,
+    /// 实现语言 (plpgsql, sql, c, etc.)
+    pub language: String,
+    /// 函数注释
+    pub comment: Option<String>,
+}
+
+/// 序列信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequenceInfo {
+    /// 序列所属的 schema
+    pub schema: String,
+    /// 序列名
+    pub name: String,
+    /// 数据类型 (smallint, integer, bigint)
+    pub data_type: String,
+    /// 起始值
+    pub start_value: i64,
+    /// 最小值
+    pub min_value: i64,
+    /// 最大值
+    pub max_value: i64,
+    /// 步长
+    pub increment: i64,
+    /// 达到边界后是否循环
+    pub cycle: bool,
+    /// 每个会话预分配的值的数量
+    pub cache_size: i64,
+    /// 当前值（尚未调用过 nextval 或没有权限读取时为 None）
+    pub last_value: Option<i64>,
+    /// 拥有该序列的列（`OWNED BY`、serial 或标识列）
+    pub owned_by: Option<SequenceOwner>,
+    /// 序列注释
+    pub comment: Option<String>,
+}
+
+/// 拥有序列的列（与序列在同一个 schema 中）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequenceOwner {
+    /// 表名
+    pub table: String,
+    /// 列名
+    pub column: String,
+    /// 是否是标识列（`GENERATED ... AS IDENTITY`）的内部序列
+    pub is_identity: bool,
+}
+
+/// 已安装的扩展
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtensionInfo {
+    /// 扩展名
+    pub name: String,
+    /// 已安装的版本
+    pub version: String,
+    /// 扩展对象所在的 schema
+    pub schema: String,
+    /// 可用的默认版本（`ALTER EXTENSION ... UPDATE` 会升级到该版本）
+    pub default_version: Option<String>,
+    /// 是否可以移动到其他 schema
+    pub relocatable: bool,
+    /// 扩展注释
+    pub comment: Option<String>,
+}
+
+impl ExtensionInfo {
+    /// 是否有可升级的版本
+    pub fn has_update(&self) -> bool {
+        self.default_version
+            .as_deref()
+            .is_some_and(|version| version != self.version)
+    }
+}
+
+/// 数据库中的 schema（命名空间）信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaInfo {
+    /// Schema 名称
+    pub name: String,
+    /// 所有者角色
+    pub owner: String,
+    /// 普通表和分区表数量
+    pub table_count: i64,
+    /// 视图和物化视图数量
+    pub view_count: i64,
+    /// Schema 注释
+    pub comment: Option<String>,
+}
+
 /// 数据库 Schema 概览
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SchemaOverview {
     /// Schema 名称 (例如: "public")
     pub name: String,
-    /// 该 schema 下的表列表
+    /// 该 schema 下的表列表（包含视图、物化视图和外部表）
     pub tables: Vec<String>,
     /// 表数量
     pub table_count: usize,
+    /// 每个关系的名称和类型
+    #[serde(default)]
+    pub relations: Vec<RelationSummary>,
+    /// 每个表的完整结构（仅详细概览时填充）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub table_schemas: Vec<TableSchema>,
+    /// 每个表的统计信息和存储大小（仅请求统计时填充）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub table_stats: Vec<TableStats>,
+}
+
+impl RelationKind {
+    /// 从 `pg_class.relkind` 字符解析关系类型
+    pub fn from_relkind(relkind: &str) -> Option<Self> {
+        match relkind {
+            "r" => Some(Self::Table),
+            "v" => Some(Self::View),
+            "m" => Some(Self::MaterializedView),
+            "f" => Some(Self::ForeignTable),
+            "p" => Some(Self::PartitionedTable),
+            _ => None,
+        }
+    }
+
+    /// 是否是视图或物化视图
+    pub fn is_view(&self) -> bool {
+        matches!(self, Self::View | Self::MaterializedView)
+    }
 }
 
 impl TableSchema {
+    /// 获取指定角色的有效权限
+    pub fn privileges_for(&self, role: &str) -> Option<&RolePrivileges> {
+        self.privileges.iter().find(|p| p.role == role)
+    }
+
+    /// 拥有指定权限的角色（指定列时包含只有该列权限的角色）
+    pub fn roles_with(&self, privilege: Privilege, column: Option<&str>) -> Vec<&str> {
+        self.privileges
+            .iter()
+            .filter(|p| p.can(privilege, column))
+            .map(|p| p.role.as_str())
+            .collect()
+    }
+
     /// 获取指定列的信息
     pub fn get_column(&self, name: &str) -> Option<&ColumnInfo> {
         self.columns.iter().find(|c| c.name == name)
@@ -117,11 +843,55 @@ impl TableSchema {
         self.primary_keys.iter().any(|pk| pk == column_name)
     }
 
+    /// 本表所属的分区表（本表不是分区时返回 None）
+    pub fn partition_parent(&self) -> Option<&ParentTable> {
+        self.parents.iter().find(|p| p.partition_bound.is_some())
+    }
+
+    /// 是否是某个分区表的分区
+    pub fn is_partition(&self) -> bool {
+        self.partition_parent().is_some()
+    }
+
     /// 获取所有可空列
     pub fn nullable_columns(&self) -> Vec<&ColumnInfo> {
         self.columns.iter().filter(|c| c.is_nullable).collect()
     }
 
+    /// 获取指定类型的约束
+    pub fn constraints_of(&self, constraint_type: ConstraintType) -> Vec<&ConstraintInfo> {
+        self.constraints
+            .iter()
+            .filter(|c| c.constraint_type == constraint_type)
+            .collect()
+    }
+
+    /// 列集合是否恰好是主键或某个唯一约束（与顺序无关）
+    pub fn is_unique_key(&self, columns: &[&str]) -> bool {
+        let same_columns = |key: &[String]| {
+            key.len() == columns.len() && key.iter().all(|k| columns.contains(&k.as_str()))
+        };
+
+        same_columns(&self.primary_keys)
+            || self
+                .constraints_of(ConstraintType::Unique)
+                .iter()
+                .any(|c| same_columns(&c.columns))
+    }
+
+    /// 获取涉及指定列的唯一约束（包含主键）
+    pub fn unique_constraints_for(&self, column_name: &str) -> Vec<&ConstraintInfo> {
+        self.constraints
+            .iter()
+            .filter(|c| {
+                matches!(
+                    c.constraint_type,
+                    ConstraintType::PrimaryKey | ConstraintType::Unique
+                ) && c.columns.iter().any(|col| col == column_name)
+            })
+            .collect()
+    }
+
     /// 获取所有必填列（不可空且无默认值）
     pub fn required_columns(&self) -> Vec<&ColumnInfo> {
         self.columns
@@ -131,44 +901,302 @@ impl TableSchema {
     }
 }
 
+impl ForeignKeyInfo {
+    /// 本表的列名（按约束定义顺序）
+    pub fn local_columns(&self) -> Vec<&str> {
+        self.columns.iter().map(|c| c.column_name.as_str()).collect()
+    }
+
+    /// 引用的列名（按约束定义顺序）
+    pub fn foreign_columns(&self) -> Vec<&str> {
+        self.columns
+            .iter()
+            .map(|c| c.foreign_column_name.as_str())
+            .collect()
+    }
+
+    /// 是否是复合外键
+    pub fn is_composite(&self) -> bool {
+        self.columns.len() > 1
+    }
+}
+
+impl IndexInfo {
+    /// 是否是部分索引（带 WHERE 条件）
+    pub fn is_partial(&self) -> bool {
+        self.predicate.is_some()
+    }
+
+    /// 是否包含表达式键
+    pub fn has_expressions(&self) -> bool {
+        self.keys.iter().any(|k| k.expression.is_some())
+    }
+}
+
+impl FunctionKind {
+    /// 从 `pg_proc.prokind` 字符解析函数种类
+    pub fn from_prokind(prokind: &str) -> Option<Self> {
+        match prokind {
+            "f" => Some(Self::Function),
+            "p" => Some(Self::Procedure),
+            "a" => Some(Self::Aggregate),
+            "w" => Some(Self::Window),
+            _ => None,
+        }
+    }
+}
+
+impl Volatility {
+    /// 从 `pg_proc.provolatile` 字符解析易变性
+    pub fn from_provolatile(provolatile: &str) -> Option<Self> {
+        match provolatile {
+            "i" => Some(Self::Immutable),
+            "s" => Some(Self::Stable),
+            "v" => Some(Self::Volatile),
+            _ => None,
+        }
+    }
+}
+
+impl ArgumentMode {
+    /// 从 `pg_proc.proargmodes` 字符解析参数模式
+    pub fn from_argmode(argmode: &str) -> Option<Self> {
+        match argmode {
+            "i" => Some(Self::In),
+            "o" => Some(Self::Out),
+            "b" => Some(Self::InOut),
+            "v" => Some(Self::Variadic),
+            "t" => Some(Self::Table),
+            _ => None,
+        }
+    }
+
+    /// 是否是调用时需要传入的参数
+    pub fn is_input(&self) -> bool {
+        matches!(self, Self::In | Self::InOut | Self::Variadic)
+    }
+
+    /// 是否出现在返回结果中
+    pub fn is_output(&self) -> bool {
+        matches!(self, Self::Out | Self::InOut | Self::Table)
+    }
+}
+
+impl FunctionInfo {
+    /// 调用时需要传入的参数
+    pub fn input_arguments(&self) -> Vec<&FunctionArgument> {
+        self.arguments.iter().filter(|a| a.mode.is_input()).collect()
+    }
+
+    /// 返回结果中的列（OUT / INOUT / TABLE 参数）
+    pub fn output_columns(&self) -> Vec<&FunctionArgument> {
+        self.arguments.iter().filter(|a| a.mode.is_output()).collect()
+    }
+
+    /// 是否可以直接调用（排除聚合函数、窗口函数和触发器函数）
+    pub fn is_callable(&self) -> bool {
+        matches!(self.kind, FunctionKind::Function | FunctionKind::Procedure)
+            && !matches!(self.return_type.as_deref(), Some("trigger" | "event_trigger"))
+    }
+}
+
+impl IdentityGeneration {
+    /// 从 `pg_attribute.attidentity` 字符解析生成方式（非标识列为空字符串）
+    pub fn from_attidentity(attidentity: &str) -> Option<Self> {
+        match attidentity {
+            "a" => Some(Self::Always),
+            "d" => Some(Self::ByDefault),
+            _ => None,
+        }
+    }
+
+    /// DDL 中的写法
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            Self::Always => "ALWAYS",
+            Self::ByDefault => "BY DEFAULT",
+        }
+    }
+}
+
+impl UserTypeKind {
+    /// 从 `pg_type.typtype` 字符解析类型种类
+    pub fn from_typtype(typtype: &str) -> Option<Self> {
+        match typtype {
+            "e" => Some(Self::Enum),
+            "d" => Some(Self::Domain),
+            "c" => Some(Self::Composite),
+            _ => None,
+        }
+    }
+}
+
 impl ColumnInfo {
+    /// 可用于 DDL 的完整列类型 (例如: "character varying(255)", "numeric(10,2)", "text[]")
+    pub fn sql_type(&self) -> String {
+        let array_suffix = "[]".repeat(self.array_dimensions.unwrap_or(1).max(1) as usize);
+
+        // 域、枚举、复合类型使用带 schema 的类型名
+        if let Some(user_type) = &self.user_type {
+            let name = format!("{}.{}", user_type.schema, user_type.name);
+            return if self.is_array() {
+                name + &array_suffix
+            } else {
+                name
+            };
+        }
+
+        if self.is_array() {
+            let element = match &self.element_type {
+                Some(element) => element.clone(),
+                None => self.udt_name.trim_start_matches('_').to_string(),
+            };
+            return element + &array_suffix;
+        }
+
+        match self.data_type.as_str() {
+            "character varying" | "character" | "bit" | "bit varying" => match self.max_length {
+                Some(length) => format!("{}({})", self.data_type, length),
+                None => self.data_type.clone(),
+            },
+            "numeric" => match (self.numeric_precision, self.numeric_scale) {
+                (Some(precision), Some(scale)) => format!("numeric({},{})", precision, scale),
+                _ => self.data_type.clone(),
+            },
+            "USER-DEFINED" => self.udt_name.clone(),
+            _ => self.data_type.clone(),
+        }
+    }
+
+    /// 是否是数组类型
+    pub fn is_array(&self) -> bool {
+        self.data_type == "ARRAY"
+    }
+
+    /// 枚举列（或枚举数组列）的可选值
+    pub fn enum_values(&self) -> Option<&[String]> {
+        self.user_type
+            .as_ref()
+            .filter(|t| t.kind == UserTypeKind::Enum)
+            .map(|t| t.enum_labels.as_slice())
+    }
+
     /// 是否是数值类型
     pub fn is_numeric(&self) -> bool {
-        matches!(
-            self.data_type.as_str(),
-            "integer" | "bigint" | "smallint" | "numeric" | "decimal" | "real" | "double precision"
-        )
+        self.pg_type().is_numeric()
     }
 
     /// 是否是字符串类型
     pub fn is_text(&self) -> bool {
-        matches!(
-            self.data_type.as_str(),
-            "character varying" | "varchar" | "character" | "char" | "text"
-        )
+        self.pg_type().is_text()
     }
 
-    /// 是否是时间类型
+    /// 是否是日期/时间类型（date, time, timestamp, interval）
     pub fn is_temporal(&self) -> bool {
-        matches!(
-            self.data_type.as_str(),
-            "timestamp"
-                | "timestamptz"
-                | "timestamp with time zone"
-                | "timestamp without time zone"
-                | "date"
-                | "time"
-        )
+        self.pg_type().is_temporal()
     }
 
     /// 是否是布尔类型
     pub fn is_boolean(&self) -> bool {
-        self.data_type == "boolean"
+        self.pg_type().is_boolean()
     }
 
     /// 是否是 JSON 类型
     pub fn is_json(&self) -> bool {
-        matches!(self.data_type.as_str(), "json" | "jsonb")
+        self.pg_type().is_json()
+    }
+}
+
+// 测试用的构造函数，各生成器的测试共用
+
+#[cfg(test)]
+impl ColumnInfo {
+    /// 除名称、类型和可为空性外都取默认值的列（`udt_name` 与 `data_type` 相同）
+    pub(crate) fn test(name: &str, data_type: &str, is_nullable: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            udt_name: data_type.to_string(),
+            is_nullable,
+            default_value: None,
+            is_identity: false,
+            identity_generation: None,
+            max_length: None,
+            numeric_precision: None,
+            numeric_scale: None,
+            ordinal_position: 1,
+            comment: None,
+            element_type: None,
+            array_dimensions: None,
+            user_type: None,
+        }
+    }
+}
+
+#[cfg(test)]
+impl TableSchema {
+    /// public schema 下以 id 为主键的普通表
+    pub(crate) fn test(name: &str, columns: Vec<ColumnInfo>) -> Self {
+        Self {
+            name: name.to_string(),
+            schema: "public".to_string(),
+            kind: RelationKind::Table,
+            columns,
+            primary_keys: vec!["id".to_string()],
+            foreign_keys: vec![],
+            indexes: vec![],
+            constraints: vec![],
+            comment: None,
+            view: None,
+            triggers: vec![],
+            row_security: RowSecurityInfo::default(),
+            privileges: vec![],
+            partitioning: None,
+            parents: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+impl ForeignKeyInfo {
+    /// 引用 public schema 下 `foreign_table` 的外键，`columns` 为 (本表列, 被引用列)
+    pub(crate) fn test(name: &str, columns: &[(&str, &str)], foreign_table: &str) -> Self {
+        Self {
+            constraint_name: name.to_string(),
+            columns: columns
+                .iter()
+                .map(|(local, foreign)| ForeignKeyColumn {
+                    column_name: local.to_string(),
+                    foreign_column_name: foreign.to_string(),
+                })
+                .collect(),
+            foreign_schema: "public".to_string(),
+            foreign_table_name: foreign_table.to_string(),
+            on_delete: None,
+            on_update: None,
+            match_type: "SIMPLE".to_string(),
+            is_deferrable: false,
+            initially_deferred: false,
+        }
+    }
+}
+
+#[cfg(test)]
+impl UserTypeInfo {
+    /// public schema 下的枚举类型
+    pub(crate) fn test_enum(name: &str, labels: &[&str]) -> Self {
+        Self {
+            schema: "public".to_string(),
+            name: name.to_string(),
+            kind: UserTypeKind::Enum,
+            enum_labels: labels.iter().map(|label| label.to_string()).collect(),
+            base_type: None,
+            not_null: false,
+            default_value: None,
+            checks: vec![],
+            fields: vec![],
+            comment: None,
+        }
     }
 }
 
@@ -181,6 +1209,7 @@ mod tests {
         let schema = TableSchema {
             name: "users".to_string(),
             schema: "public".to_string(),
+            kind: RelationKind::Table,
             columns: vec![
                 ColumnInfo {
                     name: "id".to_string(),
@@ -189,11 +1218,15 @@ mod tests {
                     is_nullable: false,
                     default_value: None,
                     is_identity: true,
+                    identity_generation: Some(IdentityGeneration::ByDefault),
                     max_length: None,
                     numeric_precision: Some(32),
                     numeric_scale: Some(0),
                     ordinal_position: 1,
                     comment: None,
+                    element_type: None,
+                    array_dimensions: None,
+                    user_type: None,
                 },
                 ColumnInfo {
                     name: "email".to_string(),
@@ -202,17 +1235,43 @@ mod tests {
                     is_nullable: false,
                     default_value: None,
                     is_identity: false,
+                    identity_generation: None,
                     max_length: Some(255),
                     numeric_precision: None,
                     numeric_scale: None,
                     ordinal_position: 2,
                     comment: None,
+                    element_type: None,
+                    array_dimensions: None,
+                    user_type: None,
                 },
             ],
             primary_keys: vec!["id".to_string()],
             foreign_keys: vec![],
             indexes: vec![],
+            constraints: vec![
+                ConstraintInfo {
+                    name: "users_pkey".to_string(),
+                    constraint_type: ConstraintType::PrimaryKey,
+                    columns: vec!["id".to_string()],
+                    expression: None,
+                    definition: "PRIMARY KEY (id)".to_string(),
+                },
+                ConstraintInfo {
+                    name: "users_email_key".to_string(),
+                    constraint_type: ConstraintType::Unique,
+                    columns: vec!["email".to_string()],
+                    expression: None,
+                    definition: "UNIQUE (email)".to_string(),
+                },
+            ],
             comment: None,
+            view: None,
+            triggers: vec![],
+            row_security: RowSecurityInfo::default(),
+            privileges: vec![],
+            partitioning: None,
+            parents: vec![],
         };
 
         assert!(schema.has_column("id"));
@@ -225,6 +1284,13 @@ mod tests {
         let required = schema.required_columns();
         assert_eq!(required.len(), 1);
         assert_eq!(required[0].name, "email");
+
+        assert_eq!(schema.constraints_of(ConstraintType::Unique).len(), 1);
+        assert!(schema.constraints_of(ConstraintType::Check).is_empty());
+        assert_eq!(schema.unique_constraints_for("email").len(), 1);
+        assert_eq!(schema.unique_constraints_for("id").len(), 1);
+        assert_eq!(ConstraintType::from_contype("c"), Some(ConstraintType::Check));
+        assert_eq!(ConstraintType::from_contype("x"), None);
     }
 
     #[test]
@@ -236,11 +1302,15 @@ mod tests {
             is_nullable: false,
             default_value: None,
             is_identity: false,
+            identity_generation: None,
             max_length: None,
             numeric_precision: Some(32),
             numeric_scale: Some(0),
             ordinal_position: 1,
             comment: None,
+            element_type: None,
+            array_dimensions: None,
+            user_type: None,
         };
 
         assert!(int_col.is_numeric());
@@ -254,15 +1324,214 @@ mod tests {
             is_nullable: true,
             default_value: None,
             is_identity: false,
+            identity_generation: None,
             max_length: None,
             numeric_precision: None,
             numeric_scale: None,
             ordinal_position: 2,
             comment: None,
+            element_type: None,
+            array_dimensions: None,
+            user_type: None,
         };
 
         assert!(!text_col.is_numeric());
         assert!(text_col.is_text());
         assert!(!text_col.is_temporal());
+
+        assert_eq!(int_col.sql_type(), "integer");
+        assert_eq!(text_col.sql_type(), "text");
+
+        let varchar_col = ColumnInfo {
+            data_type: "character varying".to_string(),
+            udt_name: "varchar".to_string(),
+            max_length: Some(255),
+            ..text_col.clone()
+        };
+        assert_eq!(varchar_col.sql_type(), "character varying(255)");
+
+        let array_col = ColumnInfo {
+            data_type: "ARRAY".to_string(),
+            udt_name: "_text".to_string(),
+            element_type: Some("text".to_string()),
+            array_dimensions: Some(2),
+            ..text_col
+        };
+        assert_eq!(array_col.sql_type(), "text[][]");
+    }
+
+    #[test]
+    fn test_relation_kind_from_relkind() {
+        assert_eq!(RelationKind::from_relkind("r"), Some(RelationKind::Table));
+        assert_eq!(RelationKind::from_relkind("v"), Some(RelationKind::View));
+        assert_eq!(
+            RelationKind::from_relkind("m"),
+            Some(RelationKind::MaterializedView)
+        );
+        assert_eq!(RelationKind::from_relkind("f"), Some(RelationKind::ForeignTable));
+        assert_eq!(
+            RelationKind::from_relkind("p"),
+            Some(RelationKind::PartitionedTable)
+        );
+        assert_eq!(RelationKind::from_relkind("i"), None);
+
+        assert!(RelationKind::View.is_view());
+        assert!(RelationKind::MaterializedView.is_view());
+        assert!(!RelationKind::Table.is_view());
+    }
+
+    #[test]
+    fn test_enum_values() {
+        let mut col = ColumnInfo {
+            name: "status".to_string(),
+            data_type: "USER-DEFINED".to_string(),
+            udt_name: "order_status".to_string(),
+            is_nullable: false,
+            default_value: None,
+            is_identity: false,
+            identity_generation: None,
+            max_length: None,
+            numeric_precision: None,
+            numeric_scale: None,
+            ordinal_position: 1,
+            comment: None,
+            element_type: None,
+            array_dimensions: None,
+            user_type: Some(UserTypeInfo {
+                schema: "public".to_string(),
+                name: "order_status".to_string(),
+                kind: UserTypeKind::Enum,
+                enum_labels: vec!["pending".to_string(), "shipped".to_string()],
+                base_type: None,
+                not_null: false,
+                default_value: None,
+                checks: vec![],
+                fields: vec![],
+                comment: None,
+            }),
+        };
+
+        assert_eq!(
+            col.enum_values(),
+            Some(["pending".to_string(), "shipped".to_string()].as_slice())
+        );
+        assert!(!col.is_array());
+
+        if let Some(user_type) = col.user_type.as_mut() {
+            user_type.kind = UserTypeKind::Domain;
+        }
+        assert_eq!(col.enum_values(), None);
+        assert_eq!(UserTypeKind::from_typtype("e"), Some(UserTypeKind::Enum));
+        assert_eq!(UserTypeKind::from_typtype("b"), None);
+    }
+
+    #[test]
+    fn test_composite_foreign_key_columns() {
+        let fk = ForeignKeyInfo {
+            constraint_name: "orders_customer_fkey".to_string(),
+            columns: vec![
+                ForeignKeyColumn {
+                    column_name: "tenant_id".to_string(),
+                    foreign_column_name: "tenant_id".to_string(),
+                },
+                ForeignKeyColumn {
+                    column_name: "customer_id".to_string(),
+                    foreign_column_name: "id".to_string(),
+                },
+            ],
+            foreign_schema: "public".to_string(),
+            foreign_table_name: "customers".to_string(),
+            on_delete: Some("CASCADE".to_string()),
+            on_update: Some("NO ACTION".to_string()),
+            match_type: "SIMPLE".to_string(),
+            is_deferrable: false,
+            initially_deferred: false,
+        };
+
+        assert!(fk.is_composite());
+        assert_eq!(fk.local_columns(), vec!["tenant_id", "customer_id"]);
+        assert_eq!(fk.foreign_columns(), vec!["tenant_id", "id"]);
+    }
+
+    #[test]
+    fn test_function_argument_modes() {
+        let arg = |name: &str, mode: ArgumentMode| FunctionArgument {
+            name: Some(name.to_string()),
+            mode,
+            data_type: "integer".to_string(),
+            default_value: None,
+        };
+        let function = FunctionInfo {
+            schema: "public".to_string(),
+            name: "top_customers".to_string(),
+            kind: FunctionKind::Function,
+            arguments: vec![
+                arg("min_orders", ArgumentMode::In),
+                arg("customer_id", ArgumentMode::Table),
+                arg("order_count", ArgumentMode::Table),
+            ],
+            identity_arguments: "min_orders integer".to_string(),
+            return_type: Some("TABLE(customer_id integer, order_count integer)".to_string()),
+            returns_set: true,
+            volatility: Volatility::Stable,
+            security_definer: false,
+            language: "sql".to_string(),
+            comment: None,
+        };
+
+        assert_eq!(function.input_arguments().len(), 1);
+        assert_eq!(function.output_columns().len(), 2);
+        assert!(ArgumentMode::InOut.is_input() && ArgumentMode::InOut.is_output());
+        assert_eq!(ArgumentMode::from_argmode("t"), Some(ArgumentMode::Table));
+        assert_eq!(FunctionKind::from_prokind("p"), Some(FunctionKind::Procedure));
+        assert_eq!(Volatility::from_provolatile("i"), Some(Volatility::Immutable));
+    }
+
+    #[test]
+    fn test_trigger_and_policy_parsing() {
+        // BEFORE INSERT OR UPDATE ... FOR EACH ROW
+        let tgtype = 1 | 2 | 4 | 16;
+        assert_eq!(TriggerTiming::from_tgtype(tgtype), TriggerTiming::Before);
+        assert_eq!(
+            TriggerEvent::from_tgtype(tgtype),
+            vec![TriggerEvent::Insert, TriggerEvent::Update]
+        );
+        assert_eq!(TriggerTiming::from_tgtype(8), TriggerTiming::After);
+        assert_eq!(TriggerTiming::from_tgtype(1 | 64 | 8), TriggerTiming::InsteadOf);
+        assert_eq!(TriggerEnabled::from_tgenabled("D"), Some(TriggerEnabled::Disabled));
+        assert_eq!(PolicyCommand::from_polcmd("*"), Some(PolicyCommand::All));
+        assert_eq!(PolicyCommand::from_polcmd("w"), Some(PolicyCommand::Update));
+        assert_eq!(PolicyCommand::from_polcmd("x"), None);
+    }
+
+    #[test]
+    fn test_role_privileges() {
+        let mut schema = TableSchema::test("documents", vec![]);
+        schema.privileges = vec![
+            RolePrivileges {
+                role: "admin".to_string(),
+                table_privileges: Privilege::TABLE.to_vec(),
+                column_privileges: vec![],
+            },
+            RolePrivileges {
+                role: "reader".to_string(),
+                table_privileges: vec![],
+                column_privileges: vec![ColumnPrivileges {
+                    column: "title".to_string(),
+                    privileges: vec![Privilege::Select],
+                }],
+            },
+        ];
+
+        let reader = schema.privileges_for("reader").expect("reader missing");
+        assert!(reader.can(Privilege::Select, Some("title")));
+        assert!(!reader.can(Privilege::Select, Some("body")));
+        assert!(!reader.can(Privilege::Select, None));
+        assert!(schema.privileges_for("nobody").is_none());
+
+        assert_eq!(schema.roles_with(Privilege::Select, Some("title")), vec!["admin", "reader"]);
+        assert_eq!(schema.roles_with(Privilege::Select, None), vec!["admin"]);
+        assert_eq!(Privilege::from_name("TRUNCATE"), Some(Privilege::Truncate));
+        assert_eq!(Privilege::Trigger.as_sql(), "TRIGGER");
     }
 }
@@ -0,0 +1,645 @@
+// TypeScript - 根据表结构生成 TypeScript 类型定义
+// 输出格式与 Supabase `gen types typescript` 一致：
+// `Database[schema]` 下分为 Tables / Views / Functions / Enums / CompositeTypes
+
+use super::{
+    pg_type::{JsonType, PgType},
+    types::{
+        ArgumentMode, ColumnInfo, FunctionInfo, TableSchema, UserTypeInfo, UserTypeKind,
+    },
+};
+use std::collections::BTreeMap;
+
+/// `Json` 类型定义，json / jsonb 列使用
+const JSON_TYPE: &str = "export type Json =
+  | string
+  | number
+  | boolean
+  | null
+  | { [key: string]: Json | undefined }
+  | Json[]
+";
+
+/// 空对象类型（没有表、视图、函数等时使用）
+const EMPTY: &str = "[_ in never]: never";
+
+/// 类型名解析时需要的上下文
+struct TypeContext<'a> {
+    schema: &'a str,
+    tables: &'a [&'a TableSchema],
+    enums: &'a BTreeMap<String, Vec<String>>,
+    composites: &'a BTreeMap<String, Vec<(String, String)>>,
+}
+
+/// 生成 schema 的 TypeScript 类型定义
+///
+/// # Arguments
+/// * `schema_name` - 表、函数和类型所属的 schema
+/// * `tables` - 表结构（包含视图），输出按名称排序
+/// * `functions` - 函数，只包含可直接调用的函数
+/// * `user_types` - 用户定义类型，用于生成 Enums 和 CompositeTypes
+pub fn generate_typescript(
+    schema_name: &str,
+    tables: &[TableSchema],
+    functions: &[FunctionInfo],
+    user_types: &[UserTypeInfo],
+) -> String {
+    let mut tables: Vec<&TableSchema> = tables.iter().collect();
+    tables.sort_by(|a, b| a.name.cmp(&b.name));
+
+    // 列使用的枚举也要输出，即使没有传入对应的用户定义类型
+    let mut enums: BTreeMap<String, Vec<String>> = user_types
+        .iter()
+        .filter(|t| t.schema == schema_name && t.kind == UserTypeKind::Enum)
+        .map(|t| (t.name.clone(), t.enum_labels.clone()))
+        .collect();
+    for column in tables.iter().flat_map(|t| &t.columns) {
+        if let Some(user_type) = column
+            .user_type
+            .as_ref()
+            .filter(|t| t.schema == schema_name && t.kind == UserTypeKind::Enum)
+        {
+            enums
+                .entry(user_type.name.clone())
+                .or_insert_with(|| user_type.enum_labels.clone());
+        }
+    }
+    let composites: BTreeMap<String, Vec<(String, String)>> = user_types
+        .iter()
+        .filter(|t| t.schema == schema_name && t.kind == UserTypeKind::Composite)
+        .map(|t| {
+            let fields = t
+                .fields
+                .iter()
+                .map(|f| (f.name.clone(), f.data_type.clone()))
+                .collect();
+            (t.name.clone(), fields)
+        })
+        .collect();
+
+    let context = TypeContext {
+        schema: schema_name,
+        tables: &tables,
+        enums: &enums,
+        composites: &composites,
+    };
+
+    let mut out = String::new();
+    out.push_str(JSON_TYPE);
+    out.push('\n');
+    out.push_str("export type Database = {\n");
+    line(&mut out, 1, &format!("{}: {{", property_name(schema_name)));
+
+    line(&mut out, 2, "Tables: {");
+    let (views, base_tables): (Vec<&TableSchema>, Vec<&TableSchema>) =
+        tables.iter().partition(|t| t.kind.is_view());
+    write_relations(&mut out, &base_tables, &context);
+    line(&mut out, 2, "}");
+
+    line(&mut out, 2, "Views: {");
+    write_relations(&mut out, &views, &context);
+    line(&mut out, 2, "}");
+
+    line(&mut out, 2, "Functions: {");
+    write_functions(&mut out, functions, &context);
+    line(&mut out, 2, "}");
+
+    line(&mut out, 2, "Enums: {");
+    if enums.is_empty() {
+        line(&mut out, 3, EMPTY);
+    }
+    for (name, values) in &enums {
+        line(
+            &mut out,
+            3,
+            &format!("{}: {}", property_name(name), literal_union(values)),
+        );
+    }
+    line(&mut out, 2, "}");
+
+    line(&mut out, 2, "CompositeTypes: {");
+    if composites.is_empty() {
+        line(&mut out, 3, EMPTY);
+    }
+    for (name, fields) in &composites {
+        line(&mut out, 3, &format!("{}: {{", property_name(name)));
+        for (field, data_type) in fields {
+            let ts_type = nullable(context.ts_type(&PgType::from_name(data_type)));
+            line(&mut out, 4, &format!("{}: {}", property_name(field), ts_type));
+        }
+        line(&mut out, 3, "}");
+    }
+    line(&mut out, 2, "}");
+
+    line(&mut out, 1, "}");
+    out.push_str("}\n\n");
+
+    write_helpers(&mut out, schema_name);
+    out
+}
+
+/// 输出表或视图：Row / Insert / Update / Relationships
+///
+/// 不可插入或不可更新的视图省略 Insert 或 Update
+fn write_relations(out: &mut String, relations: &[&TableSchema], context: &TypeContext) {
+    if relations.is_empty() {
+        line(out, 3, EMPTY);
+    }
+
+    for table in relations {
+        if let Some(comment) = &table.comment {
+            doc_comment(out, 3, comment);
+        }
+        line(out, 3, &format!("{}: {{", property_name(&table.name)));
+
+        line(out, 4, "Row: {");
+        for column in &table.columns {
+            column_line(out, column, false, context);
+        }
+        line(out, 4, "}");
+
+        let (insertable, updatable) = match &table.view {
+            Some(view) => (view.is_insertable_into, view.is_updatable),
+            None => (!table.kind.is_view(), !table.kind.is_view()),
+        };
+        if insertable {
+            line(out, 4, "Insert: {");
+            for column in &table.columns {
+                let optional =
+                    column.is_nullable || column.default_value.is_some() || column.is_identity;
+                column_line(out, column, optional, context);
+            }
+            line(out, 4, "}");
+        }
+        if updatable {
+            line(out, 4, "Update: {");
+            for column in &table.columns {
+                column_line(out, column, true, context);
+            }
+            line(out, 4, "}");
+        }
+
+        write_relationships(out, table);
+        line(out, 3, "}");
+    }
+}
+
+/// 输出单个列
+fn column_line(out: &mut String, column: &ColumnInfo, optional: bool, context: &TypeContext) {
+    let mut ts_type = context.ts_type(&column.pg_type());
+    if column.is_nullable {
+        ts_type = nullable(ts_type);
+    }
+    line(
+        out,
+        5,
+        &format!(
+            "{}{}: {}",
+            property_name(&column.name),
+            if optional { "?" } else { "" },
+            ts_type
+        ),
+    );
+}
+
+/// 输出本表外键产生的关系
+///
+/// 外键列恰好是主键或唯一约束时为一对一关系
+fn write_relationships(out: &mut String, table: &TableSchema) {
+    if table.foreign_keys.is_empty() {
+        line(out, 4, "Relationships: []");
+        return;
+    }
+
+    line(out, 4, "Relationships: [");
+    for fk in &table.foreign_keys {
+        let columns = fk.local_columns();
+        let is_one_to_one = table.is_unique_key(&columns);
+
+        line(out, 5, "{");
+        line(
+            out,
+            6,
+            &format!("foreignKeyName: {}", string_literal(&fk.constraint_name)),
+        );
+        line(out, 6, &format!("columns: {}", string_tuple(&columns)));
+        line(out, 6, &format!("isOneToOne: {}", is_one_to_one));
+        line(
+            out,
+            6,
+            &format!(
+                "referencedRelation: {}",
+                string_literal(&fk.foreign_table_name)
+            ),
+        );
+        line(
+            out,
+            6,
+            &format!("referencedColumns: {}", string_tuple(&fk.foreign_columns())),
+        );
+        line(out, 5, "},");
+    }
+    line(out, 4, "]");
+}
+
+/// 输出函数：Args / Returns，重载的函数输出为联合类型
+fn write_functions(out: &mut String, functions: &[FunctionInfo], context: &TypeContext) {
+    let mut overloads: BTreeMap<&str, Vec<&FunctionInfo>> = BTreeMap::new();
+    for function in functions
+        .iter()
+        .filter(|f| f.schema == context.schema && f.is_callable())
+    {
+        overloads.entry(&function.name).or_default().push(function);
+    }
+
+    if overloads.is_empty() {
+        line(out, 3, EMPTY);
+    }
+
+    for (name, overloads) in overloads {
+        let signatures: Vec<(String, String)> = overloads
+            .iter()
+            .map(|f| (function_args(f, context), function_returns(f, context)))
+            .collect();
+
+        match signatures.as_slice() {
+            [(args, returns)] => {
+                line(out, 3, &format!("{}: {{", property_name(name)));
+                line(out, 4, &format!("Args: {}", args));
+                line(out, 4, &format!("Returns: {}", returns));
+                line(out, 3, "}");
+            }
+            _ => {
+                line(out, 3, &format!("{}:", property_name(name)));
+                for (args, returns) in &signatures {
+                    line(out, 4, "| {");
+                    line(out, 6, &format!("Args: {}", args));
+                    line(out, 6, &format!("Returns: {}", returns));
+                    line(out, 4, "  }");
+                }
+            }
+        }
+    }
+}
+
+/// 函数参数对象，未命名的参数按 PostgREST 的约定无法传入，因此省略
+fn function_args(function: &FunctionInfo, context: &TypeContext) -> String {
+    let arguments: Vec<String> = function
+        .input_arguments()
+        .iter()
+        .filter_map(|a| {
+            let name = a.name.as_ref()?;
+            Some(format!(
+                "{}{}: {}",
+                property_name(name),
+                if a.default_value.is_some() { "?" } else { "" },
+                context.ts_type(&PgType::from_name(&a.data_type))
+            ))
+        })
+        .collect();
+
+    if arguments.is_empty() {
+        "Record<PropertyKey, never>".to_string()
+    } else {
+        format!("{{ {} }}", arguments.join("; "))
+    }
+}
+
+/// 函数返回值类型
+fn function_returns(function: &FunctionInfo, context: &TypeContext) -> String {
+    let return_type = match function.return_type.as_deref() {
+        None | Some("void") => return "undefined".to_string(),
+        Some(return_type) => return_type,
+    };
+
+    let outputs = function.output_columns();
+    let row = if outputs.len() > 1 || outputs.iter().any(|a| a.mode == ArgumentMode::Table) {
+        let columns: Vec<String> = outputs
+            .iter()
+            .enumerate()
+            .map(|(i, a)| {
+                let name = a.name.clone().unwrap_or_else(|| format!("column{}", i + 1));
+                format!(
+                    "{}: {}",
+                    property_name(&name),
+                    context.ts_type(&PgType::from_name(&a.data_type))
+                )
+            })
+            .collect();
+        format!("{{ {} }}", columns.join("; "))
+    } else {
+        let element = return_type.strip_prefix("SETOF ").unwrap_or(return_type);
+        match context.find_table(element) {
+            Some(table) => format!(
+                "Database[{}][{}][{}][\"Row\"]",
+                string_literal(context.schema),
+                string_literal(if table.kind.is_view() { "Views" } else { "Tables" }),
+                string_literal(&table.name)
+            ),
+            None => context.ts_type(&PgType::from_name(element)),
+        }
+    };
+
+    if function.returns_set {
+        array_of(&row)
+    } else {
+        row
+    }
+}
+
+/// 便捷类型：`Tables<"users">`、`TablesInsert<"users">`、`Enums<"mood">` 等
+fn write_helpers(out: &mut String, schema_name: &str) {
+    let schema = format!("Database[{}]", string_literal(schema_name));
+
+    for (alias, section, variant) in [
+        ("Tables", "Tables", "Row"),
+        ("TablesInsert", "Tables", "Insert"),
+        ("TablesUpdate", "Tables", "Update"),
+        ("Views", "Views", "Row"),
+    ] {
+        out.push_str(&format!(
+            "export type {alias}<T extends keyof {schema}[\"{section}\"]> =\n  \
+             {schema}[\"{section}\"][T][\"{variant}\"]\n\n"
+        ));
+    }
+    out.push_str(&format!(
+        "export type Enums<T extends keyof {schema}[\"Enums\"]> = {schema}[\"Enums\"][T]\n\n"
+    ));
+    out.push_str(&format!(
+        "export type CompositeTypes<T extends keyof {schema}[\"CompositeTypes\"]> =\n  \
+         {schema}[\"CompositeTypes\"][T]\n"
+    ));
+}
+
+impl TypeContext<'_> {
+    /// PostgreSQL 类型对应的 TypeScript 类型
+    ///
+    /// 本 schema 的枚举和复合类型引用 `Database` 中的定义，
+    /// 其他 schema 的枚举展开为字面量联合类型
+    fn ts_type(&self, pg_type: &PgType) -> String {
+        match pg_type.resolve() {
+            PgType::Array {
+                element,
+                dimensions,
+            } => {
+                let mut ts_type = self.ts_type(element);
+                for _ in 0..*dimensions {
+                    ts_type = array_of(&ts_type);
+                }
+                ts_type
+            }
+            PgType::Json | PgType::Jsonb => "Json".to_string(),
+            PgType::Enum {
+                schema,
+                name,
+                values,
+            } => {
+                if schema == self.schema && self.enums.contains_key(name) {
+                    self.section_ref("Enums", name)
+                } else {
+                    literal_union(values)
+                }
+            }
+            PgType::Composite { schema, name } if schema == self.schema => {
+                if self.composites.contains_key(name) {
+                    self.section_ref("CompositeTypes", name)
+                } else {
+                    "Json".to_string()
+                }
+            }
+            // 函数参数只有类型名，按名称查找本 schema 的枚举和复合类型
+            PgType::Other { name } => {
+                let name = name
+                    .strip_prefix(&format!("{}.", self.schema))
+                    .unwrap_or(name);
+                if self.enums.contains_key(name) {
+                    self.section_ref("Enums", name)
+                } else if self.composites.contains_key(name) {
+                    self.section_ref("CompositeTypes", name)
+                } else {
+                    "unknown".to_string()
+                }
+            }
+            resolved => match resolved.json_type() {
+                JsonType::String => "string",
+                JsonType::Integer | JsonType::Number => "number",
+                JsonType::Boolean => "boolean",
+                JsonType::Object => "Json",
+                JsonType::Array => "Json[]",
+                JsonType::Any => "unknown",
+            }
+            .to_string(),
+        }
+    }
+
+    /// 按名称查找表或视图（支持带 schema 前缀的名称）
+    fn find_table(&self, name: &str) -> Option<&TableSchema> {
+        self.tables
+            .iter()
+            .find(|t| t.name == name || format!("{}.{}", t.schema, t.name) == name)
+            .copied()
+    }
+
+    /// 引用 `Database[schema][section][name]`
+    fn section_ref(&self, section: &str, name: &str) -> String {
+        format!(
+            "Database[{}][{}][{}]",
+            string_literal(self.schema),
+            string_literal(section),
+            string_literal(name)
+        )
+    }
+}
+
+/// 输出一行（两个空格缩进）
+fn line(out: &mut String, indent: usize, text: &str) {
+    out.push_str(&"  ".repeat(indent));
+    out.push_str(text);
+    out.push('\n');
+}
+
+/// 输出 JSDoc 注释
+fn doc_comment(out: &mut String, indent: usize, comment: &str) {
+    line(out, indent, "/**");
+    for comment_line in comment.lines() {
+        line(out, indent, &format!(" * {}", comment_line.replace("*/", "*\\/")));
+    }
+    line(out, indent, " */");
+}
+
+/// 对象属性名，不是合法标识符时加引号
+fn property_name(name: &str) -> String {
+    let mut chars = name.chars();
+    let is_identifier = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+
+    if is_identifier {
+        name.to_string()
+    } else {
+        string_literal(name)
+    }
+}
+
+/// 字符串字面量（JSON 字符串的转义规则与 TypeScript 兼容）
+fn string_literal(value: &str) -> String {
+    serde_json::Value::from(value).to_string()
+}
+
+/// 字符串元组：`["a", "b"]`
+fn string_tuple(values: &[&str]) -> String {
+    let values: Vec<String> = values.iter().map(|v| string_literal(v)).collect();
+    format!("[{}]", values.join(", "))
+}
+
+/// 字面量联合类型：`"a" | "b"`，没有值时为 never
+fn literal_union(values: &[String]) -> String {
+    if values.is_empty() {
+        return "never".to_string();
+    }
+    values
+        .iter()
+        .map(|v| string_literal(v))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// 加上 `| null`
+fn nullable(ts_type: String) -> String {
+    format!("{} | null", ts_type)
+}
+
+/// 数组类型，联合类型需要加括号
+fn array_of(ts_type: &str) -> String {
+    if ts_type.contains(" | ") {
+        format!("({})[]", ts_type)
+    } else {
+        format!("{}[]", ts_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::types::{
+        ForeignKeyInfo, FunctionArgument, FunctionKind, RelationKind, ViewInfo, Volatility,
+    };
+
+    fn fixture() -> Vec<TableSchema> {
+        let mut id = ColumnInfo::test("id", "integer", false);
+        id.is_identity = true;
+        let mut user_mood = ColumnInfo::test("mood", "USER-DEFINED", true);
+        user_mood.user_type = Some(UserTypeInfo::test_enum("mood", &["happy", "sad"]));
+        let mut tags = ColumnInfo::test("tags", "ARRAY", true);
+        tags.udt_name = "_text".to_string();
+        tags.element_type = Some("text".to_string());
+        tags.array_dimensions = Some(1);
+        let mut users = TableSchema::test(
+            "users",
+            vec![
+                id.clone(),
+                ColumnInfo::test("email", "text", false),
+                user_mood,
+                tags,
+                ColumnInfo::test("metadata", "jsonb", true),
+            ],
+        );
+        users.comment = Some("Application users".to_string());
+
+        let mut profiles = TableSchema::test(
+            "profiles",
+            vec![id.clone(), ColumnInfo::test("user_id", "integer", false)],
+        );
+        profiles.primary_keys = vec!["user_id".to_string()];
+        profiles.foreign_keys = vec![ForeignKeyInfo::test(
+            "profiles_user_id_fkey",
+            &[("user_id", "id")],
+            "users",
+        )];
+
+        let mut active_users = TableSchema::test("active_users", vec![id, ColumnInfo::test("email", "text", true)]);
+        active_users.kind = RelationKind::View;
+        active_users.view = Some(ViewInfo {
+            definition: "SELECT id, email FROM users".to_string(),
+            is_updatable: false,
+            is_insertable_into: false,
+        });
+
+        vec![users, profiles, active_users]
+    }
+
+    #[test]
+    fn test_table_types() {
+        let output = generate_typescript("public", &fixture(), &[], &[UserTypeInfo::test_enum("mood", &["happy", "sad"])]);
+
+        assert!(output.starts_with("export type Json ="));
+        assert!(output.contains("      users: {\n"));
+        assert!(output.contains("      /**\n       * Application users\n       */\n"));
+        assert!(output.contains("          id: number\n"));
+        assert!(output.contains("          id?: number\n"));
+        assert!(output.contains("          email: string\n"));
+        assert!(output.contains("          mood: Database[\"public\"][\"Enums\"][\"mood\"] | null\n"));
+        assert!(output.contains("          mood?: Database[\"public\"][\"Enums\"][\"mood\"] | null\n"));
+        assert!(output.contains("          tags: string[] | null\n"));
+        assert!(output.contains("          metadata: Json | null\n"));
+        assert!(output.contains("      mood: \"happy\" | \"sad\"\n"));
+        assert!(output.contains("foreignKeyName: \"profiles_user_id_fkey\""));
+        assert!(output.contains("isOneToOne: true"));
+        assert!(output.contains("referencedRelation: \"users\""));
+
+        // 只读视图没有 Insert 和 Update
+        let views = output
+            .split("    Views: {")
+            .nth(1)
+            .and_then(|rest| rest.split("    Functions: {").next())
+            .unwrap_or_default();
+        assert!(views.contains("active_users: {"));
+        assert!(views.contains("Row: {"));
+        assert!(!views.contains("Insert: {"));
+        assert!(output.contains("    CompositeTypes: {\n      [_ in never]: never\n    }"));
+    }
+
+    #[test]
+    fn test_function_types() {
+        let argument = |name: &str, data_type: &str, default_value: Option<&str>| FunctionArgument {
+            name: Some(name.to_string()),
+            mode: ArgumentMode::In,
+            data_type: data_type.to_string(),
+            default_value: default_value.map(str::to_string),
+        };
+        let function = |name: &str, arguments: Vec<FunctionArgument>, return_type: &str| {
+            FunctionInfo {
+                schema: "public".to_string(),
+                name: name.to_string(),
+                kind: FunctionKind::Function,
+                arguments,
+                identity_arguments: String::new(),
+                return_type: Some(return_type.to_string()),
+                returns_set: return_type.starts_with("SETOF "),
+                volatility: Volatility::Stable,
+                security_definer: false,
+                language: "sql".to_string(),
+                comment: None,
+            }
+        };
+        let functions = [
+            function(
+                "find_users",
+                vec![argument("pattern", "text", None), argument("max", "integer", Some("10"))],
+                "SETOF users",
+            ),
+            function("by_mood", vec![argument("m", "mood", None)], "integer"),
+            function("now_utc", vec![], "timestamp with time zone"),
+            function("audit", vec![], "trigger"),
+        ];
+        let output = generate_typescript("public", &fixture(), &functions, &[UserTypeInfo::test_enum("mood", &["happy", "sad"])]);
+
+        assert!(output.contains("        Args: { pattern: string; max?: number }\n"));
+        assert!(output.contains("        Returns: Database[\"public\"][\"Tables\"][\"users\"][\"Row\"][]\n"));
+        assert!(output.contains("        Args: { m: Database[\"public\"][\"Enums\"][\"mood\"] }\n"));
+        assert!(output.contains("        Args: Record<PropertyKey, never>\n"));
+        assert!(!output.contains("audit"));
+        assert!(output.contains("export type TablesInsert<T extends keyof Database[\"public\"][\"Tables\"]>"));
+    }
+}
@@ -8,7 +8,12 @@
 // 运行测试：
 // cargo test --test schema_inspector_tests -- --test-threads=1
 
+use orpheus::schema::pg_type::{JsonType, PgType};
 use orpheus::schema::{self, SchemaCache};
+use orpheus::schema::types::{
+    ArgumentMode, ConstraintType, FunctionKind, IdentityGeneration, PolicyCommand, Privilege,
+    RelationKind, TriggerEnabled, TriggerEvent, TriggerTiming, UserTypeKind, Volatility,
+};
 use sqlx::PgPool;
 
 // 测试辅助函数：获取测试数据库连接
@@ -222,14 +227,73 @@ async fn test_get_table_schema_foreign_keys() {
     assert_eq!(schema.foreign_keys.len(), 1);
     
     let fk = &schema.foreign_keys[0];
-    assert_eq!(fk.column_name, "author_id");
+    assert_eq!(fk.local_columns(), vec!["author_id"]);
+    assert_eq!(fk.foreign_schema, "public");
     assert_eq!(fk.foreign_table_name, "test_users");
-    assert_eq!(fk.foreign_column_name, "id");
+    assert_eq!(fk.foreign_columns(), vec!["id"]);
     assert_eq!(fk.on_delete, Some("CASCADE".to_string()));
+    assert_eq!(fk.on_update, Some("NO ACTION".to_string()));
+    assert_eq!(fk.match_type, "SIMPLE");
+    assert!(!fk.is_deferrable);
 
     cleanup_test_tables(&pool).await.expect("Failed to cleanup");
 }
 
+#[tokio::test]
+async fn test_composite_and_cross_schema_foreign_keys() {
+    let pool = get_test_pool().await;
+
+    sqlx::query("DROP TABLE IF EXISTS test_invoices").execute(&pool).await.ok();
+    sqlx::query("DROP SCHEMA IF EXISTS test_billing CASCADE").execute(&pool).await.ok();
+
+    sqlx::query("CREATE SCHEMA test_billing").execute(&pool).await.expect("Failed to create schema");
+    sqlx::query(
+        "CREATE TABLE test_billing.customers (
+            tenant_id INTEGER NOT NULL,
+            id INTEGER NOT NULL,
+            PRIMARY KEY (tenant_id, id)
+        )",
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create customers table");
+    sqlx::query(
+        "CREATE TABLE test_invoices (
+            id SERIAL PRIMARY KEY,
+            customer_id INTEGER NOT NULL,
+            tenant_id INTEGER NOT NULL,
+            CONSTRAINT test_invoices_customer_fkey
+                FOREIGN KEY (tenant_id, customer_id)
+                REFERENCES test_billing.customers (tenant_id, id)
+                MATCH FULL ON DELETE RESTRICT
+                DEFERRABLE INITIALLY DEFERRED
+        )",
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create invoices table");
+
+    let schema = schema::get_table_schema(&pool, "test_invoices", None)
+        .await
+        .expect("Failed to get table schema");
+
+    // 复合外键只产生一条记录，列对按约束定义顺序排列
+    assert_eq!(schema.foreign_keys.len(), 1);
+    let fk = &schema.foreign_keys[0];
+    assert!(fk.is_composite());
+    assert_eq!(fk.local_columns(), vec!["tenant_id", "customer_id"]);
+    assert_eq!(fk.foreign_columns(), vec!["tenant_id", "id"]);
+    assert_eq!(fk.foreign_schema, "test_billing");
+    assert_eq!(fk.foreign_table_name, "customers");
+    assert_eq!(fk.on_delete, Some("RESTRICT".to_string()));
+    assert_eq!(fk.match_type, "FULL");
+    assert!(fk.is_deferrable);
+    assert!(fk.initially_deferred);
+
+    sqlx::query("DROP TABLE IF EXISTS test_invoices").execute(&pool).await.ok();
+    sqlx::query("DROP SCHEMA IF EXISTS test_billing CASCADE").execute(&pool).await.ok();
+}
+
 #[tokio::test]
 async fn test_get_table_schema_indexes() {
     let pool = get_test_pool().await;
@@ -257,6 +321,632 @@ async fn test_get_table_schema_indexes() {
     cleanup_test_tables(&pool).await.expect("Failed to cleanup");
 }
 
+#[tokio::test]
+async fn test_index_details() {
+    let pool = get_test_pool().await;
+
+    sqlx::query("DROP TABLE IF EXISTS test_events")
+        .execute(&pool)
+        .await
+        .ok();
+    sqlx::query(
+        "CREATE TABLE test_events (
+            id SERIAL PRIMARY KEY,
+            kind TEXT NOT NULL,
+            created_at TIMESTAMPTZ,
+            archived BOOLEAN NOT NULL DEFAULT false,
+            payload JSONB
+        )",
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create table");
+    sqlx::query(
+        "CREATE INDEX idx_test_events_recent ON test_events (created_at DESC NULLS LAST, kind)
+         INCLUDE (payload) WHERE NOT archived",
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create index");
+    sqlx::query("CREATE INDEX idx_test_events_kind_lower ON test_events (lower(kind), id)")
+        .execute(&pool)
+        .await
+        .expect("Failed to create expression index");
+
+    let schema = schema::get_table_schema(&pool, "test_events", None)
+        .await
+        .expect("Failed to get table schema");
+
+    // 列顺序按索引定义，而不是按列在表中的位置
+    let recent = schema
+        .indexes
+        .iter()
+        .find(|i| i.name == "idx_test_events_recent")
+        .expect("idx_test_events_recent not found");
+    assert_eq!(recent.columns, vec!["created_at".to_string(), "kind".to_string()]);
+    assert_eq!(recent.keys.len(), 2);
+    assert!(recent.keys[0].descending);
+    assert!(!recent.keys[0].nulls_first);
+    assert!(!recent.keys[1].descending);
+    assert_eq!(recent.include_columns, vec!["payload".to_string()]);
+    assert_eq!(recent.predicate, Some("NOT archived".to_string()));
+    assert!(recent.is_partial());
+    assert!(recent.is_valid);
+    assert!(recent.size_bytes > 0);
+    assert!(recent.definition.starts_with("CREATE INDEX idx_test_events_recent"));
+
+    // 表达式索引不会被丢弃
+    let lower = schema
+        .indexes
+        .iter()
+        .find(|i| i.name == "idx_test_events_kind_lower")
+        .expect("idx_test_events_kind_lower not found");
+    assert!(lower.has_expressions());
+    assert_eq!(lower.keys.len(), 2);
+    assert_eq!(lower.keys[0].column, None);
+    assert_eq!(lower.keys[0].expression, Some("lower(kind)".to_string()));
+    assert_eq!(lower.keys[1].column, Some("id".to_string()));
+    assert_eq!(lower.columns, vec!["id".to_string()]);
+
+    sqlx::query("DROP TABLE IF EXISTS test_events")
+        .execute(&pool)
+        .await
+        .ok();
+}
+
+#[tokio::test]
+async fn test_triggers_and_row_security() {
+    let pool = get_test_pool().await;
+
+    sqlx::query("DROP TABLE IF EXISTS test_documents")
+        .execute(&pool)
+        .await
+        .ok();
+    sqlx::query(
+        "CREATE TABLE test_documents (
+            id SERIAL PRIMARY KEY,
+            owner TEXT NOT NULL,
+            title TEXT,
+            updated_at TIMESTAMPTZ
+        )",
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create table");
+    sqlx::query(
+        "CREATE OR REPLACE FUNCTION test_touch_updated_at() RETURNS trigger
+         LANGUAGE plpgsql AS $$ BEGIN NEW.updated_at := now(); RETURN NEW; END $$",
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create trigger function");
+    sqlx::query(
+        "CREATE TRIGGER test_documents_touch BEFORE INSERT OR UPDATE OF title ON test_documents
+         FOR EACH ROW WHEN (NEW.title IS NOT NULL) EXECUTE FUNCTION test_touch_updated_at()",
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create trigger");
+    sqlx::query("ALTER TABLE test_documents DISABLE TRIGGER test_documents_touch")
+        .execute(&pool)
+        .await
+        .expect("Failed to disable trigger");
+    sqlx::query("ALTER TABLE test_documents ENABLE ROW LEVEL SECURITY")
+        .execute(&pool)
+        .await
+        .expect("Failed to enable RLS");
+    sqlx::query(
+        "CREATE POLICY test_documents_owner ON test_documents FOR UPDATE TO PUBLIC
+         USING (owner = current_user) WITH CHECK (owner = current_user)",
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create policy");
+    sqlx::query(
+        "CREATE POLICY test_documents_titled ON test_documents AS RESTRICTIVE FOR SELECT
+         USING (title IS NOT NULL)",
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create policy");
+
+    let schema = schema::get_table_schema(&pool, "test_documents", None)
+        .await
+        .expect("Failed to get table schema");
+
+    // 主键等内部触发器不出现
+    assert_eq!(schema.triggers.len(), 1);
+    let trigger = &schema.triggers[0];
+    assert_eq!(trigger.name, "test_documents_touch");
+    assert_eq!(trigger.timing, TriggerTiming::Before);
+    assert_eq!(trigger.events, vec![TriggerEvent::Insert, TriggerEvent::Update]);
+    assert!(trigger.for_each_row);
+    assert_eq!(trigger.update_columns, vec!["title".to_string()]);
+    assert_eq!(trigger.condition, Some("new.title IS NOT NULL".to_string()));
+    assert_eq!(trigger.function_schema, "public");
+    assert_eq!(trigger.function_name, "test_touch_updated_at");
+    assert_eq!(trigger.enabled, TriggerEnabled::Disabled);
+
+    assert!(schema.row_security.enabled);
+    assert!(!schema.row_security.forced);
+    assert_eq!(schema.row_security.policies.len(), 2);
+
+    let owner = &schema.row_security.policies[0];
+    assert_eq!(owner.name, "test_documents_owner");
+    assert_eq!(owner.command, PolicyCommand::Update);
+    assert_eq!(owner.roles, vec!["public".to_string()]);
+    assert_eq!(owner.using_expression, Some("owner = CURRENT_USER".to_string()));
+    assert_eq!(owner.with_check_expression, Some("owner = CURRENT_USER".to_string()));
+    assert!(owner.permissive);
+
+    let titled = &schema.row_security.policies[1];
+    assert_eq!(titled.command, PolicyCommand::Select);
+    assert_eq!(titled.with_check_expression, None);
+    assert!(!titled.permissive);
+
+    sqlx::query("DROP TABLE IF EXISTS test_documents")
+        .execute(&pool)
+        .await
+        .ok();
+    sqlx::query("DROP FUNCTION IF EXISTS test_touch_updated_at()")
+        .execute(&pool)
+        .await
+        .ok();
+}
+
+#[tokio::test]
+async fn test_role_privileges() {
+    let pool = get_test_pool().await;
+
+    for statement in [
+        "DROP TABLE IF EXISTS test_payroll",
+        "DROP ROLE IF EXISTS test_payroll_member",
+        "DROP ROLE IF EXISTS test_payroll_reader",
+    ] {
+        sqlx::query(statement).execute(&pool).await.ok();
+    }
+    for statement in [
+        "CREATE TABLE test_payroll (id SERIAL PRIMARY KEY, name TEXT, salary NUMERIC)",
+        "CREATE ROLE test_payroll_reader NOLOGIN",
+        "CREATE ROLE test_payroll_member NOLOGIN IN ROLE test_payroll_reader",
+        "GRANT SELECT (id, name), UPDATE (name) ON test_payroll TO test_payroll_reader",
+        "GRANT INSERT ON test_payroll TO test_payroll_member",
+    ] {
+        sqlx::query(statement)
+            .execute(&pool)
+            .await
+            .expect("Failed to set up privileges");
+    }
+
+    let schema = schema::get_table_schema(&pool, "test_payroll", None)
+        .await
+        .expect("Failed to get table schema");
+
+    let reader = schema
+        .privileges_for("test_payroll_reader")
+        .expect("test_payroll_reader missing");
+    assert!(reader.table_privileges.is_empty());
+    assert_eq!(reader.column_privileges.len(), 2);
+    assert_eq!(reader.column_privileges[0].column, "id");
+    assert_eq!(reader.column_privileges[0].privileges, vec![Privilege::Select]);
+    assert_eq!(
+        reader.column_privileges[1].privileges,
+        vec![Privilege::Select, Privilege::Update]
+    );
+    assert!(reader.can(Privilege::Select, Some("name")));
+    assert!(!reader.can(Privilege::Select, Some("salary")));
+
+    // 通过角色继承获得的列权限也算在内
+    let member = schema
+        .privileges_for("test_payroll_member")
+        .expect("test_payroll_member missing");
+    assert_eq!(member.table_privileges, vec![Privilege::Insert]);
+    assert!(member.can(Privilege::Select, Some("id")));
+    assert!(member.can(Privilege::Insert, Some("salary")));
+
+    let salary_readers = schema.roles_with(Privilege::Select, Some("salary"));
+    assert!(!salary_readers.contains(&"test_payroll_reader"));
+    assert!(!salary_readers.contains(&"test_payroll_member"));
+
+    for statement in [
+        "DROP TABLE IF EXISTS test_payroll",
+        "DROP ROLE IF EXISTS test_payroll_member",
+        "DROP ROLE IF EXISTS test_payroll_reader",
+    ] {
+        sqlx::query(statement).execute(&pool).await.ok();
+    }
+}
+
+#[tokio::test]
+async fn test_column_pg_types() {
+    let pool = get_test_pool().await;
+
+    sqlx::query("DROP TABLE IF EXISTS test_type_zoo")
+        .execute(&pool)
+        .await
+        .ok();
+    sqlx::query(
+        "CREATE TABLE test_type_zoo (
+            id BIGSERIAL PRIMARY KEY,
+            code CHAR(3),
+            price NUMERIC(10,2),
+            balance MONEY,
+            opens_at TIME WITH TIME ZONE,
+            duration INTERVAL,
+            external_id UUID,
+            payload BYTEA,
+            client_ip INET,
+            valid_during TSTZRANGE,
+            scores INTEGER[],
+            flags BIT VARYING(8)
+        )",
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create table");
+
+    let schema = schema::get_table_schema(&pool, "test_type_zoo", None)
+        .await
+        .expect("Failed to get table schema");
+    let pg_type = |name: &str| {
+        schema
+            .get_column(name)
+            .unwrap_or_else(|| panic!("column {} missing", name))
+            .pg_type()
+    };
+
+    assert_eq!(pg_type("id"), PgType::BigInt);
+    assert_eq!(pg_type("code"), PgType::Char { length: Some(3) });
+    assert_eq!(
+        pg_type("price"),
+        PgType::Numeric {
+            precision: Some(10),
+            scale: Some(2)
+        }
+    );
+    assert_eq!(pg_type("balance"), PgType::Money);
+    assert_eq!(
+        pg_type("opens_at"),
+        PgType::Time {
+            with_time_zone: true
+        }
+    );
+    assert!(pg_type("duration").is_temporal());
+    assert_eq!(pg_type("external_id"), PgType::Uuid);
+    assert_eq!(pg_type("payload"), PgType::Bytea);
+    assert_eq!(pg_type("client_ip"), PgType::Inet);
+    assert!(matches!(pg_type("valid_during"), PgType::Range { .. }));
+    assert_eq!(
+        pg_type("scores"),
+        PgType::Array {
+            element: Box::new(PgType::Integer),
+            dimensions: 1
+        }
+    );
+    assert_eq!(pg_type("scores").json_type(), JsonType::Array);
+    assert_eq!(
+        pg_type("flags"),
+        PgType::Bit {
+            length: Some(8),
+            varying: true
+        }
+    );
+
+    sqlx::query("DROP TABLE IF EXISTS test_type_zoo")
+        .execute(&pool)
+        .await
+        .ok();
+}
+
+#[tokio::test]
+async fn test_table_stats() {
+    let pool = get_test_pool().await;
+
+    sqlx::query("DROP TABLE IF EXISTS test_metrics")
+        .execute(&pool)
+        .await
+        .ok();
+    sqlx::query("CREATE TABLE test_metrics (id SERIAL PRIMARY KEY, body TEXT)")
+        .execute(&pool)
+        .await
+        .expect("Failed to create table");
+    sqlx::query("INSERT INTO test_metrics (body) SELECT repeat('x', 100) FROM generate_series(1, 500)")
+        .execute(&pool)
+        .await
+        .expect("Failed to insert rows");
+    sqlx::query("ANALYZE test_metrics")
+        .execute(&pool)
+        .await
+        .expect("Failed to analyze");
+
+    let stats = schema::stats::get_table_stats(&pool, "test_metrics", None)
+        .await
+        .expect("Failed to get table stats");
+    assert_eq!(stats.name, "test_metrics");
+    assert_eq!(stats.kind, RelationKind::Table);
+    assert_eq!(stats.estimated_rows, Some(500));
+    assert!(stats.table_bytes > 0);
+    assert!(stats.index_bytes > 0);
+    assert_eq!(
+        stats.total_bytes,
+        stats.table_bytes + stats.index_bytes + stats.toast_bytes
+    );
+    assert!(stats.last_analyzed().is_some());
+
+    // 视图没有存储，不出现在统计中
+    sqlx::query("CREATE OR REPLACE VIEW test_metrics_view AS SELECT id FROM test_metrics")
+        .execute(&pool)
+        .await
+        .expect("Failed to create view");
+    let all = schema::stats::get_all_table_stats(&pool, None)
+        .await
+        .expect("Failed to get all table stats");
+    assert!(all.iter().any(|s| s.name == "test_metrics"));
+    assert!(!all.iter().any(|s| s.name == "test_metrics_view"));
+    assert!(schema::stats::get_table_stats(&pool, "test_metrics_view", None)
+        .await
+        .is_err());
+
+    sqlx::query("DROP TABLE IF EXISTS test_metrics CASCADE")
+        .execute(&pool)
+        .await
+        .ok();
+}
+
+#[tokio::test]
+async fn test_get_table_schema_constraints() {
+    let pool = get_test_pool().await;
+
+    sqlx::query("DROP TABLE IF EXISTS test_products").execute(&pool).await.ok();
+    sqlx::query(
+        "CREATE TABLE test_products (
+            id SERIAL PRIMARY KEY,
+            tenant_id INTEGER NOT NULL,
+            sku VARCHAR(32) NOT NULL,
+            price NUMERIC(10,2) NOT NULL CONSTRAINT test_products_price_positive CHECK (price > 0),
+            discount NUMERIC(10,2),
+            CONSTRAINT test_products_tenant_sku_key UNIQUE (tenant_id, sku),
+            CONSTRAINT test_products_discount_check CHECK (discount IS NULL OR discount < price)
+        )",
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create products table");
+
+    let schema = schema::get_table_schema(&pool, "test_products", None)
+        .await
+        .expect("Failed to get table schema");
+
+    // 主键约束
+    let pk = schema.constraints_of(ConstraintType::PrimaryKey);
+    assert_eq!(pk.len(), 1);
+    assert_eq!(pk[0].columns, vec!["id".to_string()]);
+
+    // 多列唯一约束保持定义顺序
+    let unique = schema.constraints_of(ConstraintType::Unique);
+    assert_eq!(unique.len(), 1);
+    assert_eq!(unique[0].name, "test_products_tenant_sku_key");
+    assert_eq!(unique[0].columns, vec!["tenant_id".to_string(), "sku".to_string()]);
+    assert!(unique[0].expression.is_none());
+    assert_eq!(schema.unique_constraints_for("sku").len(), 1);
+
+    // CHECK 约束带表达式
+    let checks = schema.constraints_of(ConstraintType::Check);
+    assert_eq!(checks.len(), 2);
+    let price_check = checks
+        .iter()
+        .find(|c| c.name == "test_products_price_positive")
+        .expect("price check not found");
+    assert_eq!(price_check.columns, vec!["price".to_string()]);
+    assert!(price_check.expression.as_deref().is_some_and(|e| e.contains("price > ")));
+    assert!(price_check.definition.starts_with("CHECK"));
+    let discount_check = checks
+        .iter()
+        .find(|c| c.name == "test_products_discount_check")
+        .expect("discount check not found");
+    assert_eq!(discount_check.columns.len(), 2);
+
+    sqlx::query("DROP TABLE IF EXISTS test_products").execute(&pool).await.ok();
+}
+
+#[tokio::test]
+async fn test_bulk_load_matches_single_table_load() {
+    let pool = get_test_pool().await;
+
+    create_test_table(&pool).await.expect("Failed to create test table");
+
+    let all = schema::inspector::get_all_table_schemas(&pool, None)
+        .await
+        .expect("Failed to bulk load schemas");
+
+    for name in ["test_users", "test_posts"] {
+        let bulk = all.iter().find(|t| t.name == name).expect("table missing from bulk load");
+        let single = schema::get_table_schema(&pool, name, None)
+            .await
+            .expect("Failed to get table schema");
+
+        // 批量加载和单表加载的结果应完全一致
+        assert_eq!(
+            serde_json::to_value(bulk).expect("serialize bulk"),
+            serde_json::to_value(&single).expect("serialize single"),
+        );
+    }
+
+    let users = all.iter().find(|t| t.name == "test_users").expect("test_users missing");
+    assert_eq!(users.primary_keys, vec!["id".to_string()]);
+    assert_eq!(users.comment, Some("Test users table".to_string()));
+    assert_eq!(
+        users.get_column("email").and_then(|c| c.comment.clone()),
+        Some("User email address".to_string())
+    );
+
+    // 详细概览包含完整表结构
+    let overview = schema::inspector::get_schema_overview_detailed(&pool, None)
+        .await
+        .expect("Failed to get detailed overview");
+    assert_eq!(overview.table_count, overview.table_schemas.len());
+    assert!(overview.table_schemas.iter().any(|t| t.name == "test_posts"));
+
+    cleanup_test_tables(&pool).await.expect("Failed to cleanup");
+}
+
+#[tokio::test]
+async fn test_multiple_schemas() {
+    let pool = get_test_pool().await;
+
+    sqlx::query("DROP SCHEMA IF EXISTS test_tenant_a CASCADE").execute(&pool).await.ok();
+    sqlx::query("CREATE SCHEMA test_tenant_a").execute(&pool).await.expect("Failed to create schema");
+    sqlx::query("COMMENT ON SCHEMA test_tenant_a IS 'Tenant A data'")
+        .execute(&pool)
+        .await
+        .expect("Failed to comment schema");
+    sqlx::query("CREATE TABLE test_tenant_a.accounts (id SERIAL PRIMARY KEY, name TEXT NOT NULL)")
+        .execute(&pool)
+        .await
+        .expect("Failed to create table");
+    sqlx::query("CREATE VIEW test_tenant_a.account_names AS SELECT name FROM test_tenant_a.accounts")
+        .execute(&pool)
+        .await
+        .expect("Failed to create view");
+
+    // schema 列表包含新 schema，不包含系统 schema
+    let schemas = schema::inspector::get_schemas(&pool)
+        .await
+        .expect("Failed to get schemas");
+    let tenant = schemas
+        .iter()
+        .find(|s| s.name == "test_tenant_a")
+        .expect("test_tenant_a not found");
+    assert_eq!(tenant.table_count, 1);
+    assert_eq!(tenant.view_count, 1);
+    assert_eq!(tenant.comment, Some("Tenant A data".to_string()));
+    assert!(!tenant.owner.is_empty());
+    assert!(schemas.iter().any(|s| s.name == "public"));
+    assert!(!schemas.iter().any(|s| s.name == "pg_catalog" || s.name == "information_schema"));
+    assert!(!schemas.iter().any(|s| s.name.starts_with("pg_toast")));
+
+    // 指定 schema 读取表
+    let tables = schema::get_all_tables(&pool, Some("test_tenant_a"))
+        .await
+        .expect("Failed to get tables");
+    assert_eq!(tables, vec!["account_names".to_string(), "accounts".to_string()]);
+
+    let cache = SchemaCache::with_defaults(pool.clone());
+    cache.preload(Some("test_tenant_a")).await.expect("Failed to preload");
+    let accounts = cache
+        .get_table_schema("accounts", Some("test_tenant_a"))
+        .await
+        .expect("Failed to get cached schema");
+    assert_eq!(accounts.schema, "test_tenant_a");
+    assert!(cache.get_table_schema("accounts", None).await.is_err());
+
+    sqlx::query("DROP SCHEMA IF EXISTS test_tenant_a CASCADE").execute(&pool).await.ok();
+}
+
+#[tokio::test]
+async fn test_diff_against_snapshot() {
+    use orpheus::schema::snapshot::SchemaSnapshot;
+
+    let pool = get_test_pool().await;
+
+    sqlx::query("DROP SCHEMA IF EXISTS test_diff CASCADE")
+        .execute(&pool)
+        .await
+        .ok();
+    sqlx::query("CREATE SCHEMA test_diff")
+        .execute(&pool)
+        .await
+        .expect("Failed to create schema");
+    sqlx::query("CREATE TABLE test_diff.items (id SERIAL PRIMARY KEY, name TEXT)")
+        .execute(&pool)
+        .await
+        .expect("Failed to create table");
+
+    // 使用 `orpheus snapshot` 相同格式的快照文件内容
+    let json = schema::snapshot::capture(&pool, &["test_diff".to_string()])
+        .await
+        .expect("Failed to take snapshot")
+        .to_json()
+        .expect("Failed to serialize snapshot");
+    let snapshot = SchemaSnapshot::from_json(&json).expect("Failed to load snapshot");
+
+    // 快照与数据库一致
+    let diff = schema::diff::diff_against_snapshot(&pool, Some("test_diff"), &snapshot)
+        .await
+        .expect("Failed to diff");
+    assert!(diff.is_empty());
+
+    // 快照中没有的 schema
+    assert!(schema::diff::diff_against_snapshot(&pool, Some("public"), &snapshot)
+        .await
+        .is_err());
+
+    sqlx::query("ALTER TABLE test_diff.items ALTER COLUMN name SET NOT NULL")
+        .execute(&pool)
+        .await
+        .expect("Failed to alter column");
+    sqlx::query("ALTER TABLE test_diff.items ADD COLUMN price NUMERIC(10,2)")
+        .execute(&pool)
+        .await
+        .expect("Failed to add column");
+    sqlx::query("CREATE TABLE test_diff.tags (id SERIAL PRIMARY KEY)")
+        .execute(&pool)
+        .await
+        .expect("Failed to create table");
+
+    let diff = schema::diff::diff_against_snapshot(&pool, Some("test_diff"), &snapshot)
+        .await
+        .expect("Failed to diff");
+    assert_eq!(diff.added_tables.len(), 1);
+    assert_eq!(diff.added_tables[0].name, "tags");
+    assert_eq!(diff.changed_tables.len(), 1);
+
+    let items = &diff.changed_tables[0];
+    assert_eq!(items.name, "items");
+    assert_eq!(items.added_columns.len(), 1);
+    assert_eq!(items.added_columns[0].name, "price");
+    assert_eq!(items.changed_columns.len(), 1);
+    assert_eq!(items.changed_columns[0].name, "name");
+
+    let sql = diff.to_migration_sql();
+    assert!(sql.contains("ALTER TABLE test_diff.items ADD COLUMN price numeric(10,2);"));
+    assert!(sql.contains("ALTER TABLE test_diff.items ALTER COLUMN name SET NOT NULL;"));
+    assert!(sql.contains("CREATE TABLE test_diff.tags ("));
+
+    // 从空 schema 生成的迁移能重建 SERIAL 和 IDENTITY 列
+    sqlx::query("ALTER TABLE test_diff.tags ADD COLUMN code BIGINT GENERATED ALWAYS AS IDENTITY")
+        .execute(&pool)
+        .await
+        .expect("Failed to add identity column");
+    let tables = schema::get_all_table_schemas(&pool, Some("test_diff"))
+        .await
+        .expect("Failed to inspect schema");
+    let sql = schema::diff::diff_schemas(&[], &tables).to_migration_sql();
+    assert!(sql.contains("CREATE SEQUENCE IF NOT EXISTS test_diff.items_id_seq;"));
+    assert!(sql.contains("code bigint GENERATED ALWAYS AS IDENTITY NOT NULL"));
+    for statement in ["DROP SCHEMA test_diff CASCADE", "CREATE SCHEMA test_diff", &sql] {
+        sqlx::raw_sql(statement)
+            .execute(&pool)
+            .await
+            .expect("Failed to apply migration");
+    }
+    let recreated = schema::get_all_table_schemas(&pool, Some("test_diff"))
+        .await
+        .expect("Failed to inspect schema");
+    assert!(schema::diff::diff_schemas(&tables, &recreated).is_empty());
+    let code = recreated
+        .iter()
+        .find(|t| t.name == "tags")
+        .and_then(|t| t.columns.iter().find(|c| c.name == "code"))
+        .expect("code column missing");
+    assert_eq!(code.identity_generation, Some(IdentityGeneration::Always));
+
+    sqlx::query("DROP SCHEMA IF EXISTS test_diff CASCADE")
+        .execute(&pool)
+        .await
+        .ok();
+}
+
 #[tokio::test]
 async fn test_table_not_found() {
     let pool = get_test_pool().await;
@@ -269,6 +959,251 @@ async fn test_table_not_found() {
     assert!(result.is_ok() || result.is_err());
 }
 
+#[tokio::test]
+async fn test_views_and_materialized_views() {
+    let pool = get_test_pool().await;
+
+    create_test_table(&pool).await.expect("Failed to create test table");
+
+    sqlx::query("CREATE VIEW test_active_users AS SELECT id, username, email FROM test_users WHERE is_active")
+        .execute(&pool)
+        .await
+        .expect("Failed to create view");
+    sqlx::query("CREATE MATERIALIZED VIEW test_user_post_counts AS SELECT author_id, COUNT(*) AS post_count FROM test_posts GROUP BY author_id")
+        .execute(&pool)
+        .await
+        .expect("Failed to create materialized view");
+    sqlx::query("COMMENT ON COLUMN test_user_post_counts.post_count IS 'Number of posts'")
+        .execute(&pool)
+        .await
+        .expect("Failed to comment materialized view column");
+
+    // 视图和物化视图出现在表列表中
+    let relations = schema::get_relations(&pool, None)
+        .await
+        .expect("Failed to get relations");
+    let kind_of = |name: &str| relations.iter().find(|r| r.name == name).map(|r| r.kind);
+    assert_eq!(kind_of("test_users"), Some(RelationKind::Table));
+    assert_eq!(kind_of("test_active_users"), Some(RelationKind::View));
+    assert_eq!(kind_of("test_user_post_counts"), Some(RelationKind::MaterializedView));
+
+    // 简单视图可自动更新
+    let view = schema::get_table_schema(&pool, "test_active_users", None)
+        .await
+        .expect("Failed to get view schema");
+    assert_eq!(view.kind, RelationKind::View);
+    assert_eq!(view.columns.len(), 3);
+    let view_info = view.view.expect("View info missing");
+    assert!(view_info.definition.contains("test_users"));
+    assert!(view_info.is_updatable);
+    assert!(view_info.is_insertable_into);
+
+    // 物化视图的列信息也能读取
+    let matview = schema::get_table_schema(&pool, "test_user_post_counts", None)
+        .await
+        .expect("Failed to get materialized view schema");
+    assert_eq!(matview.kind, RelationKind::MaterializedView);
+    let post_count = matview.get_column("post_count").expect("post_count column not found");
+    assert_eq!(post_count.data_type, "bigint");
+    assert_eq!(post_count.comment, Some("Number of posts".to_string()));
+    assert!(!matview.view.expect("View info missing").is_updatable);
+
+    // 普通表没有视图信息
+    let table = schema::get_table_schema(&pool, "test_users", None)
+        .await
+        .expect("Failed to get table schema");
+    assert_eq!(table.kind, RelationKind::Table);
+    assert!(table.view.is_none());
+
+    cleanup_test_tables(&pool).await.expect("Failed to cleanup");
+}
+
+#[tokio::test]
+async fn test_user_defined_types() {
+    let pool = get_test_pool().await;
+
+    sqlx::query("DROP TABLE IF EXISTS test_orders").execute(&pool).await.ok();
+    sqlx::query("DROP TYPE IF EXISTS test_order_status").execute(&pool).await.ok();
+    sqlx::query("DROP DOMAIN IF EXISTS test_positive_price").execute(&pool).await.ok();
+    sqlx::query("DROP TYPE IF EXISTS test_address").execute(&pool).await.ok();
+
+    sqlx::query("CREATE TYPE test_order_status AS ENUM ('pending', 'paid', 'shipped')")
+        .execute(&pool)
+        .await
+        .expect("Failed to create enum");
+    sqlx::query("CREATE DOMAIN test_positive_price AS numeric(10,2) NOT NULL CHECK (VALUE > 0)")
+        .execute(&pool)
+        .await
+        .expect("Failed to create domain");
+    sqlx::query("CREATE TYPE test_address AS (street text, zip varchar(10))")
+        .execute(&pool)
+        .await
+        .expect("Failed to create composite type");
+    sqlx::query(
+        "CREATE TABLE test_orders (
+            id SERIAL PRIMARY KEY,
+            status test_order_status NOT NULL,
+            history test_order_status[],
+            price test_positive_price,
+            shipping test_address,
+            tags TEXT[][]
+        )",
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create orders table");
+
+    let schema = schema::get_table_schema(&pool, "test_orders", None)
+        .await
+        .expect("Failed to get table schema");
+
+    // 枚举列：按定义顺序返回枚举值
+    let status = schema.get_column("status").expect("status column not found");
+    assert_eq!(status.data_type, "USER-DEFINED");
+    assert_eq!(
+        status.enum_values(),
+        Some(["pending".to_string(), "paid".to_string(), "shipped".to_string()].as_slice())
+    );
+
+    // 枚举数组列：解析元素类型
+    let history = schema.get_column("history").expect("history column not found");
+    assert!(history.is_array());
+    assert_eq!(history.element_type.as_deref(), Some("test_order_status"));
+    assert_eq!(history.array_dimensions, Some(1));
+    assert_eq!(history.enum_values().map(|v| v.len()), Some(3));
+
+    // 域列：基础类型和 CHECK 约束
+    let price = schema.get_column("price").expect("price column not found");
+    let domain = price.user_type.as_ref().expect("domain info missing");
+    assert_eq!(domain.kind, UserTypeKind::Domain);
+    assert_eq!(domain.base_type.as_deref(), Some("numeric(10,2)"));
+    assert!(domain.not_null);
+    assert_eq!(domain.checks.len(), 1);
+    assert!(domain.checks[0].expression.contains("VALUE > "));
+
+    // 复合类型列：字段列表
+    let shipping = schema.get_column("shipping").expect("shipping column not found");
+    let composite = shipping.user_type.as_ref().expect("composite info missing");
+    assert_eq!(composite.kind, UserTypeKind::Composite);
+    let field_names: Vec<&str> = composite.fields.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(field_names, vec!["street", "zip"]);
+    assert_eq!(composite.fields[1].data_type, "character varying(10)");
+
+    // 多维数组
+    let tags = schema.get_column("tags").expect("tags column not found");
+    assert_eq!(tags.element_type.as_deref(), Some("text"));
+    assert_eq!(tags.array_dimensions, Some(2));
+    assert!(tags.user_type.is_none());
+
+    // schema 级别的类型列表不包含表的行类型
+    let types = schema::inspector::get_user_types(&pool, None)
+        .await
+        .expect("Failed to get user types");
+    assert!(types.iter().any(|t| t.name == "test_order_status" && t.kind == UserTypeKind::Enum));
+    assert!(types.iter().any(|t| t.name == "test_positive_price" && t.kind == UserTypeKind::Domain));
+    assert!(types.iter().any(|t| t.name == "test_address" && t.kind == UserTypeKind::Composite));
+    assert!(!types.iter().any(|t| t.name == "test_orders"));
+
+    sqlx::query("DROP TABLE IF EXISTS test_orders").execute(&pool).await.ok();
+    sqlx::query("DROP TYPE IF EXISTS test_order_status").execute(&pool).await.ok();
+    sqlx::query("DROP DOMAIN IF EXISTS test_positive_price").execute(&pool).await.ok();
+    sqlx::query("DROP TYPE IF EXISTS test_address").execute(&pool).await.ok();
+}
+
+#[tokio::test]
+async fn test_functions_and_procedures() {
+    let pool = get_test_pool().await;
+
+    sqlx::query("DROP FUNCTION IF EXISTS test_add_numbers(integer, integer)").execute(&pool).await.ok();
+    sqlx::query("DROP FUNCTION IF EXISTS test_series_table(integer)").execute(&pool).await.ok();
+    sqlx::query("DROP PROCEDURE IF EXISTS test_noop_procedure(text)").execute(&pool).await.ok();
+
+    sqlx::query(
+        "CREATE FUNCTION test_add_numbers(a integer, b integer DEFAULT 10)
+         RETURNS integer LANGUAGE sql IMMUTABLE AS 'SELECT a + b'",
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create function");
+    sqlx::query("COMMENT ON FUNCTION test_add_numbers(integer, integer) IS 'Adds two numbers'")
+        .execute(&pool)
+        .await
+        .expect("Failed to comment function");
+    sqlx::query(
+        "CREATE FUNCTION test_series_table(max_value integer)
+         RETURNS TABLE(n integer, squared integer)
+         LANGUAGE plpgsql STABLE SECURITY DEFINER AS $$
+         BEGIN
+             RETURN QUERY SELECT g, g * g FROM generate_series(1, max_value) AS g;
+         END
+         $$",
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create table function");
+    sqlx::query("CREATE PROCEDURE test_noop_procedure(INOUT message text) LANGUAGE plpgsql AS $$ BEGIN END $$")
+        .execute(&pool)
+        .await
+        .expect("Failed to create procedure");
+
+    let functions = schema::inspector::get_functions(&pool, None)
+        .await
+        .expect("Failed to get functions");
+
+    // 普通函数：参数、默认值、返回类型
+    let add = functions
+        .iter()
+        .find(|f| f.name == "test_add_numbers")
+        .expect("test_add_numbers not found");
+    assert_eq!(add.kind, FunctionKind::Function);
+    assert_eq!(add.arguments.len(), 2);
+    assert_eq!(add.arguments[0].name.as_deref(), Some("a"));
+    assert_eq!(add.arguments[0].mode, ArgumentMode::In);
+    assert!(add.arguments[0].default_value.is_none());
+    assert_eq!(add.arguments[1].default_value.as_deref(), Some("10"));
+    assert_eq!(add.return_type.as_deref(), Some("integer"));
+    assert!(!add.returns_set);
+    assert_eq!(add.volatility, Volatility::Immutable);
+    assert_eq!(add.language, "sql");
+    assert_eq!(add.comment, Some("Adds two numbers".to_string()));
+
+    // RETURNS TABLE 函数
+    let series = functions
+        .iter()
+        .find(|f| f.name == "test_series_table")
+        .expect("test_series_table not found");
+    assert!(series.returns_set);
+    assert!(series.security_definer);
+    assert_eq!(series.volatility, Volatility::Stable);
+    assert_eq!(series.input_arguments().len(), 1);
+    let outputs: Vec<_> = series.output_columns().iter().filter_map(|a| a.name.clone()).collect();
+    assert_eq!(outputs, vec!["n".to_string(), "squared".to_string()]);
+    assert!(series.return_type.as_deref().is_some_and(|r| r.starts_with("TABLE(")));
+
+    // 存储过程
+    let procedure = schema::inspector::get_function(&pool, "test_noop_procedure", None)
+        .await
+        .expect("Failed to get procedure");
+    assert_eq!(procedure.len(), 1);
+    assert_eq!(procedure[0].kind, FunctionKind::Procedure);
+    assert_eq!(procedure[0].arguments[0].mode, ArgumentMode::InOut);
+
+    // 缓存
+    let cache = SchemaCache::with_defaults(pool.clone());
+    let cached = cache
+        .get_function("test_add_numbers", None)
+        .await
+        .expect("Failed to get cached function");
+    assert_eq!(cached.len(), 1);
+    assert_eq!(cache.stats().await.function_entries, 1);
+    cache.invalidate_functions(None).await;
+    assert_eq!(cache.stats().await.function_entries, 0);
+
+    sqlx::query("DROP FUNCTION IF EXISTS test_add_numbers(integer, integer)").execute(&pool).await.ok();
+    sqlx::query("DROP FUNCTION IF EXISTS test_series_table(integer)").execute(&pool).await.ok();
+    sqlx::query("DROP PROCEDURE IF EXISTS test_noop_procedure(text)").execute(&pool).await.ok();
+}
+
 // ============================================================================
 // Cache 测试
 // ============================================================================
@@ -415,6 +1350,684 @@ async fn test_cache_refresh() {
     cleanup_test_tables(&pool).await.expect("Failed to cleanup");
 }
 
+#[tokio::test]
+async fn test_cache_openapi_document() {
+    let pool = get_test_pool().await;
+
+    create_test_table(&pool).await.expect("Failed to create test table");
+
+    let cache = SchemaCache::with_defaults(pool.clone());
+
+    let document = cache.openapi_document(None)
+        .await
+        .expect("Failed to build OpenAPI document");
+
+    assert_eq!(document["openapi"], "3.1.0");
+    assert!(document["paths"]["/rest/v1/test_users"]["get"].is_object());
+    assert!(document["paths"]["/rest/v1/test_posts"]["post"].is_object());
+    assert!(document["paths"]["/schema/openapi.json"]["get"].is_object());
+    assert_eq!(
+        document["components"]["schemas"]["test_users.insert"]["required"],
+        serde_json::json!(["username", "email"])
+    );
+    assert!(document["paths"]["/rest/v1/test_users"]["get"]["parameters"]
+        .as_array()
+        .is_some_and(|p| p.iter().any(|p| p["name"] == "bio")));
+
+    // 表结构变化后刷新缓存，文档随之重新生成
+    sqlx::query("ALTER TABLE test_users ADD COLUMN nickname TEXT")
+        .execute(&pool)
+        .await
+        .expect("Failed to alter table");
+    cache.refresh("test_users", None)
+        .await
+        .expect("Failed to refresh cache");
+
+    let document = cache.openapi_document(None)
+        .await
+        .expect("Failed to rebuild OpenAPI document");
+    assert!(document["components"]["schemas"]["test_users"]["properties"]["nickname"].is_object());
+
+    cleanup_test_tables(&pool).await.expect("Failed to cleanup");
+}
+
+#[tokio::test]
+async fn test_cache_offline_snapshot() {
+    use orpheus::schema::cache::CacheConfig;
+    use orpheus::schema::snapshot::{self, SchemaSnapshot};
+
+    let pool = get_test_pool().await;
+
+    create_test_table(&pool).await.expect("Failed to create test table");
+
+    let captured = snapshot::capture(&pool, &["public".to_string()])
+        .await
+        .expect("Failed to capture snapshot");
+    let json = captured.to_json().expect("Failed to serialize snapshot");
+    let loaded = SchemaSnapshot::from_json(&json).expect("Failed to load snapshot");
+    assert_eq!(loaded.version, captured.version);
+    assert!(loaded.table("public", "test_users").is_some());
+
+    cleanup_test_tables(&pool).await.expect("Failed to cleanup");
+
+    // 离线模式：没有连接池，所有结构都来自快照
+    let cache = SchemaCache::offline(CacheConfig::default());
+    cache.load_snapshot(loaded).await;
+
+    let users = cache.get_table_schema("test_users", None)
+        .await
+        .expect("Failed to read table from snapshot");
+    assert!(users.has_column("email"));
+
+    // 失效后从快照重新读取
+    cache.invalidate("test_users", None).await;
+    assert!(cache.get_table_schema("test_users", None).await.is_ok());
+    assert!(cache.get_table_schema("missing_table", None).await.is_err());
+
+    let document = cache.openapi_document(None)
+        .await
+        .expect("Failed to build OpenAPI document offline");
+    assert!(document["paths"]["/rest/v1/test_posts"]["get"].is_object());
+
+    let exported = cache.snapshot(&["public".to_string()])
+        .await
+        .expect("Failed to export snapshot offline");
+    assert_eq!(exported.version, captured.version);
+
+    let stats = cache.stats().await;
+    assert!(stats.offline);
+    assert_eq!(stats.snapshot_version, Some(captured.version));
+}
+
+#[tokio::test]
+async fn test_partitioned_tables() {
+    use orpheus::schema::types::PartitionStrategy;
+
+    let pool = get_test_pool().await;
+
+    sqlx::query("DROP TABLE IF EXISTS test_events CASCADE").execute(&pool).await.ok();
+    sqlx::query("DROP TABLE IF EXISTS test_event_log CASCADE").execute(&pool).await.ok();
+
+    for sql in [
+        "CREATE TABLE test_events (
+            id BIGINT NOT NULL,
+            created_at DATE NOT NULL,
+            payload JSONB,
+            PRIMARY KEY (id, created_at)
+        ) PARTITION BY RANGE (created_at)",
+        "CREATE TABLE test_events_2024_01 PARTITION OF test_events
+            FOR VALUES FROM ('2024-01-01') TO ('2024-02-01')",
+        "CREATE TABLE test_events_2024_02 PARTITION OF test_events
+            FOR VALUES FROM ('2024-02-01') TO ('2024-03-01')",
+        "CREATE TABLE test_events_default PARTITION OF test_events DEFAULT",
+        "CREATE TABLE test_event_log (id BIGINT NOT NULL, message TEXT)",
+        "CREATE TABLE test_event_log_archive (archived_at DATE) INHERITS (test_event_log)",
+    ] {
+        sqlx::query(sql).execute(&pool).await.expect("Failed to create partitioned table");
+    }
+
+    // 分区不出现在表列表中
+    let tables = schema::get_all_tables(&pool, None).await.expect("Failed to get tables");
+    assert!(tables.contains(&"test_events".to_string()));
+    assert!(tables.contains(&"test_event_log_archive".to_string()));
+    assert!(!tables.iter().any(|t| t.starts_with("test_events_20")));
+
+    let relations = schema::get_relations(&pool, None).await.expect("Failed to get relations");
+    let events = relations
+        .iter()
+        .find(|r| r.name == "test_events")
+        .expect("test_events missing");
+    assert_eq!(events.partitions.len(), 3);
+
+    let all = schema::get_all_table_schemas(&pool, None)
+        .await
+        .expect("Failed to get table schemas");
+    assert!(!all.iter().any(|t| t.is_partition()));
+
+    let parent = all
+        .iter()
+        .find(|t| t.name == "test_events")
+        .expect("test_events missing");
+    let partitioning = parent.partitioning.as_ref().expect("Partitioning missing");
+    assert_eq!(partitioning.strategy, PartitionStrategy::Range);
+    assert_eq!(partitioning.key, "RANGE (created_at)");
+    assert_eq!(partitioning.columns, vec!["created_at"]);
+    let names: Vec<&str> = partitioning.partitions.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["test_events_2024_01", "test_events_2024_02", "test_events_default"]);
+    assert_eq!(
+        partitioning.partitions.first().map(|p| p.bound.as_str()),
+        Some("FOR VALUES FROM ('2024-01-01') TO ('2024-02-01')")
+    );
+    assert!(partitioning.partitions.last().is_some_and(|p| p.is_default));
+
+    // 分区仍可按名称单独查询
+    let partition = schema::get_table_schema(&pool, "test_events_2024_01", None)
+        .await
+        .expect("Failed to get partition");
+    assert!(partition.is_partition());
+    assert_eq!(partition.partition_parent().map(|p| p.name.as_str()), Some("test_events"));
+    assert!(partition.partitioning.is_none());
+
+    // INHERITS 继承的父表
+    let archive = all
+        .iter()
+        .find(|t| t.name == "test_event_log_archive")
+        .expect("test_event_log_archive missing");
+    assert!(!archive.is_partition());
+    assert_eq!(archive.parents.len(), 1);
+    assert_eq!(archive.parents.first().map(|p| p.name.as_str()), Some("test_event_log"));
+
+    sqlx::query("DROP TABLE test_events CASCADE").execute(&pool).await.ok();
+    sqlx::query("DROP TABLE test_event_log CASCADE").execute(&pool).await.ok();
+}
+
+#[tokio::test]
+async fn test_sequences_and_extensions() {
+    let pool = get_test_pool().await;
+
+    sqlx::query("DROP TABLE IF EXISTS test_counters").execute(&pool).await.ok();
+    sqlx::query("DROP SEQUENCE IF EXISTS test_ticket_seq").execute(&pool).await.ok();
+
+    sqlx::query(
+        "CREATE TABLE test_counters (
+            id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+            legacy_id SERIAL
+        )",
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create table");
+    sqlx::query("CREATE SEQUENCE test_ticket_seq START 100 INCREMENT 5 CYCLE")
+        .execute(&pool)
+        .await
+        .expect("Failed to create sequence");
+    sqlx::query("SELECT nextval('test_ticket_seq'), nextval('test_ticket_seq')")
+        .execute(&pool)
+        .await
+        .expect("Failed to advance sequence");
+
+    let sequences = schema::inspector::get_sequences(&pool, None)
+        .await
+        .expect("Failed to get sequences");
+    let find = |name: &str| {
+        sequences
+            .iter()
+            .find(|s| s.name == name)
+            .unwrap_or_else(|| panic!("sequence {} missing", name))
+    };
+
+    let ticket = find("test_ticket_seq");
+    assert_eq!(ticket.data_type, "bigint");
+    assert_eq!(ticket.start_value, 100);
+    assert_eq!(ticket.increment, 5);
+    assert!(ticket.cycle);
+    assert_eq!(ticket.last_value, Some(105));
+    assert!(ticket.owned_by.is_none());
+
+    let legacy = find("test_counters_legacy_id_seq");
+    assert_eq!(legacy.data_type, "integer");
+    assert_eq!(legacy.last_value, None);
+    let owner = legacy.owned_by.as_ref().expect("Owner missing");
+    assert_eq!((owner.table.as_str(), owner.column.as_str()), ("test_counters", "legacy_id"));
+    assert!(!owner.is_identity);
+
+    let identity = find("test_counters_id_seq");
+    assert!(identity.owned_by.as_ref().is_some_and(|o| o.is_identity && o.column == "id"));
+
+    // plpgsql 默认安装
+    let extensions = schema::inspector::get_extensions(&pool)
+        .await
+        .expect("Failed to get extensions");
+    let plpgsql = extensions
+        .iter()
+        .find(|e| e.name == "plpgsql")
+        .expect("plpgsql missing");
+    assert_eq!(plpgsql.schema, "pg_catalog");
+    assert!(!plpgsql.version.is_empty());
+
+    sqlx::query("DROP TABLE test_counters").execute(&pool).await.ok();
+    sqlx::query("DROP SEQUENCE test_ticket_seq").execute(&pool).await.ok();
+}
+
+#[tokio::test]
+async fn test_cache_metrics() {
+    use orpheus::schema::cache::CacheConfig;
+    use std::time::Duration;
+
+    let pool = get_test_pool().await;
+    create_test_table(&pool).await.expect("Failed to create test table");
+
+    let cache = SchemaCache::new(
+        pool.clone(),
+        CacheConfig {
+            ttl: Duration::from_millis(200),
+            max_entries: Some(1),
+            ..CacheConfig::default()
+        },
+    );
+
+    cache.get_table_schema("test_users", None).await.expect("Failed to get table");
+    cache.get_table_schema("test_users", None).await.expect("Failed to get table");
+    assert!(cache.get_table_schema("missing_table", None).await.is_err());
+
+    let stats = cache.stats().await;
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.misses, 2);
+    assert_eq!(stats.loads, 2);
+    assert_eq!(stats.load_failures, 1);
+    assert!(stats.average_load_ms > 0.0);
+    assert!((stats.hit_rate - 1.0 / 3.0).abs() < 1e-9);
+
+    // 超出条目上限时淘汰最久未使用的表
+    cache.get_table_schema("test_posts", None).await.expect("Failed to get table");
+    assert!(cache.is_cached("test_posts", None).await);
+    assert!(!cache.is_cached("test_users", None).await);
+    let stats = cache.stats().await;
+    assert_eq!(stats.total_entries, 1);
+    assert_eq!(stats.evictions, 1);
+    assert_eq!(stats.max_entries, Some(1));
+
+    // 定期清理移除过期条目
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(cache.sweep().await, 1);
+    let stats = cache.stats().await;
+    assert_eq!(stats.total_entries, 0);
+    assert_eq!(stats.expirations, 1);
+
+    cleanup_test_tables(&pool).await.expect("Failed to cleanup");
+}
+
+#[tokio::test]
+async fn test_cache_stale_while_revalidate() {
+    use orpheus::schema::cache::CacheConfig;
+    use std::time::Duration;
+
+    let pool = get_test_pool().await;
+    create_test_table(&pool).await.expect("Failed to create test table");
+
+    let cache = SchemaCache::new(
+        pool.clone(),
+        CacheConfig {
+            ttl: Duration::from_millis(200),
+            stale_while_revalidate: Some(Duration::from_secs(60)),
+            ..CacheConfig::default()
+        },
+    );
+
+    // 并发的首次请求只加载一次，结果一致
+    let results = futures_util::future::join_all(
+        (0..8).map(|_| cache.get_table_schema("test_users", None)),
+    )
+    .await;
+    assert!(results.iter().all(|r| r.as_ref().is_ok_and(|t| t.name == "test_users")));
+
+    sqlx::query("ALTER TABLE test_users ADD COLUMN nickname TEXT")
+        .execute(&pool)
+        .await
+        .expect("Failed to alter table");
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    // 过期后立即返回旧值，同时在后台刷新
+    let stale = cache.get_table_schema("test_users", None).await.expect("Failed to get table");
+    assert!(!stale.has_column("nickname"));
+
+    let mut refreshed = false;
+    for _ in 0..50 {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let users = cache.get_table_schema("test_users", None).await.expect("Failed to get table");
+        if users.has_column("nickname") {
+            refreshed = true;
+            break;
+        }
+    }
+    assert!(refreshed);
+
+    // 缓存中没有的表仍然等待加载，错误照常返回
+    assert!(cache.get_table_schema("missing_table", None).await.is_err());
+
+    cleanup_test_tables(&pool).await.expect("Failed to cleanup");
+}
+
+#[tokio::test]
+#[ignore] // 需要 Redis（REDIS_URL）
+async fn test_shared_cache() {
+    use orpheus::schema::shared::{self, SharedCache};
+    use std::time::Duration;
+
+    let pool = get_test_pool().await;
+    let redis_url =
+        std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1/".to_string());
+    let client = redis::Client::open(redis_url).expect("Invalid REDIS_URL");
+    let namespace = format!("orpheus-test:{}", uuid::Uuid::new_v4());
+
+    create_test_table(&pool).await.expect("Failed to create test table");
+
+    // 两个实例共享同一个 Redis
+    let shared_a = SharedCache::new(client.clone(), &namespace);
+    let shared_b = SharedCache::new(client.clone(), &namespace);
+    shared_a.spawn_connector().await.expect("Failed to connect to Redis");
+    shared_b.spawn_connector().await.expect("Failed to connect to Redis");
+    let cache_a = SchemaCache::with_defaults(pool.clone()).with_shared(shared_a.clone());
+    let cache_b = SchemaCache::with_defaults(pool.clone()).with_shared(shared_b.clone());
+    let subscriber_a = shared::spawn_invalidation_subscriber(shared_a.clone(), cache_a.clone());
+    let subscriber_b = shared::spawn_invalidation_subscriber(shared_b.clone(), cache_b.clone());
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // 实例 A 从数据库读取后写入 Redis，实例 B 从 Redis 读取
+    cache_a.get_table_schema("test_users", None).await.expect("Failed to load test_users");
+    assert!(shared_b.get_table("public", "test_users").await.is_some());
+    let users = cache_b.get_table_schema("test_users", None).await.expect("Failed to load test_users");
+    assert!(!users.has_column("nickname"));
+
+    // 实例 A 刷新后，实例 B 丢弃内存中的旧副本并读到新结构
+    sqlx::query("ALTER TABLE test_users ADD COLUMN nickname TEXT")
+        .execute(&pool)
+        .await
+        .expect("Failed to alter table");
+    cache_a.refresh("test_users", None).await.expect("Failed to refresh test_users");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(!cache_b.is_cached("test_users", None).await);
+    let users = cache_b.get_table_schema("test_users", None).await.expect("Failed to load test_users");
+    assert!(users.has_column("nickname"));
+
+    // 清空同时作用于 Redis 和其他实例
+    cache_b.preload(None).await.expect("Failed to preload");
+    assert!(shared_a.get_functions("public").await.is_some());
+    cache_a.get_table_schema("test_posts", None).await.expect("Failed to load test_posts");
+    cache_b.clear().await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(!cache_a.is_cached("test_posts", None).await);
+    assert!(shared_a.get_table("public", "test_posts").await.is_none());
+    assert!(shared_a.get_functions("public").await.is_none());
+
+    subscriber_a.abort();
+    subscriber_b.abort();
+    cleanup_test_tables(&pool).await.expect("Failed to cleanup");
+}
+
+#[tokio::test]
+#[ignore] // 需要 Redis（REDIS_URL）
+async fn test_shared_cache_ddl_eviction() {
+    use orpheus::schema::listener::{self, DdlEvent};
+    use orpheus::schema::shared::SharedCache;
+
+    let pool = get_test_pool().await;
+    let redis_url =
+        std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1/".to_string());
+    let client = redis::Client::open(redis_url).expect("Invalid REDIS_URL");
+    let namespace = format!("orpheus-test:{}", uuid::Uuid::new_v4());
+
+    create_test_table(&pool).await.expect("Failed to create test table");
+
+    let shared_a = SharedCache::new(client.clone(), &namespace);
+    let shared_b = SharedCache::new(client.clone(), &namespace);
+    shared_a.spawn_connector().await.expect("Failed to connect to Redis");
+    shared_b.spawn_connector().await.expect("Failed to connect to Redis");
+    let cache_a = SchemaCache::with_defaults(pool.clone()).with_shared(shared_a.clone());
+    let cache_b = SchemaCache::with_defaults(pool.clone()).with_shared(shared_b.clone());
+
+    // 实例 A 把 test_users 写入 Redis
+    cache_a.get_table_schema("test_users", None).await.expect("Failed to load test_users");
+    assert!(shared_b.get_table("public", "test_users").await.is_some());
+
+    // 影响整个 schema 的 DDL（例如 CREATE TYPE），每个实例都收到同一个通知
+    sqlx::query("ALTER TABLE test_users ADD COLUMN nickname TEXT")
+        .execute(&pool)
+        .await
+        .expect("Failed to alter table");
+    let event = DdlEvent {
+        tag: "CREATE TYPE".to_string(),
+        object_type: "type".to_string(),
+        schema: Some("public".to_string()),
+        table: None,
+    };
+    listener::apply_event(&pool, &cache_a, &event).await;
+    listener::apply_event(&pool, &cache_b, &event).await;
+
+    // Redis 中的旧条目已删除，实例 B 从数据库读到新结构
+    assert!(shared_b.get_table("public", "test_users").await.is_none());
+    let users = cache_b.get_table_schema("test_users", None).await.expect("Failed to load test_users");
+    assert!(users.has_column("nickname"));
+
+    cleanup_test_tables(&pool).await.expect("Failed to cleanup");
+}
+
+#[tokio::test]
+async fn test_ddl_listener() {
+    use orpheus::schema::listener;
+    use std::time::Duration;
+
+    // 等待监听任务处理通知
+    async fn wait_until<F, Fut>(mut condition: F) -> bool
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = bool>,
+    {
+        for _ in 0..50 {
+            if condition().await {
+                return true;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        false
+    }
+
+    let pool = get_test_pool().await;
+    let cleanup = [
+        "DROP TABLE IF EXISTS test_ddl_renamed, test_ddl_renamed_new, test_ddl_moved, test_ddl_moods",
+        "DROP SCHEMA IF EXISTS test_ddl_other CASCADE",
+    ];
+    for statement in cleanup {
+        sqlx::query(statement).execute(&pool).await.ok();
+    }
+
+    create_test_table(&pool).await.expect("Failed to create test table");
+    for statement in [
+        "CREATE SCHEMA test_ddl_other",
+        "CREATE TYPE test_ddl_other.mood AS ENUM ('happy')",
+        "CREATE TABLE test_ddl_renamed (id INT)",
+        "CREATE TABLE test_ddl_moved (id INT)",
+        "CREATE TABLE test_ddl_moods (id INT, mood test_ddl_other.mood)",
+    ] {
+        sqlx::query(statement)
+            .execute(&pool)
+            .await
+            .expect("Failed to set up tables");
+    }
+    listener::install_ddl_trigger(&pool).await.expect("Failed to install event trigger");
+    assert!(listener::ddl_trigger_installed(&pool).await.expect("Failed to check trigger"));
+
+    let cache = SchemaCache::with_defaults(pool.clone());
+    for table in ["test_users", "test_posts", "test_ddl_renamed", "test_ddl_moved", "test_ddl_moods"] {
+        cache.get_table_schema(table, None).await.expect("Failed to cache table");
+    }
+
+    let handle = listener::spawn_ddl_listener(pool.clone(), cache.clone());
+    // 等待监听连接建立
+    assert!(
+        wait_until(|| async {
+            sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS (SELECT 1 FROM pg_stat_activity WHERE query LIKE 'LISTEN%')",
+            )
+            .fetch_one(&pool)
+            .await
+            .unwrap_or(false)
+        })
+        .await
+    );
+
+    // 修改列后缓存中的表被刷新，其他表不受影响
+    sqlx::query("ALTER TABLE test_users ADD COLUMN nickname TEXT")
+        .execute(&pool)
+        .await
+        .expect("Failed to alter table");
+    assert!(
+        wait_until(|| async {
+            cache
+                .get_table_schema("test_users", None)
+                .await
+                .is_ok_and(|t| t.has_column("nickname"))
+        })
+        .await
+    );
+    assert!(cache.is_cached("test_posts", None).await);
+
+    // 索引归到所属的表
+    sqlx::query("CREATE INDEX test_posts_title_idx ON test_posts (title)")
+        .execute(&pool)
+        .await
+        .expect("Failed to create index");
+    assert!(
+        wait_until(|| async {
+            cache
+                .get_table_schema("test_posts", None)
+                .await
+                .is_ok_and(|t| t.indexes.iter().any(|i| i.name == "test_posts_title_idx"))
+        })
+        .await
+    );
+
+    // 重命名或移动到其他 schema 后，旧名称下的缓存条目失效
+    sqlx::query("ALTER TABLE test_ddl_renamed RENAME TO test_ddl_renamed_new")
+        .execute(&pool)
+        .await
+        .expect("Failed to rename table");
+    assert!(wait_until(|| async { !cache.is_cached("test_ddl_renamed", None).await }).await);
+    sqlx::query("ALTER TABLE test_ddl_moved SET SCHEMA test_ddl_other")
+        .execute(&pool)
+        .await
+        .expect("Failed to move table");
+    assert!(wait_until(|| async { !cache.is_cached("test_ddl_moved", None).await }).await);
+
+    // 修改其他 schema 中的枚举类型后，引用它的表失效
+    assert!(cache.is_cached("test_ddl_moods", None).await);
+    sqlx::query("ALTER TYPE test_ddl_other.mood ADD VALUE 'sad'")
+        .execute(&pool)
+        .await
+        .expect("Failed to alter type");
+    assert!(wait_until(|| async { !cache.is_cached("test_ddl_moods", None).await }).await);
+
+    // 断开监听连接后自动重连
+    sqlx::query(
+        "SELECT pg_terminate_backend(pid) FROM pg_stat_activity WHERE query LIKE 'LISTEN%'",
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to terminate listener");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    cache.get_table_schema("test_posts", None).await.expect("Failed to cache test_posts");
+    assert!(
+        wait_until(|| async {
+            sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS (SELECT 1 FROM pg_stat_activity WHERE query LIKE 'LISTEN%')",
+            )
+            .fetch_one(&pool)
+            .await
+            .unwrap_or(false)
+        })
+        .await
+    );
+
+    // 删除表后缓存条目失效
+    cache.get_table_schema("test_posts", None).await.expect("Failed to cache test_posts");
+    sqlx::query("DROP TABLE test_posts")
+        .execute(&pool)
+        .await
+        .expect("Failed to drop table");
+    assert!(wait_until(|| async { !cache.is_cached("test_posts", None).await }).await);
+
+    handle.abort();
+    listener::uninstall_ddl_trigger(&pool).await.expect("Failed to uninstall event trigger");
+    assert!(!listener::ddl_trigger_installed(&pool).await.expect("Failed to check trigger"));
+
+    sqlx::query("DROP TABLE IF EXISTS test_ddl_renamed_new, test_ddl_moods")
+        .execute(&pool)
+        .await
+        .expect("Failed to cleanup");
+    sqlx::query("DROP SCHEMA IF EXISTS test_ddl_other CASCADE")
+        .execute(&pool)
+        .await
+        .expect("Failed to cleanup");
+    cleanup_test_tables(&pool).await.expect("Failed to cleanup");
+}
+
+#[tokio::test]
+async fn test_ddl_trigger_non_superuser() {
+    use orpheus::schema::listener::{self, DdlEvent};
+    use sqlx::postgres::PgListener;
+    use std::time::Duration;
+
+    let pool = get_test_pool().await;
+
+    let cleanup = [
+        "DROP SCHEMA IF EXISTS test_ddl_owned CASCADE",
+        "DROP SCHEMA IF EXISTS test_ddl_created CASCADE",
+        "DO $$ BEGIN
+             IF EXISTS (SELECT 1 FROM pg_roles WHERE rolname = 'test_ddl_migrator') THEN
+                 EXECUTE format('REVOKE CREATE ON DATABASE %I FROM test_ddl_migrator', current_database());
+             END IF;
+         END $$",
+        "DROP ROLE IF EXISTS test_ddl_migrator",
+    ];
+    for statement in cleanup {
+        sqlx::query(statement).execute(&pool).await.ok();
+    }
+    for statement in [
+        "CREATE ROLE test_ddl_migrator NOLOGIN",
+        "CREATE SCHEMA test_ddl_owned AUTHORIZATION test_ddl_migrator",
+        "DO $$ BEGIN
+             EXECUTE format('GRANT CREATE ON DATABASE %I TO test_ddl_migrator', current_database());
+         END $$",
+    ] {
+        sqlx::query(statement)
+            .execute(&pool)
+            .await
+            .expect("Failed to set up role");
+    }
+
+    listener::install_ddl_trigger(&pool).await.expect("Failed to install event trigger");
+    let mut notifications = PgListener::connect_with(&pool).await.expect("Failed to connect listener");
+    notifications.listen(listener::DDL_CHANNEL).await.expect("Failed to listen");
+
+    // 没有 orpheus schema 权限的角色执行 DDL 不受 event trigger 影响
+    let mut conn = pool.acquire().await.expect("Failed to acquire connection");
+    for statement in [
+        "SET ROLE test_ddl_migrator",
+        "CREATE TABLE test_ddl_owned.items (id INT)",
+        "DROP TABLE test_ddl_owned.items",
+        "CREATE SCHEMA test_ddl_created",
+        "RESET ROLE",
+    ] {
+        sqlx::query(statement)
+            .execute(&mut *conn)
+            .await
+            .unwrap_or_else(|e| panic!("'{}' failed: {}", statement, e));
+    }
+    drop(conn);
+
+    let mut events = Vec::new();
+    while let Ok(Ok(notification)) =
+        tokio::time::timeout(Duration::from_secs(2), notifications.recv()).await
+    {
+        events.push(DdlEvent::parse(notification.payload()).expect("Invalid DDL event"));
+    }
+    let has_event = |tag: &str, schema: &str, table: Option<&str>| {
+        events.iter().any(|event| {
+            event.tag == tag
+                && event.schema.as_deref() == Some(schema)
+                && event.table.as_deref() == table
+        })
+    };
+    assert!(has_event("CREATE TABLE", "test_ddl_owned", Some("items")));
+    assert!(has_event("DROP TABLE", "test_ddl_owned", Some("items")));
+    assert!(has_event("CREATE SCHEMA", "test_ddl_created", None));
+
+    listener::uninstall_ddl_trigger(&pool).await.expect("Failed to uninstall event trigger");
+    for statement in cleanup {
+        sqlx::query(statement).execute(&pool).await.ok();
+    }
+}
+
 // ============================================================================
 // 类型辅助方法测试
 // ============================================================================